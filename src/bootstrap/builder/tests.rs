@@ -572,6 +572,8 @@ fn test_with_no_doc_stage0() {
         doc_tests: DocTests::No,
         bless: false,
         compare_mode: None,
+        codegen_backend: None,
+        backend_expected_failures: None,
         rustfix_coverage: false,
         pass: None,
     };
@@ -614,6 +616,8 @@ fn test_exclude() {
         doc_tests: DocTests::No,
         bless: false,
         compare_mode: None,
+        codegen_backend: None,
+        backend_expected_failures: None,
         rustfix_coverage: false,
         pass: None,
     };
@@ -4,7 +4,7 @@
 //! has various flags to configure how it's run.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use getopts::Options;
@@ -61,6 +61,9 @@ pub enum Subcommand {
         /// Whether to automatically update stderr/stdout files
         bless: bool,
         compare_mode: Option<String>,
+        /// The codegen backend to compile the compiletest suites with
+        codegen_backend: Option<String>,
+        backend_expected_failures: Option<PathBuf>,
         pass: Option<String>,
         test_args: Vec<String>,
         rustc_args: Vec<String>,
@@ -203,6 +206,18 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`"
                     "mode describing what file the actual ui output will be compared to",
                     "COMPARE MODE",
                 );
+                opts.optopt(
+                    "",
+                    "codegen-backend",
+                    "the codegen backend to compile the compiletest suites with",
+                    "NAME | PATH",
+                );
+                opts.optopt(
+                    "",
+                    "backend-expected-failures",
+                    "file listing the tests that are known to fail with --codegen-backend",
+                    "PATH",
+                );
                 opts.optopt(
                     "",
                     "pass",
@@ -335,6 +350,7 @@ Arguments:
         ./x.py test src/libstd --stage 0 --no-doc
         ./x.py test src/test/ui --bless
         ./x.py test src/test/ui --compare-mode nll
+        ./x.py test src/test/ui --codegen-backend cranelift
 
     Note that `test src/test/* --stage N` does NOT depend on `build src/rustc --stage N`;
     just like `build src/libstd --stage N` it tests the compiler produced by the previous
@@ -411,6 +427,9 @@ Arguments:
                 paths,
                 bless: matches.opt_present("bless"),
                 compare_mode: matches.opt_str("compare-mode"),
+                codegen_backend: matches.opt_str("codegen-backend"),
+                backend_expected_failures: matches.opt_str("backend-expected-failures")
+                    .map(PathBuf::from),
                 pass: matches.opt_str("pass"),
                 test_args: matches.opt_strs("test-args"),
                 rustc_args: matches.opt_strs("rustc-args"),
@@ -536,6 +555,24 @@ impl Subcommand {
         }
     }
 
+    pub fn codegen_backend(&self) -> Option<&str> {
+        match *self {
+            Subcommand::Test {
+                ref codegen_backend, ..
+            } => codegen_backend.as_ref().map(|s| &s[..]),
+            _ => None,
+        }
+    }
+
+    pub fn backend_expected_failures(&self) -> Option<&Path> {
+        match *self {
+            Subcommand::Test {
+                ref backend_expected_failures, ..
+            } => backend_expected_failures.as_ref().map(|p| p.as_path()),
+            _ => None,
+        }
+    }
+
     pub fn pass(&self) -> Option<&str> {
         match *self {
             Subcommand::Test {
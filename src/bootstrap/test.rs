@@ -1346,6 +1346,13 @@ impl Step for Compiletest {
             cmd.arg("--rustfix-coverage");
         }
 
+        if let Some(backend) = builder.config.cmd.codegen_backend() {
+            cmd.arg("--codegen-backend").arg(backend);
+        }
+        if let Some(path) = builder.config.cmd.backend_expected_failures() {
+            cmd.arg("--backend-expected-failures").arg(path);
+        }
+
         builder.ci_env.force_coloring_in_ci(&mut cmd);
 
         builder.info(&format!(
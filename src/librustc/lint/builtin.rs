@@ -316,6 +316,18 @@ declare_lint! {
     "detects code samples in docs of private items not documented by rustdoc"
 }
 
+declare_lint! {
+    pub UNFULFILLED_LINT_EXPECTATIONS,
+    Warn,
+    "detects lint expectations of `#[expect]` attributes that no lint was emitted for"
+}
+
+declare_lint! {
+    pub UNTESTED_DOC_CODE_EXAMPLES,
+    Allow,
+    "detects Rust code samples in docs that are never tested because of `ignore` or their language"
+}
+
 declare_lint! {
     pub WHERE_CLAUSES_OBJECT_SAFETY,
     Warn,
@@ -455,6 +467,8 @@ declare_lint_pass! {
         INTRA_DOC_LINK_RESOLUTION_FAILURE,
         MISSING_DOC_CODE_EXAMPLES,
         PRIVATE_DOC_TESTS,
+        UNTESTED_DOC_CODE_EXAMPLES,
+        UNFULFILLED_LINT_EXPECTATIONS,
         WHERE_CLAUSES_OBJECT_SAFETY,
         PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
         MACRO_USE_EXTERN_CRATE,
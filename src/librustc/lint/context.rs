@@ -385,6 +385,7 @@ impl LintStore {
             let msg = format!("requested on the command line with `{} {}`",
                               match level {
                                   Level::Allow => "-A",
+                                  Level::Expect => bug!("`expect` set on the command line"),
                                   Level::Warn => "-W",
                                   Level::Deny => "-D",
                                   Level::Forbid => "-F",
@@ -689,6 +690,16 @@ impl<'a, T: EarlyLintPass> EarlyContextAndPass<'a, T> {
         self.context.builder.pop(push);
     }
 
+    /// Pushes the levels from the `--lint-config` file for the module whose
+    /// contents are at `inner`. Its attributes should be pushed in `f`.
+    fn with_path_overrides<F>(&mut self, inner: Span, f: F)
+        where F: FnOnce(&mut Self)
+    {
+        let push = self.context.builder.push_path_overrides(inner);
+        f(self);
+        self.context.builder.pop(push);
+    }
+
     fn enter_attrs(&mut self, attrs: &'a [ast::Attribute]) {
         debug!("early context: enter_attrs({:?})", attrs);
         run_early_pass!(self, enter_lint_attrs, attrs);
@@ -1171,11 +1182,15 @@ impl<'a, T: EarlyLintPass> ast_visit::Visitor<'a> for EarlyContextAndPass<'a, T>
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
-        self.with_lint_attrs(it.id, &it.attrs, |cx| {
+        let walk = |cx: &mut Self| cx.with_lint_attrs(it.id, &it.attrs, |cx| {
             run_early_pass!(cx, check_item, it);
             ast_visit::walk_item(cx, it);
             run_early_pass!(cx, check_item_post, it);
-        })
+        });
+        match it.kind {
+            ast::ItemKind::Mod(ref module) => self.with_path_overrides(module.inner, walk),
+            _ => walk(self),
+        }
     }
 
     fn visit_foreign_item(&mut self, it: &'a ast::ForeignItem) {
@@ -1563,14 +1578,16 @@ fn early_lint_crate<T: EarlyLintPass>(
     };
 
     // Visit the whole crate.
-    cx.with_lint_attrs(ast::CRATE_NODE_ID, &krate.attrs, |cx| {
-        // since the root module isn't visited as an item (because it isn't an
-        // item), warn for it here.
-        run_early_pass!(cx, check_crate, krate);
+    cx.with_path_overrides(krate.module.inner, |cx| {
+        cx.with_lint_attrs(ast::CRATE_NODE_ID, &krate.attrs, |cx| {
+            // since the root module isn't visited as an item (because it isn't an
+            // item), warn for it here.
+            run_early_pass!(cx, check_crate, krate);
 
-        ast_visit::walk_crate(cx, krate);
+            ast_visit::walk_crate(cx, krate);
 
-        run_early_pass!(cx, check_crate_post, krate);
+            run_early_pass!(cx, check_crate_post, krate);
+        });
     });
     cx.context.buffered
 }
@@ -1,9 +1,12 @@
 use std::cmp;
+use std::env;
+use std::iter;
+use std::path::PathBuf;
 
 use crate::hir::HirId;
 use crate::ich::StableHashingContext;
 use crate::lint::builtin;
-use crate::lint::context::CheckLintNameResult;
+use crate::lint::context::{CheckLintNameResult, LintStore};
 use crate::lint::{self, Lint, LintId, Level, LintSource};
 use crate::session::Session;
 use crate::util::nodemap::FxHashMap;
@@ -14,6 +17,7 @@ use syntax::attr;
 use syntax::feature_gate;
 use syntax::source_map::MultiSpan;
 use syntax::symbol::{Symbol, sym};
+use syntax_pos::{BytePos, FileName, Span};
 
 pub struct LintLevelSets {
     list: Vec<LintSet>,
@@ -23,7 +27,7 @@ pub struct LintLevelSets {
 enum LintSet {
     CommandLine {
         // -A,-W,-D flags, a `Symbol` for the flag itself and `Level` for which
-        // flag.
+        // flag, and the levels from the `--lint-config` file.
         specs: FxHashMap<LintId, (Level, LintSource)>,
     },
 
@@ -52,6 +56,12 @@ impl LintLevelSets {
         let mut specs = FxHashMap::default();
         self.lint_cap = sess.opts.lint_cap.unwrap_or(Level::Forbid);
 
+        // The levels from the config file come first so that command-line
+        // flags take precedence over them.
+        if let Some(ref lint_config) = sess.opts.lint_config {
+            specs = self.lint_config_specs(&store, &lint_config.lints);
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             store.check_lint_name_cmdline(sess, &lint_name, level);
 
@@ -76,6 +86,29 @@ impl LintLevelSets {
         });
     }
 
+    /// Resolves a table of lint and lint group levels from the `--lint-config`
+    /// file. Groups are applied first, so that the level of a lint wins over
+    /// the levels of its groups.
+    fn lint_config_specs(&self, store: &LintStore, lints: &[(String, Level)])
+        -> FxHashMap<LintId, (Level, LintSource)>
+    {
+        let mut resolved = lints.iter().filter_map(|&(ref lint_name, level)| {
+            // Unknown names are reported by `LintLevelsBuilder::check_lint_config_names`.
+            store.find_lints(lint_name).ok().map(|ids| (lint_name, level, ids))
+        }).collect::<Vec<_>>();
+        resolved.sort_by_key(|&(_, _, ref ids)| ids.len() == 1);
+
+        let mut specs = FxHashMap::default();
+        for (lint_name, level, ids) in resolved {
+            let level = cmp::min(level, self.lint_cap);
+            let src = LintSource::ConfigFile(Symbol::intern(lint_name));
+            for id in ids {
+                specs.insert(id, (level, src));
+            }
+        }
+        specs
+    }
+
     fn get_lint_level(&self,
                       lint: &'static Lint,
                       idx: u32,
@@ -152,22 +185,90 @@ pub struct LintLevelsBuilder<'a> {
     id_to_set: FxHashMap<HirId, u32>,
     cur: u32,
     warn_about_weird_lints: bool,
+    /// The overrides of the `--lint-config` file, as absolute paths and the
+    /// levels they set.
+    path_overrides: Vec<(Vec<PathBuf>, FxHashMap<LintId, (Level, LintSource)>)>,
+    /// The start of the source file of the innermost module whose path
+    /// overrides were pushed.
+    cur_file: Option<BytePos>,
 }
 
 pub struct BuilderPush {
     prev: u32,
+    prev_file: Option<BytePos>,
     pub(super) changed: bool,
 }
 
 impl<'a> LintLevelsBuilder<'a> {
     pub fn new(sess: &'a Session, sets: LintLevelSets) -> LintLevelsBuilder<'a> {
         assert_eq!(sets.list.len(), 1);
+        let path_overrides = match sess.opts.lint_config {
+            Some(ref lint_config) => {
+                let store = sess.lint_store.borrow();
+                lint_config.overrides.iter().map(|lint_override| {
+                    let specs = sets.lint_config_specs(&store, &lint_override.lints);
+                    (lint_override.paths.clone(), specs)
+                }).collect()
+            }
+            None => Vec::new(),
+        };
         LintLevelsBuilder {
             sess,
             sets,
             cur: 0,
             id_to_set: Default::default(),
             warn_about_weird_lints: sess.buffered_lints.borrow().is_some(),
+            path_overrides,
+            cur_file: None,
+        }
+    }
+
+    /// Pushes the levels that the overrides of the `--lint-config` file set
+    /// for a module, given the span of its contents.
+    ///
+    /// Levels are only pushed for the first module in each source file, so
+    /// that they don't override the attributes of the modules around inline
+    /// modules. The attributes of the module itself should be pushed after
+    /// this and take precedence, and lints set to `forbid` outside of the
+    /// module stay forbidden.
+    ///
+    /// Don't forget to call `pop`!
+    pub fn push_path_overrides(&mut self, inner: Span) -> BuilderPush {
+        let prev = self.cur;
+        let prev_file = self.cur_file;
+        if !self.path_overrides.is_empty() && !inner.is_dummy() {
+            let file = self.sess.source_map().lookup_char_pos(inner.lo()).file;
+            if self.cur_file != Some(file.start_pos) {
+                self.cur_file = Some(file.start_pos);
+                let file_name = file.unmapped_path.as_ref().unwrap_or(&file.name);
+                if let FileName::Real(ref path) = *file_name {
+                    let path = env::current_dir().unwrap_or_default().join(path);
+                    let mut specs = FxHashMap::default();
+                    for &(ref paths, ref override_specs) in &self.path_overrides {
+                        if paths.iter().any(|p| path.starts_with(p)) {
+                            specs.extend(override_specs.iter().map(|(id, spec)| (*id, *spec)));
+                        }
+                    }
+                    let sets = &self.sets;
+                    specs.retain(|id, _| match sets.get_lint_id_level(*id, prev, None) {
+                        (Some(Level::Forbid), _) => false,
+                        _ => true,
+                    });
+                    if specs.len() > 0 {
+                        self.cur = self.sets.list.len() as u32;
+                        self.sets.list.push(LintSet::Node {
+                            specs: specs,
+                            parent: prev,
+                        });
+                    }
+                }
+            }
+        }
+
+        BuilderPush {
+            prev: prev,
+            prev_file: prev_file,
+            changed: prev != self.cur,
         }
     }
 
@@ -420,6 +521,7 @@ impl<'a> LintLevelsBuilder<'a> {
                 LintSource::Default => id.to_string(),
                 LintSource::Node(name, _, _) => name.to_string(),
                 LintSource::CommandLine(name) => name.to_string(),
+                LintSource::ConfigFile(name) => name.to_string(),
             };
             let (lint_attr_name, lint_attr_span) = match *src {
                 LintSource::Node(name, span, _) => (name, span),
@@ -445,6 +547,9 @@ impl<'a> LintLevelsBuilder<'a> {
                 LintSource::CommandLine(_) => {
                     diag_builder.note("`forbid` lint level was set on command line");
                 }
+                LintSource::ConfigFile(_) => {
+                    diag_builder.note("`forbid` lint level was set in the lint config file");
+                }
             }
             diag_builder.emit();
             // don't set a separate error for every lint in the group
@@ -462,13 +567,53 @@ impl<'a> LintLevelsBuilder<'a> {
 
         BuilderPush {
             prev: prev,
+            prev_file: self.cur_file,
             changed: prev != self.cur,
         }
     }
 
+    /// Reports the names in the `--lint-config` file that aren't lints or lint
+    /// groups. Unlike unknown lints on the command line these only warn, so
+    /// that a config file can be shared by crates built with different
+    /// toolchains.
+    ///
+    /// The levels are built more than once per crate, so this is only called
+    /// when building the `LintLevelMap`.
+    pub fn check_lint_config_names(&self) {
+        let lint_config = match self.sess.opts.lint_config {
+            Some(ref lint_config) => lint_config,
+            None => return,
+        };
+        let store = self.sess.lint_store.borrow();
+        let tables = iter::once(&lint_config.lints)
+            .chain(lint_config.overrides.iter().map(|o| &o.lints));
+        for &(ref lint_name, _) in tables.flat_map(|lints| lints) {
+            let (lint, msg, help) = match store.check_lint_name(lint_name, None) {
+                CheckLintNameResult::Warning(msg, renamed) => {
+                    let help = renamed.map(|new_name| format!("use the new name `{}`", new_name));
+                    (builtin::RENAMED_AND_REMOVED_LINTS, msg, help)
+                }
+                CheckLintNameResult::NoLint(suggestion) => {
+                    let help = suggestion.map(|name| format!("did you mean: `{}`", name));
+                    (builtin::UNKNOWN_LINTS, format!("unknown lint: `{}`", lint_name), help)
+                }
+                CheckLintNameResult::Ok(_) | CheckLintNameResult::Tool(_) => continue,
+            };
+            let (level, src) = self.sets.get_lint_level(lint, 0, None, self.sess);
+            let mut err = lint::struct_lint_level(self.sess, lint, level, src, None, &msg);
+            if let Some(help) = help {
+                err.help(&help);
+            }
+            err.note(&format!("requested in the lint config file `{}`",
+                              lint_config.path.display()));
+            err.emit();
+        }
+    }
+
     /// Called after `push` when the scope of a set of attributes are exited.
     pub fn pop(&mut self, push: BuilderPush) {
         self.cur = push.prev;
+        self.cur_file = push.prev_file;
     }
 
     /// Used to emit a lint-related diagnostic based on the current state of
@@ -521,6 +666,39 @@ impl LintLevelMap {
             self.sets.get_lint_level(lint, *idx, None, session)
         })
     }
+
+    /// Returns the lints named in the `#[expect]` attributes of the crate, ordered by where they
+    /// are named.
+    pub fn expectations(&self) -> Vec<LintExpectation> {
+        let mut expectations = vec![];
+        for (&hir_id, &idx) in &self.id_to_set {
+            let specs = match self.sets.list[idx as usize] {
+                LintSet::Node { ref specs, .. } => specs,
+                LintSet::CommandLine { .. } => continue,
+            };
+            for &(level, src) in specs.values() {
+                if let (Level::Expect, LintSource::Node(lint_name, span, reason)) = (level, src) {
+                    expectations.push(LintExpectation { span, lint_name, reason, hir_id });
+                }
+            }
+        }
+        // All the lints of a group share the same expectation.
+        expectations.sort_by_key(|expectation| expectation.span);
+        expectations.dedup_by_key(|expectation| expectation.span);
+        expectations
+    }
+}
+
+/// A lint named in an `#[expect]` attribute.
+pub struct LintExpectation {
+    /// Where the lint is named in the attribute.
+    pub span: Span,
+    /// The name of the lint, or of the lint group.
+    pub lint_name: Symbol,
+    /// The reason given in the attribute (RFC 2383).
+    pub reason: Option<Symbol>,
+    /// The node the attribute is on.
+    pub hir_id: HirId,
 }
 
 impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
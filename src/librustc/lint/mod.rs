@@ -32,7 +32,8 @@ use crate::session::{Session, DiagnosticMessageId};
 use crate::ty::TyCtxt;
 use crate::ty::query::Providers;
 use crate::util::nodemap::NodeMap;
-use errors::{DiagnosticBuilder, DiagnosticId};
+use errors::{Diagnostic, DiagnosticBuilder, DiagnosticId};
+use errors::baseline::{self, BaselineKey};
 use std::{hash, ptr};
 use syntax::ast;
 use syntax::source_map::{MultiSpan, ExpnKind, DesugaringKind};
@@ -520,11 +521,16 @@ impl LintId {
 /// Setting for how to handle a lint.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 pub enum Level {
-    Allow, Warn, Deny, Forbid,
+    Allow,
+    /// Like `Allow`, but the lint is expected to fire, and `unfulfilled_lint_expectations` warns
+    /// if it never does.
+    Expect,
+    Warn, Deny, Forbid,
 }
 
 impl_stable_hash_for!(enum self::Level {
     Allow,
+    Expect,
     Warn,
     Deny,
     Forbid
@@ -535,6 +541,7 @@ impl Level {
     pub fn as_str(self) -> &'static str {
         match self {
             Allow => "allow",
+            Expect => "expect",
             Warn => "warn",
             Deny => "deny",
             Forbid => "forbid",
@@ -556,6 +563,7 @@ impl Level {
     pub fn from_symbol(x: Symbol) -> Option<Level> {
         match x {
             sym::allow => Some(Allow),
+            sym::expect => Some(Expect),
             sym::warn => Some(Warn),
             sym::deny => Some(Deny),
             sym::forbid => Some(Forbid),
@@ -576,12 +584,17 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine(Symbol),
+
+    /// Lint level was set by the file given to `--lint-config`, naming the lint
+    /// or lint group.
+    ConfigFile(Symbol),
 }
 
 impl_stable_hash_for!(enum self::LintSource {
     Default,
     Node(name, span, reason),
-    CommandLine(text)
+    CommandLine(text),
+    ConfigFile(name)
 });
 
 pub type LevelSource = (Level, LintSource);
@@ -591,7 +604,7 @@ pub mod internal;
 mod context;
 mod levels;
 
-pub use self::levels::{LintLevelSets, LintLevelMap};
+pub use self::levels::{LintLevelSets, LintLevelMap, LintExpectation};
 
 #[derive(Default)]
 pub struct LintBuffer {
@@ -628,6 +641,23 @@ impl LintBuffer {
     }
 }
 
+/// The key of a lint diagnostic in the warnings baseline. Its item is the file
+/// the diagnostic is in until `TyCtxt::struct_lint_node` sets the item path.
+fn baseline_key(sess: &Session, lint: &'static Lint, span: &MultiSpan) -> BaselineKey {
+    let (item, snippet) = match span.primary_span() {
+        Some(span) => (
+            sess.source_map().span_to_filename(span).to_string(),
+            sess.source_map().span_to_snippet(span).unwrap_or_default(),
+        ),
+        None => (String::new(), String::new()),
+    };
+    BaselineKey {
+        lint: lint.name_lower(),
+        item,
+        hash: baseline::hash_snippet(&snippet),
+    }
+}
+
 pub fn struct_lint_level<'a>(sess: &'a Session,
                              lint: &'static Lint,
                              level: Level,
@@ -636,8 +666,41 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
                              msg: &str)
     -> DiagnosticBuilder<'a>
 {
+    let lints = sess.lint_store.borrow();
+    let lint_id = LintId::of(lint);
+    let future_incompatible = lints.future_incompatible(lint_id);
+
+    // Future incompatibility lints are recorded for the report whatever their
+    // level is, so that the ones in dependencies built with `--cap-lints allow`
+    // can still be reported. Like fulfilled expectations, they are recorded with
+    // a diagnostic so that the incremental cache replays them.
+    if let Some(future_incompatible) = future_incompatible {
+        if sess.opts.debugging_opts.emit_future_incompat_report {
+            let mut diagnostic = Diagnostic::new(errors::Level::FutureIncompat, msg);
+            diagnostic.future_incompat = Some(errors::FutureIncompatLint {
+                lint: lint.name_lower(),
+                level: level.as_str().to_string(),
+                reference: future_incompatible.reference.to_string(),
+                message: msg.to_string(),
+                span: span.clone().unwrap_or_else(MultiSpan::new),
+            });
+            sess.diagnostic().emit_diagnostic(&diagnostic);
+        }
+    }
+
     let mut err = match (level, span) {
         (Level::Allow, _) => return sess.diagnostic().struct_dummy(),
+        (Level::Expect, _) => {
+            // The lint is silenced, but it fulfills the expectation of the `#[expect]` attribute.
+            // This is recorded with a diagnostic rather than on the side, so that the incremental
+            // cache replays it when the query that emitted the lint is reused.
+            if let LintSource::Node(_, expectation, _) = src {
+                let mut diagnostic = Diagnostic::new(errors::Level::Expect, msg);
+                diagnostic.set_span(expectation);
+                sess.diagnostic().emit_diagnostic(&diagnostic);
+            }
+            return sess.diagnostic().struct_dummy();
+        }
         (Level::Warn, Some(span)) => sess.struct_span_warn(span, msg),
         (Level::Warn, None) => sess.struct_warn(msg),
         (Level::Deny, Some(span)) |
@@ -646,11 +709,6 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
         (Level::Forbid, None) => sess.struct_err(msg),
     };
 
-    // Check for future incompatibility lints and issue a stronger warning.
-    let lints = sess.lint_store.borrow();
-    let lint_id = LintId::of(lint);
-    let future_incompatible = lints.future_incompatible(lint_id);
-
     // If this code originates in a foreign macro, aka something that this crate
     // did not itself author, then it's likely that there's nothing this crate
     // can do about it. We probably want to skip the lint entirely.
@@ -670,6 +728,10 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
         }
     }
 
+    if sess.opts.debugging_opts.warnings_baseline.is_some() {
+        err.baseline_key = Some(baseline_key(sess, lint, &err.span));
+    }
+
     let name = lint.name_lower();
     match src {
         LintSource::Default => {
@@ -683,7 +745,7 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
                 Level::Warn => "-W",
                 Level::Deny => "-D",
                 Level::Forbid => "-F",
-                Level::Allow => panic!(),
+                Level::Allow | Level::Expect => panic!(),
             };
             let hyphen_case_lint_name = name.replace("_", "-");
             if lint_flag_val.as_str() == name {
@@ -715,10 +777,25 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
                                              level_str, name, level_str, lint_attr_name));
             }
         }
+        LintSource::ConfigFile(lint_config_name) => {
+            let path = match sess.opts.lint_config {
+                Some(ref lint_config) => lint_config.path.display().to_string(),
+                None => bug!("lint level set by a lint config file without `--lint-config`"),
+            };
+            let msg = if lint_config_name.as_str() == name {
+                format!("`{}` set to `{}` in the lint config file `{}`",
+                        name, level.as_str(), path)
+            } else {
+                format!("`{}` set to `{}` by `{}` in the lint config file `{}`",
+                        name, level.as_str(), lint_config_name, path)
+            };
+            sess.diag_note_once(&mut err, DiagnosticMessageId::from(lint), &msg);
+        }
     }
 
     err.code(DiagnosticId::Lint(name));
 
+    // Issue a stronger warning for future incompatibility lints.
     if let Some(future_incompatible) = future_incompatible {
         const STANDARD_MESSAGE: &str =
             "this was previously accepted by the compiler but is being phased out; \
@@ -759,6 +836,8 @@ fn lint_levels(tcx: TyCtxt<'_>, cnum: CrateNum) -> &LintLevelMap {
     };
     let krate = tcx.hir().krate();
 
+    builder.levels.check_lint_config_names();
+    let overrides = builder.levels.push_path_overrides(krate.module.inner);
     let push = builder.levels.push(&krate.attrs);
     builder.levels.register_id(hir::CRATE_HIR_ID);
     for macro_def in &krate.exported_macros {
@@ -766,10 +845,40 @@ fn lint_levels(tcx: TyCtxt<'_>, cnum: CrateNum) -> &LintLevelMap {
     }
     intravisit::walk_crate(&mut builder, krate);
     builder.levels.pop(push);
+    builder.levels.pop(overrides);
 
     tcx.arena.alloc(builder.levels.build_map())
 }
 
+/// Warns about the lints named in `#[expect]` attributes that were never emitted in the scope of
+/// the attribute. Must run after all lints of the crate have been emitted.
+pub fn check_expectations(tcx: TyCtxt<'_>) {
+    // An `#[expect(unfulfilled_lint_expectations)]` is fulfilled by the unfulfilled expectations
+    // nested in it, which are named after it. So the expectations are checked from last to first,
+    // and the warnings are buffered to be emitted in order.
+    let mut diagnostics = vec![];
+    for expectation in tcx.lint_levels(LOCAL_CRATE).expectations().into_iter().rev() {
+        if tcx.sess.diagnostic().is_expectation_fulfilled(expectation.span) {
+            continue;
+        }
+        let mut err = tcx.struct_span_lint_hir(
+            builtin::UNFULFILLED_LINT_EXPECTATIONS,
+            expectation.hir_id,
+            expectation.span,
+            "this lint expectation is unfulfilled",
+        );
+        err.note(&format!("no `{}` lint was emitted in the scope of this attribute",
+                          expectation.lint_name));
+        if let Some(rationale) = expectation.reason {
+            err.note(&rationale.as_str());
+        }
+        err.buffer(&mut diagnostics);
+    }
+    for diagnostic in diagnostics.iter().rev() {
+        tcx.sess.diagnostic().emit_diagnostic(diagnostic);
+    }
+}
+
 struct LintLevelMapBuilder<'tcx> {
     levels: levels::LintLevelsBuilder<'tcx>,
     tcx: TyCtxt<'tcx>,
@@ -789,6 +898,19 @@ impl LintLevelMapBuilder<'tcx> {
         f(self);
         self.levels.pop(push);
     }
+
+    /// Pushes the levels from the `--lint-config` file for the module `id`,
+    /// whose contents are at `inner`. Its attributes should be pushed in `f`.
+    fn with_path_overrides<F>(&mut self, id: hir::HirId, inner: Span, f: F)
+        where F: FnOnce(&mut Self)
+    {
+        let push = self.levels.push_path_overrides(inner);
+        if push.changed {
+            self.levels.register_id(id);
+        }
+        f(self);
+        self.levels.pop(push);
+    }
 }
 
 impl intravisit::Visitor<'tcx> for LintLevelMapBuilder<'tcx> {
@@ -803,9 +925,15 @@ impl intravisit::Visitor<'tcx> for LintLevelMapBuilder<'tcx> {
     }
 
     fn visit_item(&mut self, it: &'tcx hir::Item) {
-        self.with_lint_attrs(it.hir_id, &it.attrs, |builder| {
+        let walk = |builder: &mut Self| builder.with_lint_attrs(it.hir_id, &it.attrs, |builder| {
             intravisit::walk_item(builder, it);
         });
+        match it.kind {
+            hir::ItemKind::Mod(ref module) => {
+                self.with_path_overrides(it.hir_id, module.inner, walk)
+            }
+            _ => walk(self),
+        }
     }
 
     fn visit_foreign_item(&mut self, it: &'tcx hir::ForeignItem) {
@@ -8,6 +8,7 @@ use crate::session::search_paths::SearchPath;
 
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{self, Json};
 
 use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
 use rustc_target::spec::{Target, TargetTriple};
@@ -35,6 +36,8 @@ use std::fmt;
 use std::str::{self, FromStr};
 use std::hash::Hasher;
 use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
 use std::iter::FromIterator;
 use std::path::{Path, PathBuf};
 
@@ -227,6 +230,25 @@ impl OutputType {
     }
 }
 
+/// Lint levels read from the file given to `--lint-config`.
+#[derive(Clone, Debug, Hash)]
+pub struct LintConfig {
+    /// The file, as given on the command line.
+    pub path: PathBuf,
+    /// The levels of lints and lint groups in the whole crate.
+    pub lints: Vec<(String, lint::Level)>,
+    /// The levels for the modules in some files, in the order they're listed in the file.
+    pub overrides: Vec<LintConfigOverride>,
+}
+
+/// Lint levels for the modules whose source is in one of `paths`.
+#[derive(Clone, Debug, Hash)]
+pub struct LintConfigOverride {
+    /// Absolute paths of files, or of directories containing the files.
+    pub paths: Vec<PathBuf>,
+    pub lints: Vec<(String, lint::Level)>,
+}
+
 /// The type of diagnostics output to generate.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ErrorOutputType {
@@ -240,6 +262,11 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A SARIF log, for code scanning tools.
+    Sarif {
+        /// Write the log in a human readable way (with indents and newlines).
+        pretty: bool,
+    },
 }
 
 impl Default for ErrorOutputType {
@@ -393,6 +420,7 @@ top_level_options!(
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED],
         lint_cap: Option<lint::Level> [TRACKED],
+        lint_config: Option<LintConfig> [TRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -601,6 +629,7 @@ impl Default for Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -1457,6 +1486,14 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "specifies which kinds of events get recorded by the self profiler"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emits a section containing stack size metadata"),
+    warnings_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "don't emit the lint diagnostics that are recorded in this warnings baseline file"),
+    update_warnings_baseline: bool = (false, parse_bool, [UNTRACKED],
+        "record the lint diagnostics in the `-Z warnings-baseline` file instead of \
+         suppressing them"),
+    emit_future_incompat_report: bool = (false, parse_bool, [UNTRACKED],
+        "emit a JSON report of the future-incompatible lints in the crate, including the ones \
+         silenced by `--cap-lints` or attributes (requires `--error-format=json`)"),
     plt: Option<bool> = (None, parse_opt_bool, [TRACKED],
           "whether to use the PLT when calling into shared libraries;
           only has effect for PIC code on systems with ELF binaries
@@ -1837,6 +1874,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
                                  never  = never colorize output",
             "auto|always|never",
         ),
+        opt::opt(
+            "",
+            "lint-config",
+            "Read lint levels for the crate, and for the modules in some files, \
+             from a JSON file",
+            "PATH",
+        ),
         opt::opt(
             "",
             "pretty",
@@ -1931,6 +1975,92 @@ pub fn get_cmd_lint_options(matches: &getopts::Matches,
     (lint_opts, describe_lints, lint_cap)
 }
 
+/// Reads the file given to `--lint-config`, which looks like:
+///
+/// ```json
+/// {
+///     "lints": { "unsafe_code": "forbid", "rust_2018_idioms": "warn" },
+///     "overrides": [
+///         { "paths": ["tests", "src/generated"], "lints": { "missing_docs": "allow" } }
+///     ]
+/// }
+/// ```
+///
+/// Relative paths in `overrides` are relative to the directory of the file.
+pub fn parse_lint_config(path: PathBuf, error_format: ErrorOutputType) -> LintConfig {
+    macro_rules! error {($($arg:tt)*) => {
+        early_error(error_format, &format!("invalid lint config file `{}`: {}",
+                                           path.display(), format!($($arg)*)))
+    }}
+
+    let contents = fs::read_to_string(&path).unwrap_or_else(|e| {
+        early_error(error_format,
+                    &format!("couldn't read lint config file `{}`: {}", path.display(), e))
+    });
+    let config = match json::from_str(&contents) {
+        Ok(Json::Object(config)) => config,
+        Ok(_) => error!("expected an object"),
+        Err(e) => error!("{}", e),
+    };
+    let dir = env::current_dir().unwrap_or_default()
+        .join(path.parent().unwrap_or(Path::new("")));
+
+    let lint_levels = |levels: Json, key: &str| -> Vec<(String, lint::Level)> {
+        let levels = match levels {
+            Json::Object(levels) => levels,
+            _ => error!("`{}` must be an object", key),
+        };
+        levels.into_iter().map(|(lint_name, level)| {
+            let level = level.as_string().and_then(lint::Level::from_str).unwrap_or_else(|| {
+                error!("the level of `{}` in `{}` must be `allow`, `warn`, `deny` or `forbid`",
+                       lint_name, key)
+            });
+            (lint_name.replace("-", "_"), level)
+        }).collect()
+    };
+
+    let mut lints = vec![];
+    let mut overrides = vec![];
+    for (key, value) in config {
+        match &key[..] {
+            "lints" => lints = lint_levels(value, "lints"),
+            "overrides" => {
+                let list = match value {
+                    Json::Array(list) => list,
+                    _ => error!("`overrides` must be an array"),
+                };
+                for (i, entry) in list.into_iter().enumerate() {
+                    let mut entry = match entry {
+                        Json::Object(entry) => entry,
+                        _ => error!("`overrides[{}]` must be an object", i),
+                    };
+                    let paths = match entry.remove("paths") {
+                        Some(Json::Array(ref paths)) if !paths.is_empty() => {
+                            paths.iter().map(|p| match p.as_string() {
+                                Some(p) => dir.join(p),
+                                None => error!("`overrides[{}].paths` must only contain strings",
+                                               i),
+                            }).collect()
+                        }
+                        _ => error!("`overrides[{}].paths` must be a non-empty array", i),
+                    };
+                    let lints = match entry.remove("lints") {
+                        Some(levels) => lint_levels(levels, &format!("overrides[{}].lints", i)),
+                        None => error!("`overrides[{}]` is missing `lints`", i),
+                    };
+                    if let Some(key) = entry.keys().next() {
+                        error!("unknown key `{}` in `overrides[{}]`", key, i);
+                    }
+                    overrides.push(LintConfigOverride { paths, lints });
+                }
+            }
+            _ => error!("unknown key `{}`", key),
+        }
+    }
+
+    LintConfig { path, lints, overrides }
+}
+
 /// Parses the `--color` flag.
 pub fn parse_color(matches: &getopts::Matches) -> ColorConfig {
     match matches.opt_str("color").as_ref().map(|s| &s[..]) {
@@ -2008,6 +2138,8 @@ pub fn parse_error_format(
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
+            Some("sarif") => ErrorOutputType::Sarif { pretty: false },
+            Some("pretty-sarif") => ErrorOutputType::Sarif { pretty: true },
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
@@ -2082,6 +2214,8 @@ pub fn build_session_options_and_crate_config(
 
 
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_config = matches.opt_str("lint-config")
+        .map(|path| parse_lint_config(PathBuf::from(path), error_format));
 
     let mut debugging_opts = build_debugging_options(matches, error_format);
 
@@ -2099,6 +2233,28 @@ pub fn build_session_options_and_crate_config(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif { .. } = error_format {
+            early_error(
+                ErrorOutputType::default(),
+                "`--error-format=sarif` is unstable",
+            );
+        }
+    }
+
+    if debugging_opts.update_warnings_baseline && debugging_opts.warnings_baseline.is_none() {
+        early_error(
+            error_format,
+            "using `-Z update-warnings-baseline` requires also using `-Z warnings-baseline`",
+        );
+    }
+
+    if debugging_opts.emit_future_incompat_report {
+        if let ErrorOutputType::HumanReadable(_) | ErrorOutputType::Sarif { .. } = error_format {
+            early_error(
+                error_format,
+                "using `-Z emit-future-incompat-report` requires also using `--error-format=json`",
+            );
+        }
     }
 
     let mut output_types = BTreeMap::new();
@@ -2487,6 +2643,7 @@ pub fn build_session_options_and_crate_config(
             debuginfo,
             lint_opts,
             lint_cap,
+            lint_config,
             describe_lints,
             output_types: OutputTypes(output_types),
             search_paths,
@@ -2648,7 +2805,7 @@ mod dep_tracking {
     use std::hash::Hash;
     use std::path::PathBuf;
     use std::collections::hash_map::DefaultHasher;
-    use super::{CrateType, DebugInfo, ErrorOutputType, OptLevel, OutputTypes,
+    use super::{CrateType, DebugInfo, ErrorOutputType, LintConfig, OptLevel, OutputTypes,
                 Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
                 SymbolManglingVersion};
     use rustc_target::spec::{MergeFunctions, PanicStrategy, RelroLevel, TargetTriple};
@@ -2700,6 +2857,7 @@ mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(Option<PanicStrategy>);
     impl_dep_tracking_hash_via_hash!(Option<RelroLevel>);
     impl_dep_tracking_hash_via_hash!(Option<lint::Level>);
+    impl_dep_tracking_hash_via_hash!(Option<LintConfig>);
     impl_dep_tracking_hash_via_hash!(Option<PathBuf>);
     impl_dep_tracking_hash_via_hash!(Option<cstore::NativeLibraryKind>);
     impl_dep_tracking_hash_via_hash!(CrateType);
@@ -15,7 +15,7 @@ use crate::util::common::ProfileQueriesMsg;
 
 use rustc_data_structures::base_n;
 use rustc_data_structures::sync::{
-    self, Lrc, Lock, OneThread, Once, RwLock, AtomicU64, AtomicUsize, Ordering,
+    self, Lrc, Lock, OneThread, Once, RwLock, AtomicBool, AtomicU64, AtomicUsize, Ordering,
     Ordering::SeqCst,
 };
 
@@ -23,6 +23,8 @@ use errors::{DiagnosticBuilder, DiagnosticId, Applicability};
 use errors::emitter::{Emitter, EmitterWriter};
 use errors::emitter::HumanReadableErrorType;
 use errors::annotate_snippet_emitter_writer::{AnnotateSnippetEmitterWriter};
+use errors::sarif_emitter::SarifEmitter;
+use errors::baseline::WarningsBaseline;
 use syntax::ast::{self, NodeId};
 use syntax::edition::Edition;
 use syntax::ext::allocator::AllocatorKind;
@@ -47,6 +49,7 @@ use std::io::Write;
 use std::path::PathBuf;
 use std::time::Duration;
 use std::sync::{Arc, mpsc};
+use std::thread;
 
 mod code_stats;
 pub mod config;
@@ -164,6 +167,10 @@ pub struct Session {
     /// Cap lint level specified by a driver specifically.
     pub driver_lint_caps: FxHashMap<lint::LintId, lint::Level>,
 
+    /// Set once all the lints of the crate have been emitted, which the
+    /// warnings baseline can only be compared to after that.
+    pub lints_checked: AtomicBool,
+
     /// `Span`s of trait methods that weren't found to avoid emitting object safety errors
     pub trait_methods_not_found: Lock<FxHashSet<Span>>,
 
@@ -413,6 +420,67 @@ impl Session {
         &self.parse_sess.span_diagnostic
     }
 
+    /// Emits the report of `-Z emit-future-incompat-report`, once the crate
+    /// has been compiled or compilation has stopped with errors. Nothing is
+    /// reported while unwinding from a fatal error or an ICE, as the report
+    /// would be incomplete.
+    pub fn emit_future_incompat_report(&self) {
+        if self.opts.debugging_opts.emit_future_incompat_report && !thread::panicking() {
+            self.diagnostic().emit_future_incompat_report();
+        }
+    }
+
+    /// Writes the warnings baseline if `-Z update-warnings-baseline` was passed,
+    /// or else warns about the entries of the baseline that no longer occur.
+    pub fn finish_warnings_baseline(&self) {
+        let baseline = match self.diagnostic().take_warnings_baseline() {
+            Some(baseline) => baseline,
+            None => return,
+        };
+        // Not all the lints run when there are errors, so the baseline can't
+        // be compared to the diagnostics.
+        if self.has_errors() {
+            if baseline.is_recording() {
+                self.warn(&format!(
+                    "the warnings baseline `{}` was not updated because of errors",
+                    baseline.path().display(),
+                ));
+            }
+            return;
+        }
+        // Nor when compilation stopped before the lints were checked, like with
+        // `--print` or `-Z parse-only`, or when the compiler panicked.
+        if !self.lints_checked.load(Ordering::SeqCst) || thread::panicking() {
+            return;
+        }
+        if baseline.is_recording() {
+            if let Err(e) = baseline.write() {
+                self.err(&e);
+            }
+            return;
+        }
+        let stale = baseline.stale_entries();
+        if stale.is_empty() {
+            return;
+        }
+        let count = stale.iter().map(|&(_, count)| count).sum::<usize>();
+        let path = baseline.path().display();
+        let msg = match count {
+            1 => format!("1 diagnostic in the warnings baseline `{}` no longer occurs", path),
+            n => format!("{} diagnostics in the warnings baseline `{}` no longer occur", n, path),
+        };
+        let mut diag = self.struct_warn(&msg);
+        for (key, count) in stale {
+            let times = if count == 1 { String::new() } else { format!(" ({} times)", count) };
+            diag.note(&format!(
+                "`{}` in `{}` with hash `{}`{}",
+                key.lint, key.item, key.hash, times,
+            ));
+        }
+        diag.help("rerun with `-Z update-warnings-baseline` to remove them from the baseline");
+        diag.emit();
+    }
+
     /// Analogous to calling methods on the given `DiagnosticBuilder`, but
     /// deduplicates on lint ID, span (if any), and message for this `Session`
     fn diag_once<'a, 'b>(
@@ -1063,6 +1131,12 @@ fn default_emitter(
                 external_macro_backtrace,
             ).ui_testing(sopts.debugging_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif { pretty }, None) => Box::new(
+            SarifEmitter::stderr(Some(source_map.clone()), pretty),
+        ),
+        (config::ErrorOutputType::Sarif { pretty }, Some(dst)) => Box::new(
+            SarifEmitter::new(dst, Some(source_map.clone()), pretty),
+        ),
     }
 }
 
@@ -1118,6 +1192,14 @@ pub fn build_session_with_source_map(
         },
     );
 
+    if let Some(ref path) = sopts.debugging_opts.warnings_baseline {
+        let recording = sopts.debugging_opts.update_warnings_baseline;
+        match WarningsBaseline::load(path.clone(), recording) {
+            Ok(baseline) => diagnostic_handler.set_warnings_baseline(baseline),
+            Err(e) => early_error(sopts.error_format, &e),
+        }
+    }
+
     build_session_(sopts, local_crate_source_file, diagnostic_handler, source_map, lint_caps)
 }
 
@@ -1251,6 +1333,7 @@ fn build_session_(
         has_global_allocator: Once::new(),
         has_panic_handler: Once::new(),
         driver_lint_caps,
+        lints_checked: AtomicBool::new(false),
         trait_methods_not_found: Lock::new(Default::default()),
         confused_type_with_std_module: Lock::new(Default::default()),
     };
@@ -1360,6 +1443,8 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         }
         config::ErrorOutputType::Json { pretty, json_rendered } =>
             Box::new(JsonEmitter::basic(pretty, json_rendered, false)),
+        config::ErrorOutputType::Sarif { pretty } =>
+            Box::new(SarifEmitter::stderr(None, pretty)),
     };
     let handler = errors::Handler::with_emitter(true, None, emitter);
     handler.struct_fatal(msg).emit();
@@ -1374,6 +1459,8 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         }
         config::ErrorOutputType::Json { pretty, json_rendered } =>
             Box::new(JsonEmitter::basic(pretty, json_rendered, false)),
+        config::ErrorOutputType::Sarif { pretty } =>
+            Box::new(SarifEmitter::stderr(None, pretty)),
     };
     let handler = errors::Handler::with_emitter(true, None, emitter);
     handler.struct_warn(msg).emit();
@@ -2797,14 +2797,26 @@ impl<'tcx> TyCtxt<'tcx> {
         -> DiagnosticBuilder<'tcx>
     {
         let (level, src) = self.lint_level_at_node(lint, hir_id);
-        lint::struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg)
+        let mut err = lint::struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg);
+        self.set_baseline_item(&mut err, hir_id);
+        err
     }
 
     pub fn struct_lint_node(self, lint: &'static Lint, id: HirId, msg: &str)
         -> DiagnosticBuilder<'tcx>
     {
         let (level, src) = self.lint_level_at_node(lint, id);
-        lint::struct_lint_level(self.sess, lint, level, src, None, msg)
+        let mut err = lint::struct_lint_level(self.sess, lint, level, src, None, msg);
+        self.set_baseline_item(&mut err, id);
+        err
+    }
+
+    /// Keys a lint diagnostic in the warnings baseline by the path of the item
+    /// it's in, rather than by its file.
+    fn set_baseline_item(self, err: &mut DiagnosticBuilder<'_>, hir_id: HirId) {
+        if let Some(ref mut key) = err.baseline_key {
+            key.item = self.def_path_str(DefId::local(hir_id.owner));
+        }
     }
 
     pub fn in_scope_traits(self, id: HirId) -> Option<&'tcx StableVec<TraitCandidate>> {
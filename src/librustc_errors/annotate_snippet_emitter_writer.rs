@@ -156,8 +156,11 @@ impl<'a>  DiagnosticConverter<'a> {
             Level::Warning => AnnotationType::Warning,
             Level::Note => AnnotationType::Note,
             Level::Help => AnnotationType::Help,
-            // FIXME(#59346): Not sure how to map these two levels
-            Level::Cancelled | Level::FailureNote => AnnotationType::Error
+            // FIXME(#59346): Not sure how to map these levels
+            Level::Cancelled |
+            Level::FailureNote |
+            Level::Expect |
+            Level::FutureIncompat => AnnotationType::Error
         }
     }
 }
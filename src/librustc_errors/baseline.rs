@@ -0,0 +1,147 @@
+//! Warnings baselines, which suppress the lint diagnostics a crate already had
+//! when the baseline was recorded.
+//!
+//! A baseline lists the lint diagnostics by lint name, the path of the item
+//! they're in and a hash of the code they point to, so that they still match
+//! when unrelated code moves them to other lines. Any diagnostic that isn't in
+//! the baseline is emitted as usual.
+
+use crate::Diagnostic;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_serialize::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Identifies a lint diagnostic in a baseline.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, RustcEncodable, RustcDecodable)]
+pub struct BaselineKey {
+    /// The name of the lint, e.g. `unused_variables`.
+    pub lint: String,
+    /// The path of the item the diagnostic is in, or the file it's in if
+    /// the lint was emitted before items had paths.
+    pub item: String,
+    /// A hash of the source code of the primary span, in hexadecimal.
+    pub hash: String,
+}
+
+/// A key and how many of the diagnostics with that key there were.
+#[derive(RustcEncodable, RustcDecodable)]
+struct BaselineEntry {
+    lint: String,
+    item: String,
+    hash: String,
+    count: usize,
+}
+
+#[derive(RustcEncodable, RustcDecodable)]
+struct BaselineFile {
+    warnings: Vec<BaselineEntry>,
+}
+
+pub struct WarningsBaseline {
+    path: PathBuf,
+    /// If true, the diagnostics are emitted and counted to write a new baseline
+    /// rather than suppressed.
+    recording: bool,
+    expected: FxHashMap<BaselineKey, usize>,
+    seen: FxHashMap<BaselineKey, usize>,
+    /// The hashes of the diagnostics suppressed so far, so that duplicates of
+    /// them are suppressed as well.
+    suppressed: FxHashSet<u128>,
+}
+
+impl WarningsBaseline {
+    /// Reads the baseline at `path`. If `recording` is true, the file doesn't
+    /// have to exist since it will be overwritten.
+    pub fn load(path: PathBuf, recording: bool) -> Result<WarningsBaseline, String> {
+        let mut expected = FxHashMap::default();
+        if !recording || path.exists() {
+            let contents = fs::read_to_string(&path).map_err(|e| {
+                format!("failed to read warnings baseline `{}`: {}", path.display(), e)
+            })?;
+            let file: BaselineFile = json::decode(&contents).map_err(|e| {
+                format!("invalid warnings baseline `{}`: {}", path.display(), e)
+            })?;
+            for BaselineEntry { lint, item, hash, count } in file.warnings {
+                *expected.entry(BaselineKey { lint, item, hash }).or_insert(0) += count;
+            }
+        }
+        Ok(WarningsBaseline {
+            path,
+            recording,
+            expected,
+            seen: Default::default(),
+            suppressed: Default::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Counts `diagnostic`, whose hash is `diagnostic_hash`, and returns whether
+    /// it is in the baseline and shouldn't be emitted. This must be called once
+    /// per distinct diagnostic.
+    pub(crate) fn suppresses(&mut self, diagnostic_hash: u128, diagnostic: &Diagnostic) -> bool {
+        if self.suppressed.contains(&diagnostic_hash) {
+            return true;
+        }
+        let key = match diagnostic.baseline_key {
+            Some(ref key) => key,
+            None => return false,
+        };
+        let seen = self.seen.entry(key.clone()).or_insert(0);
+        *seen += 1;
+        let expected = self.expected.get(key).cloned().unwrap_or(0);
+        if self.recording || *seen > expected {
+            return false;
+        }
+        self.suppressed.insert(diagnostic_hash);
+        true
+    }
+
+    /// The entries of the baseline that occurred fewer times than recorded,
+    /// and how many of them didn't occur.
+    pub fn stale_entries(&self) -> Vec<(&BaselineKey, usize)> {
+        let mut stale = self.expected.iter().filter_map(|(key, &expected)| {
+            let seen = self.seen.get(key).cloned().unwrap_or(0);
+            if seen < expected { Some((key, expected - seen)) } else { None }
+        }).collect::<Vec<_>>();
+        stale.sort();
+        stale
+    }
+
+    /// Writes the diagnostics seen so far to `self.path` as the new baseline.
+    pub fn write(&self) -> Result<(), String> {
+        let warnings = self.seen.iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(key, &count)| BaselineEntry {
+                lint: key.lint.clone(),
+                item: key.item.clone(),
+                hash: key.hash.clone(),
+                count,
+            })
+            .collect();
+        let file = BaselineFile { warnings };
+        fs::write(&self.path, format!("{}\n", json::as_pretty_json(&file))).map_err(|e| {
+            format!("failed to write warnings baseline `{}`: {}", self.path.display(), e)
+        })
+    }
+}
+
+/// Hashes the source code of a span for a `BaselineKey`.
+pub fn hash_snippet(snippet: &str) -> String {
+    use rustc_data_structures::stable_hasher::StableHasher;
+    use std::hash::Hash;
+
+    let mut hasher = StableHasher::new();
+    snippet.hash(&mut hasher);
+    let hash: u64 = hasher.finish();
+    format!("{:016x}", hash)
+}
@@ -4,6 +4,8 @@ use crate::SubstitutionPart;
 use crate::Substitution;
 use crate::Applicability;
 use crate::Level;
+use crate::FutureIncompatLint;
+use crate::baseline::BaselineKey;
 use crate::snippet::Style;
 use std::fmt;
 use syntax_pos::{MultiSpan, Span};
@@ -17,6 +19,10 @@ pub struct Diagnostic {
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
     pub suggestions: Vec<CodeSuggestion>,
+    /// Identifies lint diagnostics in a warnings baseline.
+    pub baseline_key: Option<BaselineKey>,
+    /// The lint that a `FutureIncompat` diagnostic records.
+    pub future_incompat: Option<FutureIncompatLint>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -87,6 +93,8 @@ impl Diagnostic {
             span: MultiSpan::new(),
             children: vec![],
             suggestions: vec![],
+            baseline_key: None,
+            future_incompat: None,
         }
     }
 
@@ -102,7 +110,9 @@ impl Diagnostic {
             Level::Warning |
             Level::Note |
             Level::Help |
-            Level::Cancelled => {
+            Level::Cancelled |
+            Level::Expect |
+            Level::FutureIncompat => {
                 false
             }
         }
@@ -13,7 +13,7 @@ use syntax_pos::{SourceFile, Span, MultiSpan};
 
 use crate::{
     Level, CodeSuggestion, Diagnostic, SubDiagnostic,
-    SuggestionStyle, SourceMapperDyn, DiagnosticId,
+    SuggestionStyle, SourceMapperDyn, DiagnosticId, FutureIncompatLint,
 };
 use crate::Level::Error;
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, StyledString, Style};
@@ -187,11 +187,20 @@ pub trait Emitter {
     /// other formats can, and will, simply ignore it.
     fn emit_artifact_notification(&mut self, _path: &Path, _artifact_type: &str) {}
 
+    /// Emit the report of `-Z emit-future-incompat-report` at the end of the
+    /// compilation. This is only supported for the JSON format as well.
+    fn emit_future_incompat_report(&mut self, _lints: &[FutureIncompatLint]) {}
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
     }
 
+    /// Checks if should show the "aborting due to previous error" summary
+    fn should_show_error_count(&self) -> bool {
+        true
+    }
+
     /// Formats the substitutions of the primary_span
     ///
     /// The are a lot of conditions to this method, but in short:
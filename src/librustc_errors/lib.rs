@@ -15,6 +15,7 @@ use Level::*;
 
 use emitter::{Emitter, EmitterWriter};
 use registry::Registry;
+use baseline::WarningsBaseline;
 
 use rustc_data_structures::sync::{self, Lrc, Lock};
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
@@ -32,6 +33,8 @@ mod diagnostic;
 mod diagnostic_builder;
 pub mod emitter;
 pub mod annotate_snippet_emitter_writer;
+pub mod sarif_emitter;
+pub mod baseline;
 mod snippet;
 pub mod registry;
 mod styled_buffer;
@@ -294,6 +297,20 @@ impl error::Error for ExplicitBug {
 pub use diagnostic::{Diagnostic, SubDiagnostic, DiagnosticStyledString, DiagnosticId};
 pub use diagnostic_builder::DiagnosticBuilder;
 
+/// A future-incompatible lint that was emitted in the crate, at any level,
+/// including `allow`.
+#[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
+pub struct FutureIncompatLint {
+    /// The name of the lint.
+    pub lint: String,
+    /// The level the lint was emitted at, after `--cap-lints`.
+    pub level: String,
+    /// Where to learn more, usually the tracking issue of the lint.
+    pub reference: String,
+    pub message: String,
+    pub span: MultiSpan,
+}
+
 /// A handler deals with errors and other compiler output.
 /// Certain errors (fatal, bug, unimpl) may cause immediate exit,
 /// others log errors for later reporting.
@@ -335,6 +352,18 @@ struct HandlerInner {
     /// The stashed diagnostics count towards the total error count.
     /// When `.abort_if_errors()` is called, these are also emitted.
     stashed_diagnostics: FxIndexMap<(Span, StashKey), Diagnostic>,
+
+    /// The lint diagnostics that are suppressed because they were already
+    /// there when the baseline was recorded, if there is a baseline.
+    warnings_baseline: Option<WarningsBaseline>,
+
+    /// The spans of the `#[expect]` attributes that an `Expect` diagnostic
+    /// was emitted for.
+    fulfilled_expectations: FxHashSet<Span>,
+
+    /// The lints recorded by `FutureIncompat` diagnostics, for the report of
+    /// `-Z emit-future-incompat-report`.
+    future_incompat_lints: Vec<FutureIncompatLint>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -450,6 +479,9 @@ impl Handler {
                 emitted_diagnostic_codes: Default::default(),
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
+                warnings_baseline: None,
+                fulfilled_expectations: Default::default(),
+                future_incompat_lints: Vec::new(),
             }),
         }
     }
@@ -734,9 +766,32 @@ impl Handler {
         self.inner.borrow_mut().emit_artifact_notification(path, artifact_type)
     }
 
+    /// Emits the report of the lints recorded by `FutureIncompat` diagnostics.
+    pub fn emit_future_incompat_report(&self) {
+        let inner = &mut *self.inner.borrow_mut();
+        inner.emitter.emit_future_incompat_report(&inner.future_incompat_lints)
+    }
+
     pub fn delay_as_bug(&self, diagnostic: Diagnostic) {
         self.inner.borrow_mut().delay_as_bug(diagnostic)
     }
+
+    /// Suppresses the diagnostics that are in `baseline` from now on.
+    pub fn set_warnings_baseline(&self, baseline: WarningsBaseline) {
+        self.inner.borrow_mut().warnings_baseline = Some(baseline);
+    }
+
+    /// Stops suppressing diagnostics, and returns the baseline with the counts
+    /// of the diagnostics emitted so far.
+    pub fn take_warnings_baseline(&self) -> Option<WarningsBaseline> {
+        self.inner.borrow_mut().warnings_baseline.take()
+    }
+
+    /// Whether an `Expect` diagnostic was emitted for the `#[expect]`
+    /// attribute at `span`.
+    pub fn is_expectation_fulfilled(&self, span: Span) -> bool {
+        self.inner.borrow().fulfilled_expectations.contains(&span)
+    }
 }
 
 impl HandlerInner {
@@ -767,6 +822,24 @@ impl HandlerInner {
             track_diagnostics.get()(diagnostic);
         });
 
+        // Expected lints aren't printed. They are tracked like the other
+        // diagnostics so that the incremental cache replays them along with
+        // the query that emitted them.
+        if diagnostic.level == Expect {
+            self.fulfilled_expectations.extend(diagnostic.span.primary_span());
+            return;
+        }
+
+        // So are the records of future-incompatible lints.
+        if diagnostic.level == FutureIncompat {
+            if let Some(ref lint) = diagnostic.future_incompat {
+                if !self.future_incompat_lints.contains(lint) {
+                    self.future_incompat_lints.push(lint.clone());
+                }
+            }
+            return;
+        }
+
         if let Some(ref code) = diagnostic.code {
             self.emitted_diagnostic_codes.insert(code.clone());
         }
@@ -778,6 +851,13 @@ impl HandlerInner {
             hasher.finish()
         };
 
+        if let Some(ref mut baseline) = self.warnings_baseline {
+            if !self.emitted_diagnostics.contains(&diagnostic_hash) &&
+                baseline.suppresses(diagnostic_hash, diagnostic) {
+                return;
+            }
+        }
+
         // Only emit the diagnostic if we haven't already emitted an equivalent
         // one:
         if self.emitted_diagnostics.insert(diagnostic_hash) {
@@ -811,7 +891,9 @@ impl HandlerInner {
             return;
         }
 
-        let _ = self.fatal(&s);
+        if self.emitter.should_show_error_count() {
+            let _ = self.fatal(&s);
+        }
 
         let can_show_explain = self.emitter.should_show_explain();
         let are_there_diagnostics = !self.emitted_diagnostic_codes.is_empty();
@@ -956,6 +1038,13 @@ pub enum Level {
     Help,
     Cancelled,
     FailureNote,
+    /// A lint silenced by an `#[expect]` attribute, whose primary span is
+    /// the lint in the attribute. It isn't printed, but marks the expectation
+    /// as fulfilled.
+    Expect,
+    /// The record of a future-incompatible lint, at any level, for
+    /// `-Z emit-future-incompat-report`. It isn't printed either.
+    FutureIncompat,
 }
 
 impl fmt::Display for Level {
@@ -985,7 +1074,7 @@ impl Level {
                     .set_intense(true);
             }
             FailureNote => {}
-            Cancelled => unreachable!(),
+            Cancelled | Expect | FutureIncompat => unreachable!(),
         }
         spec
     }
@@ -999,6 +1088,8 @@ impl Level {
             Help => "help",
             FailureNote => "failure-note",
             Cancelled => panic!("Shouldn't call on cancelled error"),
+            Expect => panic!("Shouldn't call on an expected lint"),
+            FutureIncompat => panic!("Shouldn't call on a future-incompatible lint record"),
         }
     }
 
@@ -0,0 +1,271 @@
+//! Emit diagnostics in SARIF, the Static Analysis Results Interchange Format
+//!
+//! This is the equivalent of `libsyntax/json.rs` for code scanning tools, which
+//! ingest [SARIF 2.1.0][sarif] logs. A log is a single JSON document rather than
+//! a stream of diagnostics, so the results are collected and the log is written
+//! when the emitter is dropped at the end of the compilation.
+//!
+//! [sarif]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use syntax_pos::{FileName, Span};
+use crate::{
+    Level, CodeSuggestion, Diagnostic, Emitter,
+    SourceMapperDyn, DiagnosticId
+};
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{Json, ToJson};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+
+const SARIF_SCHEMA: &str =
+    "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0-rtm.5.json";
+
+/// Builds a JSON object. SARIF property names are camel case, so the log is
+/// built out of `Json` values rather than encodable structs.
+macro_rules! object {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut object = BTreeMap::new();
+        $(object.insert($key.to_string(), $value.to_json());)*
+        Json::Object(object)
+    }}
+}
+
+/// Generates a SARIF log of the diagnostics
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    source_map: Option<Lrc<SourceMapperDyn>>,
+    /// If true, writes the log with indents and newlines.
+    pretty: bool,
+    /// The error codes and lint names of the results, which SARIF calls rules.
+    rules: Vec<String>,
+    results: Vec<Json>,
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, db: &Diagnostic) {
+        let level = match db.level {
+            Level::Bug | Level::Fatal | Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note | Level::Help => "note",
+            // Notes like "for more information about this error" aren't results.
+            Level::FailureNote | Level::Cancelled | Level::Expect | Level::FutureIncompat => return,
+        };
+
+        // SARIF results don't have children: the ones with spans become related
+        // locations, and the others are added to the message.
+        let mut message = db.message();
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for span_label in db.span.span_labels() {
+            let location = self.location(span_label.span, span_label.label.as_ref());
+            if span_label.is_primary {
+                locations.extend(location);
+            } else {
+                related_locations.extend(location);
+            }
+        }
+        for child in &db.children {
+            let child_message = format!("{}: {}", child.level, child.message());
+            let child_span = child.render_span.as_ref().unwrap_or(&child.span);
+            if child_span.primary_spans().is_empty() {
+                message.push('\n');
+                message.push_str(&child_message);
+            }
+            for &span in child_span.primary_spans() {
+                related_locations.extend(self.location(span, Some(&child_message)));
+            }
+        }
+
+        let mut result = BTreeMap::new();
+        result.insert("level".to_string(), level.to_json());
+        result.insert("message".to_string(), object! { "text" => message });
+        result.insert("locations".to_string(), locations.to_json());
+        if !related_locations.is_empty() {
+            result.insert("relatedLocations".to_string(), related_locations.to_json());
+        }
+        let fixes = db.suggestions.iter()
+            .flat_map(|suggestion| self.fixes(suggestion))
+            .collect::<Vec<_>>();
+        if !fixes.is_empty() {
+            result.insert("fixes".to_string(), fixes.to_json());
+        }
+        match db.code {
+            Some(DiagnosticId::Error(ref rule)) | Some(DiagnosticId::Lint(ref rule)) => {
+                result.insert("ruleId".to_string(), rule.to_json());
+                result.insert("ruleIndex".to_string(), self.rule_index(rule).to_json());
+            }
+            None => {}
+        }
+        self.results.push(Json::Object(result));
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+
+    // The summary would be a result of its own, with no location, rather than
+    // a note on the errors.
+    fn should_show_error_count(&self) -> bool {
+        false
+    }
+}
+
+impl SarifEmitter {
+    pub fn stderr(source_map: Option<Lrc<SourceMapperDyn>>, pretty: bool) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::stderr()), source_map, pretty)
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        source_map: Option<Lrc<SourceMapperDyn>>,
+        pretty: bool,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            source_map,
+            pretty,
+            rules: vec![],
+            results: vec![],
+        }
+    }
+
+    fn rule_index(&mut self, rule: &str) -> usize {
+        match self.rules.iter().position(|r| r == rule) {
+            Some(index) => index,
+            None => {
+                self.rules.push(rule.to_string());
+                self.rules.len() - 1
+            }
+        }
+    }
+
+    /// Returns the URI of the file `span` is in and the region of the file it
+    /// covers, if it's in a file.
+    fn artifact_region(&self, span: Span) -> Option<(String, Json)> {
+        let source_map = self.source_map.as_ref()?;
+        if span.is_dummy() {
+            return None;
+        }
+        let start = source_map.lookup_char_pos(span.lo());
+        let end = source_map.lookup_char_pos(span.hi());
+        let uri = match start.file.name {
+            FileName::Real(ref path) => uri_from_path(path),
+            _ => return None,
+        };
+        let region = object! {
+            // Lines and columns are 1-based, and columns count characters.
+            "startLine" => start.line,
+            "startColumn" => start.col.0 + 1,
+            "endLine" => end.line,
+            "endColumn" => end.col.0 + 1,
+            "byteOffset" => span.lo().0 - start.file.start_pos.0,
+            "byteLength" => span.hi().0 - span.lo().0,
+        };
+        Some((uri, region))
+    }
+
+    fn location(&self, span: Span, message: Option<&String>) -> Option<Json> {
+        let (uri, region) = self.artifact_region(span)?;
+        let mut location = BTreeMap::new();
+        location.insert("physicalLocation".to_string(), object! {
+            "artifactLocation" => object! { "uri" => uri },
+            "region" => region,
+        });
+        if let Some(message) = message {
+            location.insert("message".to_string(), object! { "text" => message });
+        }
+        Some(Json::Object(location))
+    }
+
+    /// A fix for each of the alternative substitutions of a suggestion.
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Json> {
+        suggestion.substitutions.iter().filter_map(|substitution| {
+            let mut changes = BTreeMap::new();
+            for part in &substitution.parts {
+                let (uri, region) = self.artifact_region(part.span)?;
+                changes.entry(uri).or_insert_with(Vec::new).push(object! {
+                    "deletedRegion" => region,
+                    "insertedContent" => object! { "text" => part.snippet },
+                });
+            }
+            let changes = changes.into_iter().map(|(uri, replacements)| object! {
+                "artifactLocation" => object! { "uri" => uri },
+                "replacements" => replacements,
+            }).collect::<Vec<_>>();
+            Some(object! {
+                "description" => object! { "text" => suggestion.msg },
+                "artifactChanges" => changes,
+                "properties" => object! {
+                    "applicability" => format!("{:?}", suggestion.applicability),
+                },
+            })
+        }).collect()
+    }
+
+    fn log(&self) -> Json {
+        let rules = self.rules.iter().map(|rule| {
+            let mut descriptor = BTreeMap::new();
+            descriptor.insert("id".to_string(), rule.to_json());
+            if rule.starts_with('E') {
+                let help_uri = format!("https://doc.rust-lang.org/error-index.html#{}", rule);
+                descriptor.insert("helpUri".to_string(), help_uri.to_json());
+            }
+            Json::Object(descriptor)
+        }).collect::<Vec<_>>();
+        object! {
+            "$schema" => SARIF_SCHEMA,
+            "version" => "2.1.0",
+            "runs" => vec![object! {
+                "tool" => object! {
+                    "driver" => object! {
+                        "name" => "rustc",
+                        "informationUri" => "https://www.rust-lang.org/",
+                        "rules" => rules,
+                    },
+                },
+                "columnKind" => "unicodeCodePoints",
+                "results" => self.results,
+            }],
+        }
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = self.log();
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", log.pretty())
+        } else {
+            writeln!(&mut self.dst, "{}", log)
+        };
+        if let Err(e) = result {
+            // Don't turn a panic into an abort.
+            if !thread::panicking() {
+                panic!("failed to print diagnostics: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Turns a path into a URI reference: relative paths stay relative to the
+/// working directory, and absolute paths get the `file` scheme.
+fn uri_from_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::new();
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    } else if path.get(1..2) == Some(":") {
+        uri.push_str("file:///");
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+            b'-' | b'.' | b'_' | b'~' | b'/' | b':' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
@@ -112,6 +112,8 @@ where
     };
 
     let _sess_abort_error = OnDrop(|| {
+        compiler.sess.finish_warnings_baseline();
+        compiler.sess.emit_future_incompat_report();
         compiler.sess.diagnostic().print_error_count(&util::diagnostics_registry());
     });
 
@@ -39,6 +39,7 @@ use rustc::lint::builtin::{
     INTRA_DOC_LINK_RESOLUTION_FAILURE,
     MISSING_DOC_CODE_EXAMPLES,
     PRIVATE_DOC_TESTS,
+    UNTESTED_DOC_CODE_EXAMPLES,
     parser::ILL_FORMED_ATTRIBUTE_INPUT,
 };
 use rustc::session;
@@ -288,7 +289,8 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                     "rustdoc",
                     INTRA_DOC_LINK_RESOLUTION_FAILURE,
                     MISSING_DOC_CODE_EXAMPLES,
-                    PRIVATE_DOC_TESTS);
+                    PRIVATE_DOC_TESTS,
+                    UNTESTED_DOC_CODE_EXAMPLES);
 
     // Guidelines for creating a future incompatibility lint:
     //
@@ -0,0 +1,693 @@
+//! `--api-diff`: compares the public API of the crate being documented with the
+//! one of a previous release, and classifies every difference as breaking or
+//! non-breaking.
+//!
+//! The previous release is described by the file `--output-format json` wrote
+//! for it, and the crate being documented is converted to the same form, so
+//! both sides are compared as JSON. Items are matched up by their path, since
+//! ids are not stable between compilations, and trait impls by their trait and
+//! `Self` type. The latter includes the auto trait impls synthesized by
+//! `clean::auto_trait`, so a type that stopped being `Send` shows up as a
+//! removed impl. The items of inherent impls count as items of their `Self`
+//! type, and the ones of trait impls are named like `<Type as Trait>::item`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use errors;
+use serialize::json::{self, Json, ToJson};
+
+use crate::clean;
+use crate::config::OutputFormat;
+use crate::externalfiles::load_string;
+use crate::json::{crate_to_json, object, FORMAT_VERSION};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        }
+    }
+}
+
+/// A difference between the two versions of the API.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Change {
+    /// The path of the item, or `impl Trait for Type` for trait impls.
+    path: String,
+    kind: ChangeKind,
+    breaking: bool,
+    description: String,
+}
+
+/// Compares `krate` with the previous version described by the JSON file at
+/// `baseline`, and prints the differences in `format`. Returns a failing exit
+/// code if any of them is breaking.
+pub fn run(
+    krate: &clean::Crate,
+    baseline: &Path,
+    format: OutputFormat,
+    diag: &errors::Handler,
+) -> i32 {
+    let old = match load_string(baseline, diag) {
+        Ok(s) => s,
+        Err(_) => return rustc_driver::EXIT_FAILURE,
+    };
+    let old = match json::from_str(&old) {
+        Ok(old) => old,
+        Err(e) => {
+            diag.struct_err(&format!("error parsing `{}`: {}", baseline.display(), e)).emit();
+            return rustc_driver::EXIT_FAILURE;
+        }
+    };
+    let version = field(&old, "format_version").as_u64();
+    if version != Some(FORMAT_VERSION as u64) {
+        let found = version.map_or("none".to_string(), |v| v.to_string());
+        diag.struct_err(&format!("`{}` can't be compared with this version of rustdoc",
+                                 baseline.display()))
+            .note(&format!("expected JSON format version {}, found {}", FORMAT_VERSION, found))
+            .emit();
+        return rustc_driver::EXIT_FAILURE;
+    }
+
+    let changes = diff(&old, &crate_to_json(krate));
+    match format {
+        OutputFormat::Html | OutputFormat::Man | OutputFormat::Markdown => {
+            print_report(&krate.name, baseline, &changes)
+        }
+        OutputFormat::Json => println!("{}", report_to_json(&changes).pretty()),
+    }
+
+    if changes.iter().any(|c| c.breaking) {
+        rustc_driver::EXIT_FAILURE
+    } else {
+        rustc_driver::EXIT_SUCCESS
+    }
+}
+
+fn print_report(crate_name: &str, baseline: &Path, changes: &[Change]) {
+    let breaking = changes.iter().filter(|c| c.breaking).count();
+    println!("API changes in `{}` compared to `{}`", crate_name, baseline.display());
+
+    for &(is_breaking, title) in &[(true, "Breaking changes"), (false, "Non-breaking changes")] {
+        let mut group = changes.iter().filter(|c| c.breaking == is_breaking).peekable();
+        if group.peek().is_none() {
+            continue;
+        }
+        println!("\n{}:", title);
+        for change in group {
+            println!("    {} `{}`: {}", change.kind.as_str(), change.path, change.description);
+        }
+    }
+
+    println!("\n{} breaking and {} non-breaking changes", breaking, changes.len() - breaking);
+}
+
+fn report_to_json(changes: &[Change]) -> Json {
+    Json::Array(changes.iter().map(|c| object(vec![
+        ("path", c.path.to_json()),
+        ("change", c.kind.as_str().to_json()),
+        ("breaking", c.breaking.to_json()),
+        ("description", c.description.to_json()),
+    ])).collect())
+}
+
+/// An item of the public API, as found in the JSON description of a crate.
+struct ApiItem<'a> {
+    kind: &'a str,
+    /// The path of the item this one is nested in, e.g. the struct of a field,
+    /// the `Self` type of a method or the trait impl of an associated type.
+    /// `None` for trait impls.
+    parent: Option<String>,
+    /// Whether the item is declared in a trait, rather than in a module or in
+    /// an inherent impl.
+    in_trait: bool,
+    item: &'a Json,
+}
+
+type ApiItems<'a> = BTreeMap<String, ApiItem<'a>>;
+
+/// Compares two crates written by `json::crate_to_json`.
+fn diff(old: &Json, new: &Json) -> Vec<Change> {
+    // Paths on both sides start with the name of the new crate, so that renaming
+    // the crate isn't reported as removing everything in it.
+    let crate_name = str_field(new, "name");
+    let old_items = collect_items(old, crate_name);
+    let new_items = collect_items(new, crate_name);
+
+    // The trait impls of removed types aren't reported on their own. Impls only
+    // know the name of their `Self` type, not its path.
+    let type_names = |items: &ApiItems<'_>| items.iter()
+        .filter(|&(_, item)| is_type(item.kind))
+        .filter_map(|(path, _)| path.rsplit("::").next().map(str::to_string))
+        .collect::<BTreeSet<_>>();
+    let removed_types = type_names(&old_items)
+        .difference(&type_names(&new_items))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let mut changes = Vec::new();
+    for (path, old_item) in &old_items {
+        let new_item = match new_items.get(path) {
+            Some(new_item) => new_item,
+            None => {
+                if let Some((breaking, description)) =
+                    describe_removal(old_item, &old_items, &new_items, &removed_types)
+                {
+                    changes.push(Change {
+                        path: path.clone(),
+                        kind: ChangeKind::Removed,
+                        breaking,
+                        description,
+                    });
+                }
+                continue;
+            }
+        };
+        for (breaking, description) in compare_items(old_item, new_item) {
+            changes.push(Change {
+                path: path.clone(),
+                kind: ChangeKind::Changed,
+                breaking,
+                description,
+            });
+        }
+    }
+
+    for (path, new_item) in &new_items {
+        if old_items.contains_key(path) {
+            continue;
+        }
+        let old_parent = match new_item.parent {
+            // Only the outermost added item is reported.
+            Some(ref parent) if new_items.contains_key(parent) => match old_items.get(parent) {
+                Some(old_parent) => Some(old_parent),
+                None => continue,
+            },
+            _ => None,
+        };
+        let (breaking, description) = describe_addition(new_item, old_parent);
+        changes.push(Change { path: path.clone(), kind: ChangeKind::Added, breaking, description });
+    }
+
+    changes.sort();
+    changes
+}
+
+fn collect_items<'a>(krate: &'a Json, crate_name: &str) -> ApiItems<'a> {
+    let mut items = BTreeMap::new();
+    let mut impls = Vec::new();
+    for item in array_field(field(field(krate, "root"), "inner"), "items") {
+        collect_item(item, crate_name, false, &mut items, &mut impls);
+    }
+
+    // The items of inherent impls are keyed by the path of their `Self` type,
+    // which can be anywhere in the crate, so impls come last.
+    let type_paths = items.iter()
+        .filter(|&(_, item)| is_type(item.kind))
+        .filter_map(|(path, item)| item.item.find("id")?.as_string().map(|id| (id, path.clone())))
+        .collect::<BTreeMap<_, _>>();
+    for (item, module) in impls {
+        collect_impl(item, &module, &type_paths, &mut items);
+    }
+    items
+}
+
+fn collect_item<'a>(
+    item: &'a Json,
+    parent: &str,
+    in_trait: bool,
+    items: &mut ApiItems<'a>,
+    impls: &mut Vec<(&'a Json, String)>,
+) {
+    let kind = str_field(item, "kind");
+    let inner = field(item, "inner");
+    let name = match kind {
+        "impl" => return impls.push((item, parent.to_string())),
+        // These only exist in the standard library, and have no API of their own.
+        "primitive" | "keyword" => return,
+        "import" if bool_field(inner, "glob") => format!("{}::*", str_field(inner, "source")),
+        "import" => str_field(inner, "name").to_string(),
+        _ => str_field(item, "name").to_string(),
+    };
+    let path = format!("{}::{}", parent, name);
+
+    let nested = match kind {
+        "mod" | "trait" => array_field(inner, "items"),
+        "struct" | "union" => array_field(inner, "fields"),
+        "enum" => array_field(inner, "variants"),
+        // The fields of tuple variants are just types, not items.
+        "variant" if str_field(inner, "variant_kind") == "struct" => array_field(inner, "fields"),
+        _ => &[],
+    };
+    for child in nested {
+        collect_item(child, &path, kind == "trait", items, impls);
+    }
+
+    items.insert(path, ApiItem { kind, parent: Some(parent.to_string()), in_trait, item });
+}
+
+/// Collects an impl found in `module`. `type_paths` maps the ids of the types
+/// of the crate to their paths.
+fn collect_impl<'a>(
+    item: &'a Json,
+    module: &str,
+    type_paths: &BTreeMap<&str, String>,
+    items: &mut ApiItems<'a>,
+) {
+    let inner = field(item, "inner");
+    let for_ = str_field(inner, "for");
+    // The path of the item the items of the impl are nested in, and the prefix
+    // of their paths.
+    let (parent, prefix) = match field(inner, "trait").as_string() {
+        Some(trait_) => {
+            let negative = bool_field(inner, "negative");
+            // A synthesized negative impl only spells out that an auto trait isn't
+            // implemented, which is what a missing impl means as well.
+            if negative && bool_field(inner, "synthetic") {
+                return;
+            }
+            let path = format!("impl {}{} for {}", if negative { "!" } else { "" }, trait_, for_);
+            let impl_item = ApiItem { kind: "impl", parent: None, in_trait: false, item };
+            items.insert(path.clone(), impl_item);
+            (path, format!("<{} as {}>", for_, trait_))
+        }
+        None => {
+            // Inherent impls aren't part of the API themselves, only their items
+            // are, as if they were nested in the `Self` type. Its path is only
+            // known if the `Self` type is part of the API.
+            let self_path = match type_paths.get(str_field(inner, "for_id")) {
+                Some(path) => path.clone(),
+                None => format!("{}::{}", module, type_name(for_)),
+            };
+            (self_path.clone(), self_path)
+        }
+    };
+
+    // Impls only contain functions, associated types and associated constants,
+    // none of which have items nested in them.
+    for child in array_field(inner, "items") {
+        items.insert(format!("{}::{}", prefix, str_field(child, "name")), ApiItem {
+            kind: str_field(child, "kind"),
+            parent: Some(parent.clone()),
+            in_trait: false,
+            item: child,
+        });
+    }
+}
+
+/// Describes `item`, which is missing from the new version, or returns `None`
+/// if its removal is already implied by another change. Also returns whether
+/// the removal is breaking.
+fn describe_removal(
+    item: &ApiItem<'_>,
+    old_items: &ApiItems<'_>,
+    new_items: &ApiItems<'_>,
+    removed_types: &BTreeSet<String>,
+) -> Option<(bool, String)> {
+    if let Some(ref parent) = item.parent {
+        if old_items.contains_key(parent) && !new_items.contains_key(parent) {
+            return None;
+        }
+        // Items can only be left out of a trait impl if the trait has a default
+        // for them, which the impl uses now.
+        if old_items.get(parent).map_or(false, |parent| parent.kind == "impl") {
+            return Some(if item.kind == "method" {
+                (false, "the impl now uses the default implementation of the trait".to_string())
+            } else {
+                (true, format!("the impl now uses the trait's default for the {}, which may be \
+                                different", kind_descr(item.kind)))
+            });
+        }
+    }
+
+    if item.kind == "impl" {
+        let inner = field(item.item, "inner");
+        if removed_types.contains(type_name(str_field(inner, "for"))) {
+            return None;
+        }
+        if bool_field(inner, "synthetic") {
+            return Some((true, "the auto trait is no longer implemented".to_string()));
+        }
+    }
+    Some((true, format!("{} removed", kind_descr(item.kind))))
+}
+
+/// Describes `item`, which is new in this version. `parent` is the previous
+/// version of the item it's nested in, if any.
+fn describe_addition(item: &ApiItem<'_>, parent: Option<&ApiItem<'_>>) -> (bool, String) {
+    let inner = field(item.item, "inner");
+    let descr = kind_descr(item.kind);
+    let exhaustive = |parent: &ApiItem<'_>| !bool_field(parent.item, "non_exhaustive");
+
+    match item.kind {
+        "tymethod" => {
+            (true, "new required method, existing impls of the trait don't provide it".to_string())
+        }
+        "associatedtype" | "associatedconstant"
+            if item.in_trait && field(inner, "default").is_null() =>
+        {
+            (true, format!("new {} without a default, existing impls of the trait don't \
+                            provide it", descr))
+        }
+        "variant" if parent.map_or(false, exhaustive) => {
+            (true, "new variant, exhaustive matches on the enum no longer compile".to_string())
+        }
+        "structfield" => match parent {
+            Some(p) if p.kind == "variant" ||
+                       (p.kind == "struct" && exhaustive(p) &&
+                        !bool_field(field(p.item, "inner"), "fields_stripped")) =>
+            {
+                (true, "new field, struct expressions and patterns naming all fields no longer \
+                        compile".to_string())
+            }
+            _ => (false, "field added".to_string()),
+        },
+        "impl" if bool_field(inner, "synthetic") => {
+            (false, "the auto trait is now implemented".to_string())
+        }
+        _ => (false, format!("{} added", descr)),
+    }
+}
+
+/// Returns the differences between two versions of the same item, with whether
+/// they are breaking.
+fn compare_items(old: &ApiItem<'_>, new: &ApiItem<'_>) -> Vec<(bool, String)> {
+    let mut changes = Vec::new();
+
+    if old.kind != new.kind {
+        changes.push(match (old.kind, new.kind) {
+            ("tymethod", "method") => (false, "now has a default implementation".to_string()),
+            ("method", "tymethod") => (true, "no longer has a default implementation".to_string()),
+            _ => (true, format!("changed from {} to {}", kind_descr(old.kind),
+                                kind_descr(new.kind))),
+        });
+        return changes;
+    }
+
+    let (old_inner, new_inner) = (field(old.item, "inner"), field(new.item, "inner"));
+    let changed = |name: &str| field(old_inner, name) != field(new_inner, name);
+    let from_to = |name: &str| {
+        format!("from `{}` to `{}`", display(field(old_inner, name)),
+                display(field(new_inner, name)))
+    };
+
+    match new.kind {
+        "fn" | "method" | "tymethod" => {
+            let (old_sig, new_sig) = (fn_signature(old_inner), fn_signature(new_inner));
+            if old_sig != new_sig {
+                changes.push((true, format!("signature changed from `{}` to `{}`",
+                                            old_sig, new_sig)));
+            }
+            let is_const = |inner: &Json| bool_field(field(inner, "header"), "is_const");
+            match (is_const(old_inner), is_const(new_inner)) {
+                (true, false) => changes.push((true, "no longer a `const fn`".to_string())),
+                (false, true) => changes.push((false, "now a `const fn`".to_string())),
+                _ => {}
+            }
+        }
+        "struct" | "union" => {
+            if changed("struct_type") {
+                changes.push((true, format!("changed {}", from_to("struct_type"))));
+            }
+            match (bool_field(old_inner, "fields_stripped"),
+                   bool_field(new_inner, "fields_stripped")) {
+                (false, true) => changes.push((true, "now has private fields, it can no longer \
+                                                      be constructed outside of the crate"
+                                                      .to_string())),
+                (true, false) => changes.push((false, "all fields are public now".to_string())),
+                _ => {}
+            }
+        }
+        "variant" => {
+            if changed("variant_kind") {
+                changes.push((true, format!("changed {}", from_to("variant_kind"))));
+            } else if changed("fields") && str_field(new_inner, "variant_kind") == "tuple" {
+                let fields = |inner: &Json| {
+                    array_field(inner, "fields").iter().map(display).collect::<Vec<_>>().join(", ")
+                };
+                changes.push((true, format!("fields changed from `({})` to `({})`",
+                                            fields(old_inner), fields(new_inner))));
+            }
+        }
+        "structfield" | "type" | "static" | "constant" | "associatedconstant" => {
+            if changed("type") {
+                changes.push((true, format!("type changed {}", from_to("type"))));
+            }
+            if changed("mutable") {
+                let description = if bool_field(new_inner, "mutable") {
+                    "now mutable"
+                } else {
+                    "no longer mutable"
+                };
+                changes.push((true, description.to_string()));
+            }
+            if new.kind == "constant" && changed("expr") {
+                changes.push((false, format!("value changed {}", from_to("expr"))));
+            }
+        }
+        "trait" => {
+            if changed("is_unsafe") {
+                let description = if bool_field(new_inner, "is_unsafe") {
+                    "now unsafe"
+                } else {
+                    "no longer unsafe"
+                };
+                changes.push((true, description.to_string()));
+            }
+            if changed("bounds") {
+                changes.push((true, format!("supertraits changed {}", from_to("bounds"))));
+            }
+        }
+        "associatedtype" | "opaque" | "traitalias" => {
+            if changed("bounds") {
+                changes.push((true, format!("bounds changed {}", from_to("bounds"))));
+            }
+            // Only associated types in impls have a type.
+            if changed("type") {
+                changes.push((true, format!("type changed {}", from_to("type"))));
+            }
+        }
+        "import" => {
+            if changed("source") {
+                changes.push((false, format!("re-export changed {}", from_to("source"))));
+            }
+        }
+        _ => {}
+    }
+
+    if new.in_trait && (new.kind == "associatedtype" || new.kind == "associatedconstant") {
+        match (field(old_inner, "default").is_null(), field(new_inner, "default").is_null()) {
+            (false, true) => changes.push((true, "no longer has a default".to_string())),
+            (true, false) => changes.push((false, "now has a default".to_string())),
+            _ => {}
+        }
+    } else if new.kind == "associatedconstant" && changed("default") {
+        // The value of an associated constant in an impl.
+        changes.push((false, format!("value changed {}", from_to("default"))));
+    }
+
+    if let (Some(old_generics), Some(new_generics)) =
+        (old_inner.find("generics"), new_inner.find("generics"))
+    {
+        compare_generics(old_generics, new_generics, new.in_trait, &mut changes);
+    }
+
+    match (bool_field(old.item, "non_exhaustive"), bool_field(new.item, "non_exhaustive")) {
+        (false, true) => changes.push((true, "now `#[non_exhaustive]`".to_string())),
+        (true, false) => changes.push((false, "no longer `#[non_exhaustive]`".to_string())),
+        _ => {}
+    }
+    if field(old.item, "deprecation").is_null() && !field(new.item, "deprecation").is_null() {
+        changes.push((false, "now deprecated".to_string()));
+    }
+
+    changes
+}
+
+/// Compares generic parameters and their bounds. Tightened bounds are always
+/// breaking, relaxed ones only for trait items, where impls that still have the
+/// stricter bounds stop compiling.
+fn compare_generics(old: &Json, new: &Json, in_trait: bool, changes: &mut Vec<(bool, String)>) {
+    let (old_params, new_params) = (generic_params(old), generic_params(new));
+    if old_params != new_params {
+        // Adding parameters with defaults at the end keeps all uses compiling.
+        let defaulted = new_params.len() > old_params.len() &&
+            new_params.iter().zip(&old_params).all(|(new, old)| new.0 == old.0) &&
+            new_params[old_params.len()..].iter().all(|&(_, has_default)| has_default);
+        let names = |params: &[(String, bool)]| {
+            params.iter().map(|p| &*p.0).collect::<Vec<_>>().join(", ")
+        };
+        changes.push((!defaulted, format!("generic parameters changed from `<{}>` to `<{}>`",
+                                          names(&old_params), names(&new_params))));
+    }
+
+    let (old_bounds, new_bounds) = (generic_bounds(old), generic_bounds(new));
+    if old_bounds == new_bounds {
+        return;
+    }
+    // A `?Sized` bound relaxes the implicit `Sized` one, so removing it tightens.
+    let is_relaxation = |bound: &&String| bound.contains(": ?");
+    let tightened = new_bounds.difference(&old_bounds).any(|b| !is_relaxation(&b)) ||
+        old_bounds.difference(&new_bounds).any(|b| is_relaxation(&b));
+    let join = |bounds: &BTreeSet<String>| if bounds.is_empty() {
+        "no bounds".to_string()
+    } else {
+        bounds.iter().cloned().collect::<Vec<_>>().join(", ")
+    };
+    let (breaking, verb) = if tightened {
+        (true, "tightened")
+    } else {
+        (in_trait, "relaxed")
+    };
+    changes.push((breaking, format!("bounds {} from `{}` to `{}`",
+                                    verb, join(&old_bounds), join(&new_bounds))));
+}
+
+/// The generic parameters, with whether they have a default. Parameters for
+/// `impl Trait` arguments are left out, they are part of the signature.
+fn generic_params(generics: &Json) -> Vec<(String, bool)> {
+    array_field(generics, "params").iter().filter_map(|param| {
+        let name = str_field(param, "name");
+        match str_field(param, "kind") {
+            "type" if bool_field(param, "synthetic") => None,
+            "type" => Some((name.to_string(), !field(param, "default").is_null())),
+            "const" => Some((format!("const {}: {}", name, str_field(param, "type")), false)),
+            _ => Some((name.to_string(), false)),
+        }
+    }).collect()
+}
+
+/// Every bound on a generic parameter or in the where clause, as `T: Bound`.
+fn generic_bounds(generics: &Json) -> BTreeSet<String> {
+    let mut bounds = BTreeSet::new();
+    for param in array_field(generics, "params") {
+        if bool_field(param, "synthetic") {
+            continue;
+        }
+        for bound in array_field(param, "bounds") {
+            bounds.insert(format!("{}: {}", str_field(param, "name"), display(bound)));
+        }
+    }
+    for pred in array_field(generics, "where_predicates") {
+        let lhs = match str_field(pred, "kind") {
+            "bound" => str_field(pred, "type"),
+            "region" => str_field(pred, "lifetime"),
+            _ => {
+                bounds.insert(format!("{} == {}", str_field(pred, "lhs"), str_field(pred, "rhs")));
+                continue;
+            }
+        };
+        for bound in array_field(pred, "bounds") {
+            bounds.insert(format!("{}: {}", lhs, display(bound)));
+        }
+    }
+    bounds
+}
+
+/// The signature of a function as a function pointer type, e.g.
+/// `unsafe fn(&Self, usize) -> bool`. Argument names and constness are left
+/// out, changing them doesn't break callers.
+fn fn_signature(inner: &Json) -> String {
+    let header = field(inner, "header");
+    let decl = field(inner, "decl");
+
+    let mut sig = String::new();
+    if bool_field(header, "is_async") {
+        sig.push_str("async ");
+    }
+    if bool_field(header, "is_unsafe") {
+        sig.push_str("unsafe ");
+    }
+    match str_field(header, "abi") {
+        "" | "Rust" => {}
+        abi => sig.push_str(&format!("extern \"{}\" ", abi)),
+    }
+
+    let mut inputs = array_field(decl, "inputs").iter()
+        .map(|arg| str_field(arg, "type"))
+        .collect::<Vec<_>>();
+    if bool_field(decl, "c_variadic") {
+        inputs.push("...");
+    }
+    sig.push_str(&format!("fn({})", inputs.join(", ")));
+    if let Some(output) = field(decl, "output").as_string() {
+        sig.push_str(" -> ");
+        sig.push_str(output);
+    }
+    sig
+}
+
+/// The name of `ty` without its generic arguments, e.g. `Foo` for `Foo<'a, T>`.
+fn type_name(ty: &str) -> &str {
+    ty.split('<').next().unwrap_or(ty).trim()
+}
+
+fn is_type(kind: &str) -> bool {
+    match kind {
+        "struct" | "union" | "enum" | "type" | "foreigntype" => true,
+        _ => false,
+    }
+}
+
+fn kind_descr(kind: &str) -> &str {
+    match kind {
+        "mod" => "module",
+        "externcrate" => "extern crate",
+        "import" => "re-export",
+        "fn" => "function",
+        "type" => "type alias",
+        "tymethod" => "required method",
+        "method" => "method",
+        "structfield" => "field",
+        "associatedtype" => "associated type",
+        "constant" => "constant",
+        "associatedconstant" => "associated constant",
+        "foreigntype" => "foreign type",
+        "opaque" => "opaque type",
+        "attr" => "attribute macro",
+        "derive" => "derive macro",
+        "traitalias" => "trait alias",
+        kind => kind,
+    }
+}
+
+/// Strings as they are, lists of bounds joined with `+`, and the rest as JSON.
+fn display(json: &Json) -> String {
+    match *json {
+        Json::String(ref s) => s.clone(),
+        Json::Array(ref bounds) => bounds.iter().map(display).collect::<Vec<_>>().join(" + "),
+        Json::Null => String::new(),
+        ref json => json.to_string(),
+    }
+}
+
+static NULL: Json = Json::Null;
+
+fn field<'a>(json: &'a Json, name: &str) -> &'a Json {
+    json.find(name).unwrap_or(&NULL)
+}
+
+fn str_field<'a>(json: &'a Json, name: &str) -> &'a str {
+    field(json, name).as_string().unwrap_or("")
+}
+
+fn bool_field(json: &Json, name: &str) -> bool {
+    field(json, name).as_boolean().unwrap_or(false)
+}
+
+fn array_field<'a>(json: &'a Json, name: &str) -> &'a [Json] {
+    field(json, name).as_array().map_or(&[], |items| &items[..])
+}
@@ -0,0 +1,266 @@
+use super::*;
+
+fn item(kind: &str, name: &str, inner: Vec<(&str, Json)>) -> Json {
+    object(vec![
+        ("kind", kind.to_json()),
+        ("name", name.to_json()),
+        ("deprecation", Json::Null),
+        ("inner", object(inner)),
+    ])
+}
+
+fn krate(items: Vec<Json>) -> Json {
+    object(vec![
+        ("format_version", FORMAT_VERSION.to_json()),
+        ("name", "foo".to_json()),
+        ("root", item("mod", "foo", vec![("items", Json::Array(items))])),
+    ])
+}
+
+fn generics(params: &[(&str, &[&str])]) -> Json {
+    let params = params.iter().map(|&(name, bounds)| object(vec![
+        ("name", name.to_json()),
+        ("kind", "type".to_json()),
+        ("bounds", Json::Array(bounds.iter().map(|b| b.to_json()).collect())),
+        ("default", Json::Null),
+        ("synthetic", false.to_json()),
+    ])).collect();
+    object(vec![
+        ("params", Json::Array(params)),
+        ("where_predicates", Json::Array(vec![])),
+    ])
+}
+
+fn function(kind: &str, name: &str, inputs: &[(&str, &str)], generics: Json) -> Json {
+    let inputs = inputs.iter().map(|&(name, ty)| object(vec![
+        ("name", name.to_json()),
+        ("type", ty.to_json()),
+    ])).collect();
+    item(kind, name, vec![
+        ("decl", object(vec![
+            ("inputs", Json::Array(inputs)),
+            ("output", Json::Null),
+            ("c_variadic", false.to_json()),
+        ])),
+        ("generics", generics),
+        ("header", object(vec![
+            ("is_unsafe", false.to_json()),
+            ("is_const", false.to_json()),
+            ("is_async", false.to_json()),
+            ("abi", "Rust".to_json()),
+        ])),
+    ])
+}
+
+fn structure(name: &str, fields: &[(&str, &str)]) -> Json {
+    let fields = fields.iter().map(|&(name, ty)| {
+        item("structfield", name, vec![("type", ty.to_json())])
+    }).collect();
+    item("struct", name, vec![
+        ("struct_type", "plain".to_json()),
+        ("generics", generics(&[])),
+        ("fields", Json::Array(fields)),
+        ("fields_stripped", false.to_json()),
+    ])
+}
+
+fn trait_(name: &str, items: Vec<Json>) -> Json {
+    item("trait", name, vec![
+        ("is_unsafe", false.to_json()),
+        ("generics", generics(&[])),
+        ("bounds", Json::Array(vec![])),
+        ("items", Json::Array(items)),
+    ])
+}
+
+fn auto_trait_impl(trait_: &str, for_: &str, negative: bool) -> Json {
+    object(vec![
+        ("kind", "impl".to_json()),
+        ("name", Json::Null),
+        ("inner", object(vec![
+            ("generics", generics(&[])),
+            ("trait", trait_.to_json()),
+            ("for", for_.to_json()),
+            ("negative", negative.to_json()),
+            ("synthetic", true.to_json()),
+            ("items", Json::Array(vec![])),
+        ])),
+    ])
+}
+
+fn impl_(trait_: Option<&str>, for_: &str, for_id: Option<&str>, items: Vec<Json>) -> Json {
+    object(vec![
+        ("kind", "impl".to_json()),
+        ("name", Json::Null),
+        ("inner", object(vec![
+            ("generics", generics(&[])),
+            ("trait", trait_.map_or(Json::Null, |t| t.to_json())),
+            ("for", for_.to_json()),
+            ("for_id", for_id.map_or(Json::Null, |id| id.to_json())),
+            ("negative", false.to_json()),
+            ("synthetic", false.to_json()),
+            ("items", Json::Array(items)),
+        ])),
+    ])
+}
+
+fn with_id(mut item: Json, id: &str) -> Json {
+    if let Json::Object(ref mut fields) = item {
+        fields.insert("id".to_string(), id.to_json());
+    }
+    item
+}
+
+fn changes(old: Vec<Json>, new: Vec<Json>) -> Vec<(&'static str, String, bool)> {
+    diff(&krate(old), &krate(new)).into_iter()
+        .map(|c| (c.kind.as_str(), c.path, c.breaking))
+        .collect()
+}
+
+#[test]
+fn unchanged_api() {
+    let api = || vec![
+        structure("Point", &[("x", "i32"), ("y", "i32")]),
+        function("fn", "origin", &[], generics(&[])),
+    ];
+    assert_eq!(changes(api(), api()), vec![]);
+}
+
+#[test]
+fn removed_items_are_breaking() {
+    let old = vec![
+        structure("Point", &[("x", "i32"), ("y", "i32")]),
+        function("fn", "origin", &[], generics(&[])),
+    ];
+    let new = vec![function("fn", "origin", &[], generics(&[]))];
+    // The fields are implied by the removed struct.
+    assert_eq!(changes(old, new), vec![("removed", "foo::Point".to_string(), true)]);
+}
+
+#[test]
+fn added_items() {
+    let old = vec![structure("Point", &[("x", "i32")])];
+    let new = vec![
+        structure("Point", &[("x", "i32"), ("y", "i32")]),
+        structure("Size", &[("w", "u32"), ("h", "u32")]),
+    ];
+    assert_eq!(changes(old, new), vec![
+        ("added", "foo::Point::y".to_string(), true),
+        ("added", "foo::Size".to_string(), false),
+    ]);
+}
+
+#[test]
+fn new_required_trait_method() {
+    let old = vec![trait_("Shape", vec![])];
+    let new = vec![trait_("Shape", vec![
+        function("tymethod", "area", &[("self", "&Self")], generics(&[])),
+        function("method", "name", &[("self", "&Self")], generics(&[])),
+    ])];
+    assert_eq!(changes(old, new), vec![
+        ("added", "foo::Shape::area".to_string(), true),
+        ("added", "foo::Shape::name".to_string(), false),
+    ]);
+}
+
+#[test]
+fn changed_signature() {
+    let old = vec![function("fn", "scale", &[("p", "Point"), ("by", "i32")], generics(&[]))];
+    let renamed = vec![function("fn", "scale", &[("point", "Point"), ("factor", "i32")],
+                                generics(&[]))];
+    let new = vec![function("fn", "scale", &[("p", "Point"), ("by", "f64")], generics(&[]))];
+
+    assert_eq!(changes(old.clone(), renamed), vec![]);
+    let diff = diff(&krate(old), &krate(new));
+    assert_eq!(diff.len(), 1);
+    assert!(diff[0].breaking);
+    assert_eq!(diff[0].description,
+               "signature changed from `fn(Point, i32)` to `fn(Point, f64)`");
+}
+
+#[test]
+fn tightened_and_relaxed_bounds() {
+    let with_bounds = |bounds: &[&str]| {
+        vec![function("fn", "print", &[("t", "T")], generics(&[("T", bounds)]))]
+    };
+    assert_eq!(changes(with_bounds(&["Debug"]), with_bounds(&["Debug", "Clone"])),
+               vec![("changed", "foo::print".to_string(), true)]);
+    assert_eq!(changes(with_bounds(&["Debug", "Clone"]), with_bounds(&["Debug"])),
+               vec![("changed", "foo::print".to_string(), false)]);
+    // Losing `?Sized` is a new implicit `Sized` bound.
+    assert_eq!(changes(with_bounds(&["?Sized"]), with_bounds(&[])),
+               vec![("changed", "foo::print".to_string(), true)]);
+}
+
+#[test]
+fn relaxed_bounds_on_trait_methods_are_breaking() {
+    let with_bounds = |bounds: &[&str]| {
+        vec![trait_("Shape", vec![
+            function("tymethod", "draw", &[("t", "T")], generics(&[("T", bounds)])),
+        ])]
+    };
+    assert_eq!(changes(with_bounds(&["Debug"]), with_bounds(&[])),
+               vec![("changed", "foo::Shape::draw".to_string(), true)]);
+}
+
+#[test]
+fn lost_auto_trait_impl() {
+    let old = vec![
+        structure("Handle", &[]),
+        auto_trait_impl("Send", "Handle", false),
+        auto_trait_impl("Sync", "Handle", false),
+    ];
+    let new = vec![
+        structure("Handle", &[]),
+        auto_trait_impl("Send", "Handle", true),
+        auto_trait_impl("Sync", "Handle", false),
+    ];
+    let diff = diff(&krate(old), &krate(new));
+    assert_eq!(diff, vec![Change {
+        path: "impl Send for Handle".to_string(),
+        kind: ChangeKind::Removed,
+        breaking: true,
+        description: "the auto trait is no longer implemented".to_string(),
+    }]);
+}
+
+#[test]
+fn impls_of_removed_types_are_implied() {
+    let old = vec![structure("Handle", &[]), auto_trait_impl("Send", "Handle", false)];
+    assert_eq!(changes(old, vec![]), vec![("removed", "foo::Handle".to_string(), true)]);
+}
+
+#[test]
+fn inherent_items_belong_to_their_type() {
+    // The impl is in the crate root, the type in a module.
+    let api = |methods: Vec<Json>| vec![
+        item("mod", "geom", vec![
+            ("items", Json::Array(vec![with_id(structure("Point", &[]), "0:1")])),
+        ]),
+        impl_(None, "Point", Some("0:1"), methods),
+    ];
+    let old = api(vec![function("method", "norm", &[("self", "&Self")], generics(&[]))]);
+    assert_eq!(changes(old, api(vec![])),
+               vec![("removed", "foo::geom::Point::norm".to_string(), true)]);
+}
+
+#[test]
+fn trait_impl_items() {
+    let api = |items: Vec<Json>| vec![
+        structure("Counter", &[]),
+        impl_(Some("Iterator"), "Counter", None, items),
+    ];
+    let assoc_type = |ty: &str| item("associatedtype", "Item", vec![("type", ty.to_json())]);
+    let next = || function("method", "next", &[("self", "&mut Self")], generics(&[]));
+    let size_hint = || function("method", "size_hint", &[("self", "&Self")], generics(&[]));
+
+    assert_eq!(changes(api(vec![assoc_type("u32"), next()]), api(vec![assoc_type("u64"), next()])),
+               vec![("changed", "<Counter as Iterator>::Item".to_string(), true)]);
+    // Overriding a provided method, or no longer doing so, keeps users compiling.
+    assert_eq!(changes(api(vec![assoc_type("u32"), next(), size_hint()]),
+                       api(vec![assoc_type("u32"), next()])),
+               vec![("removed", "<Counter as Iterator>::size_hint".to_string(), false)]);
+    assert_eq!(changes(api(vec![assoc_type("u32"), next()]),
+                       api(vec![assoc_type("u32"), next(), size_hint()])),
+               vec![("added", "<Counter as Iterator>::size_hint".to_string(), false)]);
+}
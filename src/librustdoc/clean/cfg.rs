@@ -138,7 +138,7 @@ impl Cfg {
 
     /// Renders the configuration for human display, as a short HTML description.
     pub(crate) fn render_short_html(&self) -> String {
-        let mut msg = Html(self, true).to_string();
+        let mut msg = Description(self, Format::ShortHtml).to_string();
         if self.should_capitalize_first_letter() {
             if let Some(i) = msg.find(|c: char| c.is_ascii_alphanumeric()) {
                 msg[i .. i+1].make_ascii_uppercase();
@@ -155,7 +155,26 @@ impl Cfg {
             "on"
         };
 
-        let mut msg = format!("This is supported {} <strong>{}</strong>", on, Html(self, false));
+        let mut msg = format!("This is supported {} <strong>{}</strong>",
+                              on, Description(self, Format::LongHtml));
+        if self.should_append_only_to_description() {
+            msg.push_str(" only");
+        }
+        msg.push('.');
+        msg
+    }
+
+    /// Renders the configuration for long display, as a Markdown description. This is what the
+    /// text backends show in place of `render_long_html`.
+    pub(crate) fn render_long_markdown(&self) -> String {
+        let on = if self.should_use_with_in_description() {
+            "with"
+        } else {
+            "on"
+        };
+
+        let mut msg = format!("This is supported {} **{}**",
+                              on, Description(self, Format::LongMarkdown));
         if self.should_append_only_to_description() {
             msg.push_str(" only");
         }
@@ -265,9 +284,15 @@ impl ops::BitOr for Cfg {
     }
 }
 
-/// Pretty-print wrapper for a `Cfg`. Also indicates whether the "short-form" rendering should be
-/// used.
-struct Html<'a>(&'a Cfg, bool);
+/// Pretty-print wrapper for a `Cfg`, along with the form of the rendering.
+struct Description<'a>(&'a Cfg, Format);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    ShortHtml,
+    LongHtml,
+    LongMarkdown,
+}
 
 fn write_with_opt_paren<T: fmt::Display>(
     fmt: &mut fmt::Formatter<'_>,
@@ -285,7 +310,7 @@ fn write_with_opt_paren<T: fmt::Display>(
 }
 
 
-impl<'a> fmt::Display for Html<'a> {
+impl<'a> fmt::Display for Description<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self.0 {
             Cfg::Not(ref child) => match **child {
@@ -297,12 +322,12 @@ impl<'a> fmt::Display for Html<'a> {
                     };
                     for (i, sub_cfg) in sub_cfgs.iter().enumerate() {
                         fmt.write_str(if i == 0 { "neither " } else { separator })?;
-                        write_with_opt_paren(fmt, !sub_cfg.is_all(), Html(sub_cfg, self.1))?;
+                        write_with_opt_paren(fmt, !sub_cfg.is_all(), Description(sub_cfg, self.1))?;
                     }
                     Ok(())
                 }
-                ref simple @ Cfg::Cfg(..) => write!(fmt, "non-{}", Html(simple, self.1)),
-                ref c => write!(fmt, "not ({})", Html(c, self.1)),
+                ref simple @ Cfg::Cfg(..) => write!(fmt, "non-{}", Description(simple, self.1)),
+                ref c => write!(fmt, "not ({})", Description(c, self.1)),
             },
 
             Cfg::Any(ref sub_cfgs) => {
@@ -315,7 +340,7 @@ impl<'a> fmt::Display for Html<'a> {
                     if i != 0 {
                         fmt.write_str(separator)?;
                     }
-                    write_with_opt_paren(fmt, !sub_cfg.is_all(), Html(sub_cfg, self.1))?;
+                    write_with_opt_paren(fmt, !sub_cfg.is_all(), Description(sub_cfg, self.1))?;
                 }
                 Ok(())
             },
@@ -325,7 +350,7 @@ impl<'a> fmt::Display for Html<'a> {
                     if i != 0 {
                         fmt.write_str(" and ")?;
                     }
-                    write_with_opt_paren(fmt, !sub_cfg.is_simple(), Html(sub_cfg, self.1))?;
+                    write_with_opt_paren(fmt, !sub_cfg.is_simple(), Description(sub_cfg, self.1))?;
                 }
                 Ok(())
             },
@@ -393,15 +418,24 @@ impl<'a> fmt::Display for Html<'a> {
                     ("target_endian", Some(endian)) => return write!(fmt, "{}-endian", endian),
                     ("target_pointer_width", Some(bits)) => return write!(fmt, "{}-bit", bits),
                     ("target_feature", Some(feat)) =>
-                        if self.1 {
-                            return write!(fmt, "<code>{}</code>", feat);
-                        } else {
-                            return write!(fmt, "target feature <code>{}</code>", feat);
+                        match self.1 {
+                            Format::ShortHtml => return write!(fmt, "<code>{}</code>", feat),
+                            Format::LongHtml => {
+                                return write!(fmt, "target feature <code>{}</code>", feat);
+                            }
+                            Format::LongMarkdown => {
+                                return write!(fmt, "target feature `{}`", feat);
+                            }
                         },
                     _ => "",
                 };
                 if !human_readable.is_empty() {
                     fmt.write_str(human_readable)
+                } else if self.1 == Format::LongMarkdown {
+                    match value {
+                        Some(v) => write!(fmt, "`{}=\"{}\"`", n, v),
+                        None => write!(fmt, "`{}`", n),
+                    }
                 } else if let Some(v) = value {
                     write!(fmt, "<code>{}=\"{}\"</code>", Escape(n), Escape(&*v.as_str()))
                 } else {
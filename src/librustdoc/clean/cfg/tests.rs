@@ -410,3 +410,29 @@ fn test_render_long_html() {
         );
     })
 }
+
+#[test]
+fn test_render_long_markdown() {
+    with_default_globals(|| {
+        assert_eq!(
+            word_cfg("unix").render_long_markdown(),
+            "This is supported on **Unix** only."
+        );
+        assert_eq!(
+            (word_cfg("unix") | !word_cfg("windows")).render_long_markdown(),
+            "This is supported on **Unix or non-Windows** only."
+        );
+        assert_eq!(
+            word_cfg("foo").render_long_markdown(),
+            "This is supported on **`foo`** only."
+        );
+        assert_eq!(
+            name_value_cfg("feature", "std").render_long_markdown(),
+            "This is supported on **`feature=\"std\"`** only."
+        );
+        assert_eq!(
+            name_value_cfg("target_feature", "sse2").render_long_markdown(),
+            "This is supported with **target feature `sse2`** only."
+        );
+    })
+}
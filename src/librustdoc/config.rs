@@ -85,6 +85,12 @@ pub struct Options {
     /// For example, using ignore-foo to ignore running the doctest on any target that
     /// contains "foo" as a substring
     pub enable_per_target_ignores: bool,
+    /// Whether to compile doctests that don't need to be compiled on their own into a single
+    /// binary per edition, instead of one binary per doctest.
+    pub merge_doctests: bool,
+    /// Whether to type-check the doctests marked `ignore` while the others run, and report the
+    /// ones that compile.
+    pub check_ignored_doctests: bool,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from $sysroot/bin/rustc.
@@ -106,6 +112,20 @@ pub struct Options {
     /// Whether to run the `calculate-doc-coverage` pass, which counts the number of public items
     /// with and without documentation.
     pub show_coverage: bool,
+    /// The minimum percentage of documented items required by `--show-coverage`. If coverage is
+    /// below it, rustdoc reports an error.
+    pub coverage_threshold: Option<f64>,
+    /// A JSON file written by `--output-format json` for a previous version of the crate. If
+    /// given, the public API is compared with it instead of generating documentation.
+    pub api_diff: Option<PathBuf>,
+    /// If given, the calls that the crate makes to the crates named by `scrape_examples_targets`
+    /// are written to this file instead of generating documentation.
+    pub scrape_examples_output: Option<PathBuf>,
+    /// The crates whose functions `scrape_examples_output` records the calls to.
+    pub scrape_examples_targets: Vec<String>,
+    /// Files written by `--scrape-examples-output-path` for the crate's examples. The calls in
+    /// them are shown on the pages of the functions they call.
+    pub with_examples: Vec<PathBuf>,
 
     // Options that alter generated documentation pages
 
@@ -150,20 +170,48 @@ impl fmt::Debug for Options {
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
             .field("show_coverage", &self.show_coverage)
+            .field("coverage_threshold", &self.coverage_threshold)
+            .field("api_diff", &self.api_diff)
+            .field("scrape_examples_output", &self.scrape_examples_output)
+            .field("scrape_examples_targets", &self.scrape_examples_targets)
+            .field("with_examples", &self.with_examples)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .field("runtool", &self.runtool)
             .field("runtool_args", &self.runtool_args)
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
+            .field("merge_doctests", &self.merge_doctests)
+            .field("check_ignored_doctests", &self.check_ignored_doctests)
             .finish()
     }
 }
 
+/// The kind of documentation to generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// HTML pages, rendered by `html::render`.
+    Html,
+    /// A machine-readable description of the crate, written by `json`.
+    Json,
+    /// Man pages, written by `text`.
+    Man,
+    /// CommonMark files, written by `text`.
+    Markdown,
+}
+
+impl Default for OutputFormat {
+    fn default() -> OutputFormat {
+        OutputFormat::Html
+    }
+}
+
 /// Configuration options for the HTML page-creation process.
 #[derive(Clone, Debug)]
 pub struct RenderOptions {
     /// Output directory to generate docs into. Defaults to `doc`.
     pub output: PathBuf,
+    /// The kind of documentation to generate. Defaults to HTML.
+    pub output_format: OutputFormat,
     /// External files to insert into generated pages.
     pub external_html: ExternalHtml,
     /// A pre-populated `IdMap` with the default headings and any headings added by Markdown files
@@ -219,6 +267,10 @@ pub struct RenderOptions {
     pub generate_search_filter: bool,
     /// Option (disabled by default) to generate files used by RLS and some other tools.
     pub generate_redirect_pages: bool,
+    /// Whether identifiers in the source pages link to their definitions.
+    pub generate_link_to_definition: bool,
+    /// Whether the search index includes the words of the documentation.
+    pub full_text_search: bool,
 }
 
 impl Options {
@@ -413,13 +465,25 @@ impl Options {
             }
         }
 
-        match matches.opt_str("w").as_ref().map(|s| &**s) {
-            Some("html") | None => {}
+        let output_format = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("html") | None => OutputFormat::Html,
+            Some(s @ "json") | Some(s @ "man") | Some(s @ "markdown") => {
+                if !nightly_options::is_unstable_enabled(matches) {
+                    diag.struct_err(&format!("the `-Z unstable-options` flag must also be passed \
+                                              to enable `--output-format {}`", s)).emit();
+                    return Err(1);
+                }
+                match s {
+                    "json" => OutputFormat::Json,
+                    "man" => OutputFormat::Man,
+                    _ => OutputFormat::Markdown,
+                }
+            }
             Some(s) => {
                 diag.struct_err(&format!("unknown output format: {}", s)).emit();
                 return Err(1);
             }
-        }
+        };
 
         let index_page = matches.opt_str("index-page").map(|s| PathBuf::from(&s));
         if let Some(ref index_page) = index_page {
@@ -440,10 +504,39 @@ impl Options {
         });
 
         let show_coverage = matches.opt_present("show-coverage");
+        let coverage_threshold = match matches.opt_str("coverage-threshold") {
+            Some(s) => match s.parse::<f64>() {
+                Ok(threshold) if threshold >= 0.0 && threshold <= 100.0 => Some(threshold),
+                _ => {
+                    diag.struct_err(&format!("`--coverage-threshold` must be a percentage \
+                                              between 0 and 100, found `{}`", s)).emit();
+                    return Err(1);
+                }
+            },
+            None => None,
+        };
+        if coverage_threshold.is_some() && !show_coverage {
+            diag.struct_err("`--coverage-threshold` requires `--show-coverage`").emit();
+            return Err(1);
+        }
         let document_private = matches.opt_present("document-private-items");
 
+        let scrape_examples_output = matches.opt_str("scrape-examples-output-path")
+                                            .map(PathBuf::from);
+        let scrape_examples_targets = matches.opt_strs("scrape-examples-target-crate");
+        if scrape_examples_output.is_some() && scrape_examples_targets.is_empty() {
+            diag.struct_err("`--scrape-examples-output-path` requires \
+                             `--scrape-examples-target-crate`").emit();
+            return Err(1);
+        }
+        let with_examples = matches.opt_strs("with-examples").into_iter()
+                                   .map(PathBuf::from).collect();
+
         let default_passes = if matches.opt_present("no-defaults") {
             passes::DefaultPassOption::None
+        } else if scrape_examples_output.is_some() {
+            // Scraping only needs the type context, not the documentation.
+            passes::DefaultPassOption::None
         } else if show_coverage && document_private {
             passes::DefaultPassOption::PrivateCoverage
         } else if show_coverage {
@@ -480,6 +573,8 @@ impl Options {
         let generate_search_filter = !matches.opt_present("disable-per-crate-search");
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let generate_redirect_pages = matches.opt_present("generate-redirect-pages");
+        let generate_link_to_definition = matches.opt_present("generate-link-to-definition");
+        let full_text_search = matches.opt_present("full-text-search");
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
         let lib_strs = matches.opt_strs("L");
@@ -487,6 +582,9 @@ impl Options {
         let runtool = matches.opt_str("runtool");
         let runtool_args = matches.opt_strs("runtool-arg");
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
+        let merge_doctests = matches.opt_present("merge-doctests");
+        let check_ignored_doctests = matches.opt_present("check-ignored-doctests");
+        let api_diff = matches.opt_str("api-diff").map(PathBuf::from);
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -515,14 +613,22 @@ impl Options {
             manual_passes,
             display_warnings,
             show_coverage,
+            coverage_threshold,
+            api_diff,
+            scrape_examples_output,
+            scrape_examples_targets,
+            with_examples,
             crate_version,
             persist_doctests,
             runtool,
             runtool_args,
             enable_per_target_ignores,
+            merge_doctests,
+            check_ignored_doctests,
             test_builder,
             render_options: RenderOptions {
                 output,
+                output_format,
                 external_html,
                 id_map,
                 playground_url,
@@ -540,6 +646,8 @@ impl Options {
                 markdown_playground_url,
                 generate_search_filter,
                 generate_redirect_pages,
+                generate_link_to_definition,
+                full_text_search,
             }
         })
     }
@@ -555,7 +663,6 @@ impl Options {
 fn check_deprecated_options(matches: &getopts::Matches, diag: &errors::Handler) {
     let deprecated_flags = [
        "input-format",
-       "output-format",
        "no-defaults",
        "passes",
     ];
@@ -21,6 +21,7 @@ use syntax::json::JsonEmitter;
 use syntax::symbol::sym;
 use errors;
 use errors::emitter::{Emitter, EmitterWriter};
+use errors::sarif_emitter::SarifEmitter;
 
 use std::cell::RefCell;
 use std::mem;
@@ -30,9 +31,10 @@ use std::rc::Rc;
 use crate::config::{Options as RustdocOptions, RenderOptions};
 use crate::clean;
 use crate::clean::{MAX_DEF_ID, AttributesExt};
-use crate::html::render::RenderInfo;
+use crate::html::render::{span_map, RenderInfo};
 
 use crate::passes;
+use crate::scrape_examples;
 
 pub use rustc::session::config::{Input, Options, CodegenOptions};
 pub use rustc::session::search_paths::SearchPath;
@@ -210,6 +212,9 @@ pub fn new_handler(error_format: ErrorOutputType,
                 ).ui_testing(ui_testing)
             )
         },
+        ErrorOutputType::Sarif { pretty } => {
+            Box::new(SarifEmitter::stderr(source_map.map(|cm| cm as _), pretty))
+        },
     };
 
     errors::Handler::with_emitter_and_flags(
@@ -246,6 +251,10 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         mut default_passes,
         mut manual_passes,
         display_warnings,
+        coverage_threshold,
+        scrape_examples_output,
+        scrape_examples_targets,
+        with_examples,
         render_options,
         ..
     } = options;
@@ -258,6 +267,7 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
     let missing_docs = rustc_lint::builtin::MISSING_DOCS.name;
     let missing_doc_example = rustc_lint::builtin::MISSING_DOC_CODE_EXAMPLES.name;
     let private_doc_tests = rustc_lint::builtin::PRIVATE_DOC_TESTS.name;
+    let untested_doc_examples = rustc_lint::builtin::UNTESTED_DOC_CODE_EXAMPLES.name;
 
     // In addition to those specific lints, we also need to whitelist those given through
     // command line, otherwise they'll get ignored and we don't want that.
@@ -265,7 +275,8 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                                      intra_link_resolution_failure_name.to_owned(),
                                      missing_docs.to_owned(),
                                      missing_doc_example.to_owned(),
-                                     private_doc_tests.to_owned()];
+                                     private_doc_tests.to_owned(),
+                                     untested_doc_examples.to_owned()];
 
     whitelisted_lints.extend(lint_opts.iter().map(|(lint, _)| lint).cloned());
 
@@ -316,7 +327,9 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         target_triple: target,
         // Ensure that rustdoc works even if rustc is feature-staged
         unstable_features: UnstableFeatures::Allow,
-        actually_rustdoc: true,
+        // Scraping examples needs the bodies of their functions, so the example crate is checked
+        // like rustc would rather than with its bodies replaced.
+        actually_rustdoc: scrape_examples_output.is_none(),
         debugging_opts: debugging_options,
         error_format,
         edition,
@@ -369,6 +382,8 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
 
             let mut renderinfo = RenderInfo::default();
             renderinfo.access_levels = access_levels;
+            renderinfo.output_format = render_options.output_format;
+            renderinfo.coverage_threshold = coverage_threshold;
 
             let mut ctxt = DocContext {
                 tcx,
@@ -460,6 +475,19 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 krate = (pass.pass)(krate, &ctxt);
             }
 
+            if let Some(ref output) = scrape_examples_output {
+                scrape_examples::scrape(&ctxt, &scrape_examples_targets, output);
+            }
+            if !with_examples.is_empty() {
+                let examples = scrape_examples::load(&ctxt, &with_examples);
+                ctxt.renderinfo.borrow_mut().scraped_examples = examples;
+            }
+
+            if render_options.generate_link_to_definition {
+                let span_map = span_map::collect(&ctxt);
+                ctxt.renderinfo.borrow_mut().span_map = span_map;
+            }
+
             ctxt.sess().abort_if_errors();
 
             (krate, ctxt.renderinfo.into_inner(), render_options)
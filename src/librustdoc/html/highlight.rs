@@ -7,6 +7,7 @@
 
 use crate::html::escape::Escape;
 
+use rustc_data_structures::fx::FxHashMap;
 use std::fmt::Display;
 use std::io;
 use std::io::prelude::*;
@@ -16,7 +17,7 @@ use syntax::parse::lexer;
 use syntax::parse::token::{self, Token};
 use syntax::parse;
 use syntax::symbol::{kw, sym};
-use syntax_pos::{Span, FileName};
+use syntax_pos::{BytePos, Span, FileName};
 
 /// Highlights `src`, returning the HTML output.
 pub fn render_with_highlighting(
@@ -24,6 +25,22 @@ pub fn render_with_highlighting(
     class: Option<&str>,
     extension: Option<&str>,
     tooltip: Option<(&str, &str)>,
+) -> String {
+    render(src, class, extension, tooltip, &FxHashMap::default())
+}
+
+/// Highlights the source file `src`, returning the HTML output. The tokens whose byte range in
+/// `src` is a key of `links` link to the corresponding URL.
+pub fn render_source_with_links(src: &str, links: &FxHashMap<(u32, u32), String>) -> String {
+    render(src, None, None, None, links)
+}
+
+fn render(
+    src: &str,
+    class: Option<&str>,
+    extension: Option<&str>,
+    tooltip: Option<(&str, &str)>,
+    links: &FxHashMap<(u32, u32), String>,
 ) -> String {
     debug!("highlighting: ================\n{}\n==============", src);
     let mut out = Vec::new();
@@ -39,8 +56,9 @@ pub fn render_with_highlighting(
         src.to_owned(),
     );
     let highlight_result = {
+        let file_start = fm.start_pos;
         let lexer = lexer::StringReader::new(&sess, fm, None);
-        let mut classifier = Classifier::new(lexer, sess.source_map());
+        let mut classifier = Classifier::new(lexer, sess.source_map(), file_start, links);
 
         let mut highlighted_source = vec![];
         if classifier.write_source(&mut highlighted_source).is_err() {
@@ -76,6 +94,10 @@ struct Classifier<'a> {
     lexer: lexer::StringReader<'a>,
     peek_token: Option<Token>,
     source_map: &'a SourceMap,
+    /// The position of the start of the highlighted source in `source_map`.
+    file_start: BytePos,
+    /// The URLs that tokens link to, keyed by their byte range in the highlighted source.
+    links: &'a FxHashMap<(u32, u32), String>,
 
     // State of the classifier.
     in_attribute: bool,
@@ -135,6 +157,13 @@ trait Writer {
                           text: T,
                           klass: Class)
                           -> io::Result<()>;
+
+    /// Called for a span of text that links to `href`. It is highlighted like `string` does.
+    fn link<T: Display>(&mut self,
+                        text: T,
+                        klass: Class,
+                        href: &str)
+                        -> io::Result<()>;
 }
 
 // Implement `Writer` for anthing that can be written to, this just implements
@@ -150,6 +179,16 @@ impl<U: Write> Writer for U {
         }
     }
 
+    fn link<T: Display>(&mut self,
+                        text: T,
+                        klass: Class,
+                        href: &str)
+                        -> io::Result<()> {
+        write!(self, "<a href=\"{}\">", Escape(href))?;
+        self.string(text, klass)?;
+        write!(self, "</a>")
+    }
+
     fn enter_span(&mut self, klass: Class) -> io::Result<()> {
         write!(self, "<span class=\"{}\">", klass.rustdoc_class())
     }
@@ -171,11 +210,17 @@ impl From<io::Error> for HighlightError {
 }
 
 impl<'a> Classifier<'a> {
-    fn new(lexer: lexer::StringReader<'a>, source_map: &'a SourceMap) -> Classifier<'a> {
+    fn new(lexer: lexer::StringReader<'a>,
+           source_map: &'a SourceMap,
+           file_start: BytePos,
+           links: &'a FxHashMap<(u32, u32), String>)
+           -> Classifier<'a> {
         Classifier {
             lexer,
             peek_token: None,
             source_map,
+            file_start,
+            links,
             in_attribute: false,
             in_macro: false,
             in_macro_nonterminal: false,
@@ -371,7 +416,12 @@ impl<'a> Classifier<'a> {
 
         // Anything that didn't return above is the simple case where we the
         // class just spans a single token, so we can use the `string` method.
-        out.string(Escape(&self.snip(token.span)), klass)?;
+        let BytePos(lo) = token.span.lo() - self.file_start;
+        let BytePos(hi) = token.span.hi() - self.file_start;
+        match self.links.get(&(lo, hi)) {
+            Some(href) => out.link(Escape(&self.snip(token.span)), klass, href)?,
+            None => out.string(Escape(&self.snip(token.span)), klass)?,
+        }
 
         Ok(())
     }
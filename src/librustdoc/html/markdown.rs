@@ -21,6 +21,7 @@
 
 use rustc_data_structures::fx::FxHashMap;
 use std::cell::RefCell;
+use std::cmp;
 use std::collections::VecDeque;
 use std::default::Default;
 use std::fmt::Write;
@@ -854,6 +855,120 @@ pub fn plain_summary_line(md: &str) -> String {
     s
 }
 
+/// Returns the text of the Markdown `md`, including inline code but not code blocks.
+pub fn plain_text(md: &str) -> String {
+    let mut s = String::with_capacity(md.len());
+    let mut in_code_block = false;
+    for event in Parser::new(md) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(ref text) | Event::Code(ref text) if !in_code_block => {
+                s.push_str(text);
+                s.push(' ');
+            }
+            _ => {}
+        }
+    }
+    s
+}
+
+/// Parses `md` with the same extensions as the HTML output, for the backends that render the
+/// events some other way.
+crate fn events(md: &str) -> impl Iterator<Item = Event<'_>> {
+    Parser::new_ext(md, opts())
+}
+
+/// Rewrites the docs `md` as plain CommonMark for the text backends:
+///
+/// * the intra-doc links in `links` are replaced with their destinations; shortcut links like
+///   `` [`Vec`] `` get a link reference definition at the end;
+/// * the hidden lines of Rust code blocks are removed, and the blocks are tagged as `rust`;
+/// * headings are moved `heading_shift` levels down, so that they nest under the headings of the
+///   page the docs are written to.
+crate fn text_markdown(md: &str, links: &[(String, String)], heading_shift: usize) -> String {
+    let mut replacements = vec![];
+    let mut definitions = vec![];
+    for (ori, range) in markdown_links(md) {
+        let dest = match links.iter().find(|link| link.0 == ori) {
+            Some(&(_, ref dest)) => dest,
+            None => continue,
+        };
+        match range {
+            Some(range) if md[..range.start].ends_with('[') => {
+                let definition = format!("[{}]: {}", ori, dest);
+                if !definitions.contains(&definition) {
+                    definitions.push(definition);
+                }
+            }
+            Some(range) => replacements.push((range, dest)),
+            None => {}
+        }
+    }
+    replacements.sort_by_key(|&(ref range, _)| range.start);
+    replacements.dedup_by_key(|&mut (ref range, _)| range.start);
+
+    let mut linked = String::with_capacity(md.len());
+    let mut last = 0;
+    for (range, dest) in replacements {
+        linked.push_str(&md[last..range.start]);
+        linked.push_str(dest);
+        last = range.end;
+    }
+    linked.push_str(&md[last..]);
+
+    let mut out = String::with_capacity(linked.len());
+    // The fence of the code block the line is in, and whether the block is Rust code.
+    let mut fence: Option<(String, bool)> = None;
+    for line in linked.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let fence_len = trimmed.chars().take_while(|&c| c == '`' || c == '~').count();
+        match fence {
+            Some((ref open, _)) if fence_len >= open.len() &&
+                                   trimmed.starts_with(&open[..]) &&
+                                   trimmed[fence_len..].trim().is_empty() => {
+                fence = None;
+                out.push_str(line);
+            }
+            Some((_, true)) => match map_line(line) {
+                Line::Shown(l) => out.push_str(&l),
+                Line::Hidden(_) => continue,
+            },
+            Some((_, false)) => out.push_str(line),
+            None if fence_len >= 3 &&
+                    trimmed[..fence_len].chars().all(|c| c == trimmed.as_bytes()[0] as char) => {
+                let info = trimmed[fence_len..].trim();
+                let rust = LangString::parse(info, ErrorCodes::No, false).rust;
+                fence = Some((trimmed[..fence_len].to_owned(), rust));
+                out.push_str(&line[..indent + fence_len]);
+                out.push_str(if rust { "rust" } else { info });
+            }
+            None if indent < 4 && trimmed.starts_with('#') => {
+                let level = trimmed.chars().take_while(|&c| c == '#').count();
+                if level <= 6 && trimmed[level..].chars().next().map_or(true, char::is_whitespace) {
+                    out.push_str(&line[..indent]);
+                    out.push_str(&"#".repeat(cmp::min(level + heading_shift, 6)));
+                    out.push_str(&trimmed[level..]);
+                } else {
+                    out.push_str(line);
+                }
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+
+    if !definitions.is_empty() {
+        out.push('\n');
+        for definition in definitions {
+            out.push_str(&definition);
+            out.push('\n');
+        }
+    }
+    out
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -906,7 +1021,7 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
 }
 
 #[derive(Debug)]
-crate struct RustCodeBlock {
+crate struct CodeBlock {
     /// The range in the markdown that the code block occupies. Note that this includes the fences
     /// for fenced code blocks.
     pub range: Range<usize>,
@@ -914,11 +1029,20 @@ crate struct RustCodeBlock {
     pub code: Range<usize>,
     pub is_fenced: bool,
     pub syntax: Option<String>,
+    /// What the info string of the block says about it, like whether it's Rust or `ignore`d.
+    pub lang_string: LangString,
 }
 
 /// Returns a range of bytes for each code block in the markdown that is tagged as `rust` or
 /// untagged (and assumed to be rust).
-crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
+crate fn rust_code_blocks(md: &str) -> Vec<CodeBlock> {
+    let mut code_blocks = code_blocks(md);
+    code_blocks.retain(|code_block| code_block.lang_string.rust);
+    code_blocks
+}
+
+/// Returns a range of bytes for each code block in the markdown, whatever its language.
+crate fn code_blocks(md: &str) -> Vec<CodeBlock> {
     let mut code_blocks = vec![];
 
     if md.is_empty() {
@@ -931,36 +1055,32 @@ crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
     let mut code_start = 0;
     let mut is_fenced = false;
     let mut previous_offset = 0;
-    let mut in_rust_code_block = false;
+    let mut lang_string = None;
     while let Some(event) = p.next() {
         let offset = p.get_offset();
 
         match event {
             Event::Start(Tag::CodeBlock(syntax)) => {
-                let lang_string = if syntax.is_empty() {
+                lang_string = Some(if syntax.is_empty() {
                     LangString::all_false()
                 } else {
                     LangString::parse(&*syntax, ErrorCodes::Yes, false)
-                };
-
-                if lang_string.rust {
-                    in_rust_code_block = true;
+                });
 
-                    code_start = offset;
-                    code_block_start = match md[previous_offset..offset].find("```") {
-                        Some(fence_idx) => {
-                            is_fenced = true;
-                            previous_offset + fence_idx
-                        }
-                        None => {
-                            is_fenced = false;
-                            offset
-                        }
-                    };
-                }
+                code_start = offset;
+                code_block_start = match md[previous_offset..offset].find("```") {
+                    Some(fence_idx) => {
+                        is_fenced = true;
+                        previous_offset + fence_idx
+                    }
+                    None => {
+                        is_fenced = false;
+                        offset
+                    }
+                };
             }
-            Event::End(Tag::CodeBlock(syntax)) if in_rust_code_block => {
-                in_rust_code_block = false;
+            Event::End(Tag::CodeBlock(syntax)) if lang_string.is_some() => {
+                let lang_string = lang_string.take().expect("a code block was started");
 
                 let code_block_end = if is_fenced {
                     let fence_str = &md[previous_offset..offset]
@@ -988,7 +1108,7 @@ crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
                     code_block_end
                 };
 
-                code_blocks.push(RustCodeBlock {
+                code_blocks.push(CodeBlock {
                     is_fenced,
                     range: Range {
                         start: code_block_start,
@@ -1003,6 +1123,7 @@ crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
                     } else {
                         None
                     },
+                    lang_string,
                 });
             }
             _ => (),
@@ -1,5 +1,5 @@
 use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap, Ignore};
-use super::plain_summary_line;
+use super::{plain_summary_line, text_markdown};
 use std::cell::RefCell;
 use syntax::edition::{Edition, DEFAULT_EDITION};
 
@@ -145,3 +145,23 @@ fn test_markdown_html_escape() {
     t("Struct<'a, T>", "<p>Struct&lt;'a, T&gt;</p>\n");
     t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
 }
+
+#[test]
+fn test_text_markdown() {
+    fn t(input: &str, links: &[(&str, &str)], expect: &str) {
+        let links = links.iter()
+            .map(|&(ori, dest)| (ori.to_string(), dest.to_string()))
+            .collect::<Vec<_>>();
+        let output = text_markdown(input, &links, 1);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("see [the struct](Foo)", &[("Foo", "struct.Foo.md")], "see [the struct](struct.Foo.md)\n");
+    t("see [`Foo`] and [Bar](Bar)", &[("`Foo`", "struct.Foo.md")],
+      "see [`Foo`] and [Bar](Bar)\n\n[`Foo`]: struct.Foo.md\n");
+    t("# Examples\n\n```\n# use foo;\nlet x = 1;\n## not hidden\n```\n", &[],
+      "## Examples\n\n```rust\nlet x = 1;\n# not hidden\n```\n");
+    t("```text\n# not a heading\n```\n###### Deep", &[],
+      "```text\n# not a heading\n```\n###### Deep\n");
+    t("#hashtag", &[], "#hashtag\n");
+}
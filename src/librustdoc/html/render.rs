@@ -36,6 +36,7 @@ use std::ffi::OsStr;
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::io::{self, BufReader};
+use std::mem;
 use std::path::{PathBuf, Path, Component};
 use std::str;
 use std::sync::Arc;
@@ -57,7 +58,7 @@ use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::flock;
 
 use crate::clean::{self, AttributesExt, Deprecation, GetDefId, SelfTy, Mutability};
-use crate::config::RenderOptions;
+use crate::config::{OutputFormat, RenderOptions};
 use crate::docfs::{DocFS, ErrorStorage, PathError};
 use crate::doctree;
 use crate::html::escape::Escape;
@@ -69,6 +70,7 @@ use crate::html::item_type::ItemType;
 use crate::html::markdown::{self, Markdown, MarkdownHtml, MarkdownSummaryLine, ErrorCodes, IdMap};
 use crate::html::{highlight, layout, static_files};
 use crate::html::sources;
+use crate::scrape_examples::{ScrapedExample, ScrapedExamples};
 
 use minifier;
 
@@ -76,6 +78,8 @@ use minifier;
 mod tests;
 
 mod cache;
+mod full_text;
+crate mod span_map;
 
 use cache::Cache;
 crate use cache::ExternalLocation::{self, *};
@@ -210,6 +214,11 @@ crate struct SharedContext {
     pub edition: Edition,
     pub codes: ErrorCodes,
     playground: Option<markdown::Playground>,
+    /// The links from identifiers in each local source file to their definitions. Empty unless
+    /// `--generate-link-to-definition` was passed.
+    crate span_map: span_map::SpanMap,
+    /// The calls to the crate's functions found in its examples by `--with-examples`.
+    crate scraped_examples: ScrapedExamples,
 }
 
 impl Context {
@@ -284,6 +293,10 @@ pub struct RenderInfo {
     pub deref_trait_did: Option<DefId>,
     pub deref_mut_trait_did: Option<DefId>,
     pub owned_box_did: Option<DefId>,
+    pub output_format: OutputFormat,
+    pub coverage_threshold: Option<f64>,
+    crate span_map: span_map::SpanMap,
+    crate scraped_examples: ScrapedExamples,
 }
 
 // Helper structs for rendering items/sidebars and carrying along contextual
@@ -300,6 +313,9 @@ struct IndexItem {
     parent: Option<DefId>,
     parent_idx: Option<usize>,
     search_type: Option<IndexItemFunctionType>,
+    /// The stems of the words in the item's documentation with their number of occurrences, if
+    /// `--full-text-search` was passed. They are written to a separate part of the index.
+    terms: Vec<(String, u32)>,
 }
 
 impl ToJson for IndexItem {
@@ -399,7 +415,7 @@ pub fn initial_ids() -> Vec<String> {
 /// Generates the documentation for `crate` into the directory `dst`
 pub fn run(mut krate: clean::Crate,
            options: RenderOptions,
-           renderinfo: RenderInfo,
+           mut renderinfo: RenderInfo,
            diag: &errors::Handler,
            edition: Edition) -> Result<(), Error> {
     // need to save a copy of the options for rendering the index page
@@ -417,6 +433,7 @@ pub fn run(mut krate: clean::Crate,
         static_root_path,
         generate_search_filter,
         generate_redirect_pages,
+        full_text_search,
         ..
     } = options;
 
@@ -491,19 +508,32 @@ pub fn run(mut krate: clean::Crate,
         edition,
         codes: ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build()),
         playground,
+        span_map: mem::take(&mut renderinfo.span_map),
+        scraped_examples: mem::take(&mut renderinfo.scraped_examples),
     };
 
     let dst = output;
     scx.ensure_dir(&dst)?;
-    krate = sources::render(&dst, &mut scx, krate)?;
+    krate = sources::collect_local_sources(&mut scx, krate);
     let (new_crate, index, cache) = Cache::from_krate(
         renderinfo,
         &extern_html_root_urls,
         &dst,
+        full_text_search,
         krate,
     );
     krate = new_crate;
     let cache = Arc::new(cache);
+
+    // Freeze the cache now that the index has been built. Put an Arc into TLS
+    // for future parallelization opportunities
+    CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
+    CURRENT_DEPTH.with(|s| s.set(0));
+
+    // The source pages are rendered once the cache is built, because the links to definitions in
+    // them need it.
+    sources::render(&dst, &mut scx, &krate.name)?;
+    sources::render_example_sources(&dst, &mut scx)?;
     let mut cx = Context {
         current: Vec::new(),
         dst,
@@ -513,11 +543,6 @@ pub fn run(mut krate: clean::Crate,
         cache: cache.clone(),
     };
 
-    // Freeze the cache now that the index has been built. Put an Arc into TLS
-    // for future parallelization opportunities
-    CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
-    CURRENT_DEPTH.with(|s| s.set(0));
-
     // Write shared runs within a flock; disable thread dispatching of IO temporarily.
     Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(true);
     write_shared(&cx, &krate, index, &md_opts, diag)?;
@@ -2276,7 +2301,64 @@ fn item_function(w: &mut Buffer, cx: &Context, it: &clean::Item, f: &clean::Func
               indent: 0,
               asyncness: f.header.asyncness,
            }.print());
-    document(w, cx, it)
+    document(w, cx, it);
+    render_scraped_examples(w, cx, it)
+}
+
+/// The number of examples shown in full under a function's documentation. The others are only
+/// linked.
+const MAX_FULL_EXAMPLES: usize = 3;
+
+/// Renders the examples that `--with-examples` found for the function or method `item`.
+fn render_scraped_examples(w: &mut Buffer, cx: &Context, item: &clean::Item) {
+    let examples = match cx.shared.scraped_examples.calls.get(&item.def_id) {
+        Some(examples) if !examples.is_empty() => examples,
+        _ => return,
+    };
+    let root_path = cx.root_path();
+    // Examples whose source file couldn't be read don't have a source page to link to.
+    let example_href = |example: &ScrapedExample, lines: String| {
+        let key = (example.krate.clone(), example.file.clone());
+        if !cx.shared.scraped_examples.files.contains_key(&key) {
+            return None;
+        }
+        Some(format!("{}src/{}/{}#{}",
+                     root_path,
+                     example.krate,
+                     sources::source_page_href(Path::new(""), Path::new(&example.file)),
+                     lines))
+    };
+    let example_title = |href: Option<String>, title: String| {
+        match href {
+            Some(href) => format!("<a href='{}'>{}</a>", Escape(&href), title),
+            None => title,
+        }
+    };
+
+    write!(w, "<div class='docblock scraped-examples'><h5>Examples found in repository</h5>");
+    for example in examples.iter().take(MAX_FULL_EXAMPLES) {
+        let last_line = example.line + example.code.lines().count().saturating_sub(1);
+        let links: FxHashMap<_, _> = example.calls.iter().filter_map(|&(lo, hi)| {
+            let line = example.line + example.code[..lo as usize].matches('\n').count();
+            Some(((lo, hi), example_href(example, line.to_string())?))
+        }).collect();
+        let href = example_href(example, format!("{}-{}", example.line, last_line));
+        write!(w, "<div class='scraped-example'>\
+                   <div class='scraped-example-title'>{title}</div>\
+                   {code}</div>",
+               title = example_title(href, Escape(&example.file).to_string()),
+               code = highlight::render_source_with_links(&example.code, &links));
+    }
+    if examples.len() > MAX_FULL_EXAMPLES {
+        write!(w, "<div class='more-scraped-examples'>More examples:<ul>");
+        for example in &examples[MAX_FULL_EXAMPLES..] {
+            let href = example_href(example, example.line.to_string());
+            let title = format!("{}:{}", Escape(&example.file), example.line);
+            write!(w, "<li>{}</li>", example_title(href, title));
+        }
+        write!(w, "</ul></div>");
+    }
+    write!(w, "</div>");
 }
 
 fn render_implementor(cx: &Context, implementor: &Impl, w: &mut Buffer,
@@ -2442,6 +2524,7 @@ fn item_trait(
         render_stability_since(w, m, t);
         write!(w, "</h3>");
         document(w, cx, m);
+        render_scraped_examples(w, cx, m);
     }
 
     if !types.is_empty() {
@@ -3495,6 +3578,7 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
                     document_stability(w, cx, item, is_hidden);
                     if show_def_docs {
                         document_full(w, item, cx, "", is_hidden);
+                        render_scraped_examples(w, cx, item);
                     }
                 }
             } else {
@@ -4107,7 +4191,7 @@ fn sidebar_enum(buf: &mut Buffer, it: &clean::Item, e: &clean::Enum) {
     }
 }
 
-fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
+crate fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
     match *ty {
         ItemType::ExternCrate |
         ItemType::Import          => ("reexports", "Re-exports"),
@@ -11,6 +11,7 @@ use syntax::symbol::sym;
 use serialize::json::{ToJson, Json, as_json};
 
 use super::{ItemType, IndexItem, IndexItemFunctionType, Impl, shorten, plain_summary_line};
+use super::full_text;
 use super::{Type, RenderInfo};
 
 /// Indicates where an external crate can be found.
@@ -96,6 +97,8 @@ crate struct Cache {
     parent_stack: Vec<DefId>,
     parent_is_trait_impl: bool,
     search_index: Vec<IndexItem>,
+    /// Whether the documentation of the items is added to the search index.
+    full_text_search: bool,
     stripped_mod: bool,
     pub deref_trait_did: Option<DefId>,
     pub deref_mut_trait_did: Option<DefId>,
@@ -128,6 +131,7 @@ impl Cache {
         renderinfo: RenderInfo,
         extern_html_root_urls: &BTreeMap<String, String>,
         dst: &Path,
+        full_text_search: bool,
         mut krate: clean::Crate,
     ) -> (clean::Crate, String, Cache) {
         // Crawl the crate to build various caches used for the output
@@ -139,6 +143,10 @@ impl Cache {
             deref_trait_did,
             deref_mut_trait_did,
             owned_box_did,
+            output_format: _,
+            coverage_threshold: _,
+            span_map: _,
+            scraped_examples: _,
         } = renderinfo;
 
         let external_paths = external_paths.into_iter()
@@ -154,6 +162,7 @@ impl Cache {
             stack: Vec::new(),
             parent_stack: Vec::new(),
             search_index: Vec::new(),
+            full_text_search,
             parent_is_trait_impl: false,
             extern_locations: Default::default(),
             primitive_locations: Default::default(),
@@ -318,6 +327,7 @@ impl DocFolder for Cache {
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
+                            terms: index_terms(self.full_text_search, &item),
                         });
                     }
                 }
@@ -500,6 +510,7 @@ impl Cache {
                                 parent: None,
                                 parent_idx: None,
                                 search_type: get_index_search_type(&item),
+                                terms: Vec::new(),
                             });
             }
         }
@@ -548,7 +559,8 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
 
     let Cache { ref mut search_index,
                 ref orphan_impl_items,
-                ref paths, .. } = *cache;
+                ref paths,
+                full_text_search, .. } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
@@ -562,6 +574,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
+                terms: index_terms(full_text_search, &item),
             });
         }
     }
@@ -571,6 +584,10 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     let mut lastpath = String::new();
     let mut lastpathid = 0usize;
 
+    // The full-text index maps each stem to the items that contain it, as a flat list of pairs of
+    // the position of the item in `crate_items` and the number of occurrences.
+    let mut crate_terms = BTreeMap::<String, Vec<Json>>::new();
+
     for item in search_index {
         item.parent_idx = item.parent.map(|nodeid| {
             if nodeid_to_pathid.contains_key(&nodeid) {
@@ -586,6 +603,12 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
             }
         });
 
+        for (term, count) in item.terms.drain(..) {
+            let postings = crate_terms.entry(term).or_default();
+            postings.push(crate_items.len().to_json());
+            postings.push(count.to_json());
+        }
+
         // Omit the parent path if it is same to that of the prior item.
         if lastpath == item.path {
             item.path.clear();
@@ -603,6 +626,12 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     crate_data.insert("doc".to_owned(), Json::String(crate_doc));
     crate_data.insert("i".to_owned(), Json::Array(crate_items));
     crate_data.insert("p".to_owned(), Json::Array(crate_paths));
+    if full_text_search {
+        let crate_terms = crate_terms.into_iter().map(|(term, postings)| {
+            (term, Json::Array(postings))
+        }).collect();
+        crate_data.insert("f".to_owned(), Json::Object(crate_terms));
+    }
 
     // Collect the index into a string
     format!("searchIndex[{}] = {};",
@@ -610,6 +639,14 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
             Json::Object(crate_data))
 }
 
+/// The terms of `item` for the full-text part of the search index, if it is written.
+fn index_terms(full_text_search: bool, item: &clean::Item) -> Vec<(String, u32)> {
+    if !full_text_search {
+        return Vec::new();
+    }
+    item.collapsed_doc_value().map_or_else(Vec::new, |doc| full_text::terms(&doc))
+}
+
 fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
     let (all_types, ret_types) = match item.inner {
         clean::FunctionItem(ref f) => (&f.all_types, &f.ret_types),
@@ -0,0 +1,74 @@
+//! The full-text part of the search index, written with `--full-text-search`.
+//!
+//! The words of each item's documentation are reduced to their stems, and the index maps every
+//! stem to the items whose documentation contains it. `main.js` stems the words of a query the
+//! same way, so the two stemmers must be kept in sync.
+
+use crate::html::markdown;
+
+use rustc_data_structures::fx::FxHashMap;
+
+/// Words too common to tell items apart. Must be kept in sync with `STOP_WORDS` in `main.js`.
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "all", "also", "and", "any", "are", "been", "but", "can", "does", "for",
+    "from", "has", "have", "how", "into", "its", "may", "more", "must", "not", "only", "other",
+    "should", "such", "than", "that", "the", "their", "then", "there", "these", "this", "those",
+    "through", "used", "was", "were", "what", "when", "where", "which", "while", "will", "with",
+    "would", "you", "your",
+];
+
+/// Returns the stems of the words in the documentation `doc`, with how often each occurs.
+crate fn terms(doc: &str) -> Vec<(String, u32)> {
+    let text = markdown::plain_text(doc).to_ascii_lowercase();
+    let mut terms = FxHashMap::default();
+    for word in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+        if word.len() < 3 || word.len() > 30 || word.bytes().all(|b| b.is_ascii_digit()) ||
+            STOP_WORDS.contains(&word)
+        {
+            continue;
+        }
+        *terms.entry(stem(word)).or_insert(0) += 1;
+    }
+    let mut terms = terms.into_iter().collect::<Vec<_>>();
+    terms.sort();
+    terms
+}
+
+/// Strips the common English suffixes of `word`, so that for example "parses", "parsed" and
+/// "parsing" all have the stem "pars". This is much simpler than a real stemmer, as it only has
+/// to be consistent with itself. Must be kept in sync with `stem` in `main.js`.
+fn stem(word: &str) -> String {
+    let mut stem = word.to_string();
+    // Strips `suffix` if at least three characters are left.
+    let strip = |stem: &mut String, suffix: &str| {
+        if stem.ends_with(suffix) && stem.len() - suffix.len() >= 3 {
+            stem.truncate(stem.len() - suffix.len());
+            true
+        } else {
+            false
+        }
+    };
+
+    if stem.ends_with("ies") && stem.len() > 4 {
+        stem.truncate(stem.len() - 3);
+        stem.push('y');
+    } else if stem.ends_with("sses") {
+        stem.truncate(stem.len() - 2);
+    } else if !stem.ends_with("ss") && !stem.ends_with("us") && !stem.ends_with("is") {
+        strip(&mut stem, "s");
+    }
+    if strip(&mut stem, "ing") || strip(&mut stem, "ed") {
+        // "running" -> "runn" -> "run"
+        let bytes = stem.as_bytes();
+        let last = bytes[bytes.len() - 1];
+        if last == bytes[bytes.len() - 2] && !b"aeioulsz".contains(&last) {
+            stem.pop();
+        }
+    }
+    strip(&mut stem, "ly");
+    strip(&mut stem, "e");
+    stem
+}
+
+#[cfg(test)]
+mod tests;
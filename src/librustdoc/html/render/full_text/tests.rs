@@ -0,0 +1,34 @@
+use super::{stem, terms};
+
+#[test]
+fn test_stem() {
+    for word in &["parse", "parses", "parsed", "parsing"] {
+        assert_eq!(stem(word), "pars");
+    }
+    assert_eq!(stem("running"), "run");
+    assert_eq!(stem("runs"), "run");
+    assert_eq!(stem("entries"), "entry");
+    assert_eq!(stem("classes"), "class");
+    assert_eq!(stem("status"), "status");
+    assert_eq!(stem("quickly"), "quick");
+    // Too short to strip anything.
+    assert_eq!(stem("thing"), "thing");
+    assert_eq!(stem("ids"), "ids");
+}
+
+#[test]
+fn test_terms() {
+    let doc = "Parses the `Config` file.\n\n\
+               ```\n\
+               let ignored = 1;\n\
+               ```\n\
+               Parsing fails if the files can't be read in 10 seconds.";
+    assert_eq!(terms(doc), vec![
+        ("config".to_string(), 1),
+        ("fail".to_string(), 1),
+        ("fil".to_string(), 2),
+        ("pars".to_string(), 2),
+        ("read".to_string(), 1),
+        ("second".to_string(), 1),
+    ]);
+}
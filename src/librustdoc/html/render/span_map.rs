@@ -0,0 +1,200 @@
+//! Collects the definitions that the identifiers in the local crate's source files resolve to, so
+//! that the source pages can link them. This runs while the type context is still available;
+//! the links are turned into URLs when the source pages are rendered.
+
+use crate::clean::{self, Clean, TypeKind};
+use crate::clean::inline;
+use crate::core::DocContext;
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::def::{CtorOf, DefKind, Res};
+use rustc::hir::def_id::DefId;
+use rustc::ty::{self, DefIdTree, TypeckTables};
+use rustc_data_structures::fx::FxHashMap;
+use syntax::ext::base::MacroKind;
+use syntax_pos::{BytePos, FileName, Span};
+
+use std::path::PathBuf;
+
+/// Where an identifier in a source page links to.
+#[derive(Clone, Debug)]
+crate enum LinkFromSrc {
+    /// A definition in the local crate, linked to its documentation page like an external item
+    /// if it has one, and to its lines in the source pages otherwise.
+    Local(Option<(DefId, Option<String>)>, clean::Span),
+    /// An item from another crate, linked to its documentation page. The string is the anchor of
+    /// the definition on that page, when it is a method, variant or other item without a page of
+    /// its own.
+    External(DefId, Option<String>),
+}
+
+/// The links of each source file in the local crate. Each link is keyed by the byte range of
+/// its identifier in the file.
+crate type SpanMap = FxHashMap<PathBuf, FxHashMap<(u32, u32), LinkFromSrc>>;
+
+/// Walks the HIR of the local crate and collects a link for every identifier that resolves to a
+/// definition.
+crate fn collect(cx: &DocContext<'_>) -> SpanMap {
+    let mut collector = SpanMapCollector {
+        cx,
+        tables: None,
+        span_map: SpanMap::default(),
+    };
+    intravisit::walk_crate(&mut collector, cx.tcx.hir().krate());
+    collector.span_map
+}
+
+struct SpanMapCollector<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    /// The type-check results of the body being visited, which resolve method calls and
+    /// type-relative paths like `Vec::new`.
+    tables: Option<&'tcx TypeckTables<'tcx>>,
+    span_map: SpanMap,
+}
+
+impl<'a, 'tcx> SpanMapCollector<'a, 'tcx> {
+    /// Records a link from the identifier at `span` to the definition of `res`.
+    fn add_link(&mut self, span: Span, res: Res) {
+        if span.is_dummy() || span.from_expansion() {
+            return;
+        }
+        let link = match res {
+            Res::Def(kind, did) => self.def_link(kind, did),
+            Res::Local(hir_id) => self.local_link(None, self.cx.tcx.hir().span(hir_id)),
+            Res::SelfTy(Some(trait_did), None) => self.def_link(DefKind::Trait, trait_did),
+            Res::SelfTy(_, Some(impl_did)) | Res::SelfCtor(impl_did) if impl_did.is_local() => {
+                self.local_link(None, self.cx.tcx.def_span(impl_did))
+            }
+            _ => None,
+        };
+        let link = match link {
+            Some(link) => link,
+            None => return,
+        };
+
+        let source_map = self.cx.sess().source_map();
+        let lo = source_map.lookup_byte_offset(span.lo());
+        let path = match lo.sf.name {
+            FileName::Real(ref path) => path.clone(),
+            _ => return,
+        };
+        let BytePos(start) = lo.pos;
+        let BytePos(len) = span.hi() - span.lo();
+        self.span_map.entry(path).or_default().insert((start, start + len), link);
+    }
+
+    fn def_link(&self, kind: DefKind, did: DefId) -> Option<LinkFromSrc> {
+        let tcx = self.cx.tcx;
+
+        // Constructors are linked to the struct or variant they construct.
+        let (kind, did) = match kind {
+            DefKind::Ctor(CtorOf::Struct, _) => (DefKind::Struct, tcx.parent(did)?),
+            DefKind::Ctor(CtorOf::Variant, _) => (DefKind::Variant, tcx.parent(did)?),
+            _ => (kind, did),
+        };
+
+        if did.is_local() {
+            return self.local_link(self.page(kind, did), tcx.def_span(did));
+        }
+        let (page, anchor) = self.page(kind, did)?;
+
+        // Make sure the page is known to the cache, even if the documentation of the crate never
+        // mentions it.
+        let page_kind = match tcx.def_kind(page)? {
+            DefKind::Fn => TypeKind::Function,
+            DefKind::TyAlias => TypeKind::Typedef,
+            DefKind::Enum => TypeKind::Enum,
+            DefKind::Trait => TypeKind::Trait,
+            DefKind::Struct => TypeKind::Struct,
+            DefKind::Union => TypeKind::Union,
+            DefKind::Mod => TypeKind::Module,
+            DefKind::ForeignTy => TypeKind::Foreign,
+            DefKind::Const => TypeKind::Const,
+            DefKind::Static => TypeKind::Static,
+            DefKind::Macro(MacroKind::Bang) => TypeKind::Macro,
+            DefKind::TraitAlias => TypeKind::TraitAlias,
+            _ => return None,
+        };
+        inline::record_extern_fqn(self.cx, page, page_kind);
+
+        Some(LinkFromSrc::External(page, anchor))
+    }
+
+    /// The item whose documentation page documents `did`, and the anchor of `did` on that page
+    /// when it is a method, variant or other item without a page of its own.
+    fn page(&self, kind: DefKind, did: DefId) -> Option<(DefId, Option<String>)> {
+        let tcx = self.cx.tcx;
+        let (page, anchor) = match kind {
+            DefKind::Variant => {
+                (tcx.parent(did)?, Some(format!("variant.{}", tcx.item_name(did))))
+            }
+            DefKind::Method | DefKind::AssocConst | DefKind::AssocTy => {
+                let item = tcx.associated_item(did);
+                let page = match item.container {
+                    ty::TraitContainer(trait_did) => trait_did,
+                    ty::ImplContainer(impl_did) => match tcx.type_of(impl_did).kind {
+                        ty::Adt(def, _) => def.did,
+                        _ => return None,
+                    },
+                };
+                let prefix = match item.kind {
+                    ty::AssocKind::Method => match item.container {
+                        ty::TraitContainer(_) if !item.defaultness.has_value() => "tymethod",
+                        _ => "method",
+                    },
+                    ty::AssocKind::Const => "associatedconstant",
+                    ty::AssocKind::Type | ty::AssocKind::OpaqueTy => "associatedtype",
+                };
+                (page, Some(format!("{}.{}", prefix, item.ident)))
+            }
+            _ => (did, None),
+        };
+        Some((page, anchor))
+    }
+
+    fn local_link(
+        &self,
+        page: Option<(DefId, Option<String>)>,
+        span: Span,
+    ) -> Option<LinkFromSrc> {
+        if span.is_dummy() || span.from_expansion() {
+            None
+        } else {
+            Some(LinkFromSrc::Local(page, span.clean(self.cx)))
+        }
+    }
+}
+
+impl<'a, 'tcx> intravisit::Visitor<'tcx> for SpanMapCollector<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::All(self.cx.tcx.hir())
+    }
+
+    fn visit_nested_body(&mut self, id: hir::BodyId) {
+        let old_tables = self.tables.replace(self.cx.tcx.body_tables(id));
+        let body = self.cx.tcx.hir().body(id);
+        self.visit_body(body);
+        self.tables = old_tables;
+    }
+
+    fn visit_path_segment(&mut self, path_span: Span, segment: &'tcx hir::PathSegment) {
+        if let Some(res) = segment.res {
+            self.add_link(segment.ident.span, res);
+        }
+        intravisit::walk_path_segment(self, path_span, segment);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        let segment = match expr.kind {
+            hir::ExprKind::MethodCall(ref segment, ..) => Some(segment),
+            hir::ExprKind::Path(hir::QPath::TypeRelative(_, ref segment)) => Some(segment),
+            _ => None,
+        };
+        if let (Some(segment), Some(tables)) = (segment, self.tables) {
+            if let Some((kind, did)) = tables.type_dependent_def(expr.hir_id) {
+                self.add_link(segment.ident.span, Res::Def(kind, did));
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
@@ -2,77 +2,140 @@ use crate::clean;
 use crate::docfs::PathError;
 use crate::fold::DocFolder;
 use crate::html::layout;
-use crate::html::render::{Error, SharedContext, BASIC_KEYWORDS};
+use crate::html::render::{Error, SharedContext, BASIC_KEYWORDS, CURRENT_DEPTH};
+use crate::html::render::span_map::LinkFromSrc;
 use crate::html::highlight;
-use crate::html::format::Buffer;
+use crate::html::format::{Buffer, href};
+use rustc::hir::def_id::DefId;
+use rustc_data_structures::fx::FxHashMap;
 use std::ffi::OsStr;
 use std::fs;
+use std::mem;
 use std::path::{Component, Path, PathBuf};
 use syntax::source_map::FileName;
 
-crate fn render(dst: &Path, scx: &mut SharedContext,
-                  krate: clean::Crate) -> Result<clean::Crate, Error> {
-    info!("emitting source files");
-    let dst = dst.join("src").join(&krate.name);
-    scx.ensure_dir(&dst)?;
-    let mut folder = SourceCollector {
-        dst,
-        scx,
-    };
-    Ok(folder.fold_crate(krate))
+/// Finds the local source files of the crate's items and records the URL of each one's page in
+/// `scx.local_sources`. The pages themselves are written by `render`.
+crate fn collect_local_sources(scx: &mut SharedContext, krate: clean::Crate) -> clean::Crate {
+    let mut collector = LocalSourcesCollector { scx };
+    collector.fold_crate(krate)
 }
 
-/// Helper struct to render all source code to HTML pages
-struct SourceCollector<'a> {
+/// Helper struct to find the source files that are rendered to HTML pages
+struct LocalSourcesCollector<'a> {
     scx: &'a mut SharedContext,
-
-    /// Root destination to place all HTML output into
-    dst: PathBuf,
 }
 
-impl<'a> DocFolder for SourceCollector<'a> {
+impl<'a> DocFolder for LocalSourcesCollector<'a> {
     fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
-        // If we're including source files, and we haven't seen this file yet,
-        // then we need to render it out to the filesystem.
         if self.scx.include_sources
             // skip all invalid or macro spans
             && item.source.filename.is_real()
             // skip non-local items
             && item.def_id.is_local() {
-
-            // If it turns out that we couldn't read this file, then we probably
-            // can't read any of the files (generating html output from json or
-            // something like that), so just don't include sources for the
-            // entire crate. The other option is maintaining this mapping on a
-            // per-file basis, but that's probably not worth it...
-            self.scx
-                .include_sources = match self.emit_source(&item.source.filename) {
-                Ok(()) => true,
-                Err(e) => {
-                    println!("warning: source code was requested to be rendered, \
-                              but processing `{}` had an error: {}",
-                             item.source.filename, e);
-                    println!("         skipping rendering of source code");
-                    false
-                }
-            };
+            self.add_local_source(&item.source.filename);
         }
         self.fold_item_recur(item)
     }
 }
 
-impl<'a> SourceCollector<'a> {
-    /// Renders the given filename into its corresponding HTML source file.
-    fn emit_source(&mut self, filename: &FileName) -> Result<(), Error> {
+impl<'a> LocalSourcesCollector<'a> {
+    fn add_local_source(&mut self, filename: &FileName) {
         let p = match *filename {
             FileName::Real(ref file) => file,
-            _ => return Ok(()),
+            _ => return,
         };
         if self.scx.local_sources.contains_key(&**p) {
-            // We've already emitted this source
-            return Ok(());
+            // We've already recorded this source
+            return;
         }
 
+        let href = source_page_href(&self.scx.src_root, p);
+        self.scx.local_sources.insert(p.clone(), href);
+    }
+}
+
+/// Returns the path of the source page of the file `p`, relative to the directory of the
+/// crate's source pages.
+crate fn source_page_href(src_root: &Path, p: &Path) -> String {
+    let mut href = String::new();
+    clean_path(src_root, p, false, |component| {
+        href.push_str(&component.to_string_lossy());
+        href.push('/');
+    });
+    let mut fname = p.file_name()
+                     .expect("source has no filename")
+                     .to_os_string();
+    fname.push(".html");
+    href.push_str(&fname.to_string_lossy());
+    href
+}
+
+/// Renders the source files found by `collect_local_sources` to HTML pages.
+crate fn render(dst: &Path, scx: &mut SharedContext, krate_name: &str) -> Result<(), Error> {
+    info!("emitting source files");
+    let dst = dst.join("src").join(krate_name);
+    scx.ensure_dir(&dst)?;
+
+    let mut files = scx.local_sources.keys().cloned().collect::<Vec<_>>();
+    files.sort();
+    let mut emitter = SourceEmitter {
+        src_root: scx.src_root.clone(),
+        dst,
+        krate_name,
+        scx,
+    };
+    for file in files {
+        // If it turns out that we couldn't read this file, then we probably
+        // can't read any of the files (generating html output from json or
+        // something like that), so just don't include sources for the
+        // entire crate. The other option is maintaining this mapping on a
+        // per-file basis, but that's probably not worth it...
+        if let Err(e) = emitter.emit_source(&file) {
+            println!("warning: source code was requested to be rendered, \
+                      but processing `{}` had an error: {}",
+                     file.display(), e);
+            println!("         skipping rendering of source code");
+            emitter.scx.include_sources = false;
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the source files of the examples loaded by `--with-examples` to HTML pages, in the
+/// source directory of each example crate.
+crate fn render_example_sources(dst: &Path, scx: &mut SharedContext) -> Result<(), Error> {
+    let files = mem::take(&mut scx.scraped_examples.files);
+    for ((krate_name, file), contents) in &files {
+        let dst = dst.join("src").join(krate_name);
+        scx.ensure_dir(&dst)?;
+        let mut emitter = SourceEmitter {
+            src_root: PathBuf::new(),
+            dst,
+            krate_name,
+            scx,
+        };
+        emitter.write_page(Path::new(file), contents)?;
+    }
+    scx.scraped_examples.files = files;
+    Ok(())
+}
+
+/// Helper struct to render all source code to HTML pages
+struct SourceEmitter<'a> {
+    scx: &'a mut SharedContext,
+
+    /// The directory that the paths of the pages are relative to
+    src_root: PathBuf,
+    /// Root destination to place all HTML output into
+    dst: PathBuf,
+    krate_name: &'a str,
+}
+
+impl<'a> SourceEmitter<'a> {
+    /// Renders the given file into its corresponding HTML source file.
+    fn emit_source(&mut self, p: &Path) -> Result<(), Error> {
         let contents = match fs::read_to_string(&p) {
             Ok(contents) => contents,
             Err(e) => {
@@ -87,15 +150,19 @@ impl<'a> SourceCollector<'a> {
             &contents[..]
         };
 
+        self.write_page(p, contents)
+    }
+
+    /// Writes the HTML source page of the file `p`.
+    fn write_page(&mut self, p: &Path, contents: &str) -> Result<(), Error> {
         // Create the intermediate directories
         let mut cur = self.dst.clone();
         let mut root_path = String::from("../../");
-        let mut href = String::new();
-        clean_path(&self.scx.src_root, &p, false, |component| {
+        let mut depth = 2;
+        clean_path(&self.src_root, &p, false, |component| {
             cur.push(component);
             root_path.push_str("../");
-            href.push_str(&component.to_string_lossy());
-            href.push('/');
+            depth += 1;
         });
         self.scx.ensure_dir(&cur)?;
         let mut fname = p.file_name()
@@ -103,11 +170,12 @@ impl<'a> SourceCollector<'a> {
                          .to_os_string();
         fname.push(".html");
         cur.push(&fname);
-        href.push_str(&fname.to_string_lossy());
+
+        let links = self.links(p, &root_path, depth);
 
         let title = format!("{} -- source", cur.file_name().expect("failed to get file name")
                                                .to_string_lossy());
-        let desc = format!("Source to the Rust file `{}`.", filename);
+        let desc = format!("Source to the Rust file `{}`.", p.display());
         let page = layout::Page {
             title: &title,
             css_class: "source",
@@ -120,12 +188,63 @@ impl<'a> SourceCollector<'a> {
             static_extra_scripts: &[&format!("source-script{}", self.scx.resource_suffix)],
         };
         let v = layout::render(&self.scx.layout,
-                       &page, "", |buf: &mut _| print_src(buf, &contents),
+                       &page, "", |buf: &mut _| print_src(buf, &contents, &links),
                        &self.scx.themes);
         self.scx.fs.write(&cur, v.as_bytes())?;
-        self.scx.local_sources.insert(p.clone(), href);
         Ok(())
     }
+
+    /// Resolves the links to definitions in the file `p` into URLs, relative to its source page
+    /// at `root_path`. Local items are linked to their documentation page if they have one, and
+    /// to their source lines otherwise. Links to source files that aren't rendered and to
+    /// external items without a page are dropped.
+    fn links(&self, p: &Path, root_path: &str, depth: usize) -> FxHashMap<(u32, u32), String> {
+        let links = match self.scx.span_map.get(p) {
+            Some(links) => links,
+            None => return FxHashMap::default(),
+        };
+
+        let old_depth = CURRENT_DEPTH.with(|d| d.replace(depth));
+        let links = links.iter().filter_map(|(&range, link)| {
+            let url = match *link {
+                LinkFromSrc::Local(ref page, ref span) => {
+                    // Items without a documentation page, like private ones, are linked to their
+                    // source lines instead.
+                    let doc_url = page.as_ref().and_then(|&(did, ref anchor)| {
+                        page_url(did, anchor)
+                    });
+                    if let Some(url) = doc_url {
+                        return Some((range, url));
+                    }
+                    let file = match span.filename {
+                        FileName::Real(ref file) => file,
+                        _ => return None,
+                    };
+                    let lines = if span.loline == span.hiline {
+                        span.loline.to_string()
+                    } else {
+                        format!("{}-{}", span.loline, span.hiline)
+                    };
+                    format!("{}src/{}/{}#{}",
+                            root_path, self.krate_name, self.scx.local_sources.get(file)?, lines)
+                }
+                LinkFromSrc::External(did, ref anchor) => page_url(did, anchor)?,
+            };
+            Some((range, url))
+        }).collect();
+        CURRENT_DEPTH.with(|d| d.set(old_depth));
+        links
+    }
+}
+
+/// The URL of the documentation page of `did`, followed by `anchor`.
+fn page_url(did: DefId, anchor: &Option<String>) -> Option<String> {
+    let (mut url, ..) = href(did)?;
+    if let Some(anchor) = anchor {
+        url.push('#');
+        url.push_str(anchor);
+    }
+    Some(url)
 }
 
 /// Takes a path to a source file and cleans the path to it. This canonicalizes
@@ -157,7 +276,7 @@ where
 
 /// Wrapper struct to render the source code of a file. This will do things like
 /// adding line numbers to the left-hand side.
-fn print_src(buf: &mut Buffer, s: &str) {
+fn print_src(buf: &mut Buffer, s: &str, links: &FxHashMap<(u32, u32), String>) {
     let lines = s.lines().count();
     let mut cols = 0;
     let mut tmp = lines;
@@ -171,5 +290,5 @@ fn print_src(buf: &mut Buffer, s: &str) {
     }
     write!(buf, "</pre>");
     write!(buf, "{}",
-            highlight::render_with_highlighting(s, None, None, None));
+            highlight::render_source_with_links(s, links));
 }
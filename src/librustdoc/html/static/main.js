@@ -448,8 +448,59 @@ function getSearchElement() {
         return s1_len + s2_len;
     }
 
+    // Words too common to tell items apart. Must be kept in sync with `STOP_WORDS` in
+    // `html/render/full_text.rs`.
+    var STOP_WORDS = [
+        "about", "after", "all", "also", "and", "any", "are", "been", "but", "can", "does", "for",
+        "from", "has", "have", "how", "into", "its", "may", "more", "must", "not", "only", "other",
+        "should", "such", "than", "that", "the", "their", "then", "there", "these", "this", "those",
+        "through", "used", "was", "were", "what", "when", "where", "which", "while", "will", "with",
+        "would", "you", "your",
+    ];
+
+    /**
+     * Strips the common English suffixes of a word, the way the full-text part of the search
+     * index was built. Must be kept in sync with `stem` in `html/render/full_text.rs`.
+     */
+    function stem(word) {
+        // Strips `suffix` if at least three characters are left.
+        function strip(suffix) {
+            if (word.length - suffix.length >= 3 &&
+                    word.substr(word.length - suffix.length) === suffix) {
+                word = word.substr(0, word.length - suffix.length);
+                return true;
+            }
+            return false;
+        }
+        function endsWith(suffix) {
+            return word.length >= suffix.length &&
+                word.substr(word.length - suffix.length) === suffix;
+        }
+
+        if (endsWith("ies") && word.length > 4) {
+            word = word.substr(0, word.length - 3) + "y";
+        } else if (endsWith("sses")) {
+            word = word.substr(0, word.length - 2);
+        } else if (!endsWith("ss") && !endsWith("us") && !endsWith("is")) {
+            strip("s");
+        }
+        if (strip("ing") || strip("ed")) {
+            // "running" -> "runn" -> "run"
+            var last = word.charAt(word.length - 1);
+            if (last === word.charAt(word.length - 2) && "aeioulsz".indexOf(last) === -1) {
+                word = word.substr(0, word.length - 1);
+            }
+        }
+        strip("ly");
+        strip("e");
+        return word;
+    }
+
     function initSearch(rawSearchIndex) {
         var currentResults, index, searchIndex;
+        // Maps the stems of the words in the docs to a flat array of pairs of the index of an
+        // item in `searchIndex` and the number of occurrences.
+        var fullTextIndex;
         var MAX_LEV_DISTANCE = 3;
         var MAX_RESULTS = 200;
         var GENERICS_DATA = 1;
@@ -571,6 +622,11 @@ function getSearchElement() {
                     b = (bbb.lev);
                     if (a !== b) { return a - b; }
 
+                    // sort by relevance of the docs to the query (less relevant goes later)
+                    a = (aaa.fullText || 0);
+                    b = (bbb.fullText || 0);
+                    if (a !== b) { return b - a; }
+
                     // sort by crate (non-current crate goes later)
                     a = (aaa.item.crate !== window.currentCrate);
                     b = (bbb.item.crate !== window.currentCrate);
@@ -878,6 +934,51 @@ function getSearchElement() {
                 return false;
             }
 
+            // Finds the items whose docs contain the words of the query in the full-text index,
+            // and scores them with tf-idf. Items that weren't found by their name are added after
+            // the ones that were, and the score ranks both.
+            function addFullTextResults() {
+                var words = valLower.split(/[^a-z0-9]+/);
+                var scores = {};
+                var nbItems = searchIndex.length;
+                var w, p;
+                for (w = 0; w < words.length; ++w) {
+                    var word = words[w];
+                    if (word.length < 3 || word.length > 30 || /^[0-9]+$/.test(word) ||
+                            STOP_WORDS.indexOf(word) !== -1) {
+                        continue;
+                    }
+                    var postings = fullTextIndex[stem(word)];
+                    if (postings === undefined) {
+                        continue;
+                    }
+                    var idf = Math.log(nbItems / (postings.length / 2));
+                    for (p = 0; p < postings.length; p += 2) {
+                        var id = postings[p];
+                        scores[id] = (scores[id] || 0) + (1 + Math.log(postings[p + 1])) * idf;
+                    }
+                }
+                for (var id in scores) {
+                    if (!scores.hasOwnProperty(id)) { continue; }
+                    var item = searchIndex[id];
+                    if ((filterCrates !== undefined && item.crate !== filterCrates) ||
+                            typePassesFilter(typeFilter, item.ty) === false) {
+                        continue;
+                    }
+                    var itemId = generateId(item);
+                    if (results[itemId] === undefined) {
+                        results[itemId] = {
+                            id: parseInt(id, 10),
+                            index: -1,
+                            lev: MAX_LEV_DISTANCE + 1,
+                            dontValidate: true,
+                        };
+                    }
+                    results[itemId].fullText = Math.max(results[itemId].fullText || 0,
+                                                        scores[id]);
+                }
+            }
+
             function generateId(ty) {
                 if (ty.parent && ty.parent.name) {
                     return itemTypes[ty.ty] + ty.path + ty.parent.name + ty.name;
@@ -1123,6 +1224,7 @@ function getSearchElement() {
                         results[fullId].lev = Math.min(results[fullId].lev, lev);
                     }
                 }
+                addFullTextResults();
             }
 
             var ret = {
@@ -1584,6 +1686,7 @@ function getSearchElement() {
 
         function buildIndex(rawSearchIndex) {
             searchIndex = [];
+            fullTextIndex = {};
             var searchWords = [];
             var i;
 
@@ -1626,6 +1729,7 @@ function getSearchElement() {
                 // faster analysis operations
                 len = items.length;
                 var lastPath = "";
+                var firstItem = searchIndex.length;
                 for (i = 0; i < len; ++i) {
                     var rawRow = items[i];
                     var row = {crate: crate, ty: rawRow[0], name: rawRow[1],
@@ -1640,6 +1744,23 @@ function getSearchElement() {
                     }
                     lastPath = row.path;
                 }
+
+                // an object mapping the stems of the words in the docs to flat arrays of
+                // [(Number) index of the item in `items`,
+                //  (Number) number of occurrences]
+                // pairs, if the crate was documented with `--full-text-search`
+                var terms = rawSearchIndex[crate].f;
+                for (var term in terms) {
+                    if (!terms.hasOwnProperty(term)) { continue; }
+                    var postings = fullTextIndex[term];
+                    if (postings === undefined) {
+                        postings = fullTextIndex[term] = [];
+                    }
+                    var crateTerms = terms[term];
+                    for (var j = 0; j < crateTerms.length; j += 2) {
+                        postings.push(firstItem + crateTerms[j], crateTerms[j + 1]);
+                    }
+                }
             }
             return searchWords;
         }
@@ -0,0 +1,376 @@
+//! Rustdoc's JSON backend, selected with `--output-format json`.
+//!
+//! Instead of rendering HTML, this writes the cleaned crate to a single
+//! `<crate name>.json` file in the output directory, for tools that need to
+//! inspect a crate's public API. The crate root module is written out as a tree
+//! of items, with every item carrying its `id`, its docs and its stability.
+//! Types, bounds and paths are written out the way they would be printed in
+//! the HTML docs, as plain text.
+//!
+//! Ids are made of the crate number and the `DefIndex` of an item, so they're
+//! only meaningful within one document: they change whenever items are added
+//! or removed, and the same crate number can refer to different crates in the
+//! documents of different crates. Tools comparing documents have to go by the
+//! paths of the items instead.
+//!
+//! The layout is versioned by `FORMAT_VERSION`, which must be bumped whenever
+//! an existing field changes its meaning or is removed.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::middle::stability::StabilityLevel;
+use serialize::json::{Json, ToJson};
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::doctree;
+use crate::html::item_type::ItemType;
+use crate::html::render::Error;
+
+/// The version of the JSON layout written by this module.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Writes `krate` to `<output>/<crate name>.json`.
+pub fn run(krate: clean::Crate, options: RenderOptions) -> Result<(), Error> {
+    let json = crate_to_json(&krate);
+
+    let output = options.output;
+    fs::create_dir_all(&output).map_err(|e| Error::new(e, &output))?;
+    let file = output.join(format!("{}.json", krate.name));
+    fs::write(&file, json.to_string()).map_err(|e| Error::new(e, &file))
+}
+
+crate fn crate_to_json(krate: &clean::Crate) -> Json {
+    let external_crates = krate.externs.iter().map(|(cnum, ext)| {
+        (cnum.as_u32().to_string(), object(vec![("name", ext.name.to_json())]))
+    }).collect::<BTreeMap<_, _>>();
+
+    object(vec![
+        ("format_version", FORMAT_VERSION.to_json()),
+        ("name", krate.name.to_json()),
+        ("version", krate.version.to_json()),
+        ("root", krate.module.as_ref().and_then(item_to_json).unwrap_or(Json::Null)),
+        ("external_crates", Json::Object(external_crates)),
+    ])
+}
+
+/// Converts `item` and everything nested in it. Items that were stripped by
+/// a pass (e.g. private items) are skipped.
+fn item_to_json(item: &clean::Item) -> Option<Json> {
+    let inner = match item.inner {
+        clean::StrippedItem(..) => return None,
+        ref inner => inner_to_json(inner),
+    };
+
+    let links = item.attrs.links.iter()
+        .filter_map(|&(ref text, did, _)| did.map(|did| (text.clone(), id(did))))
+        .collect::<BTreeMap<_, _>>();
+
+    Some(object(vec![
+        ("id", id(item.def_id)),
+        ("name", item.name.to_json()),
+        ("kind", ItemType::from(item).as_str().to_json()),
+        ("visibility", visibility_to_json(&item.visibility)),
+        ("docs", item.attrs.collapsed_doc_value().to_json()),
+        ("links", Json::Object(links)),
+        ("span", span_to_json(&item.source)),
+        ("stability", item.stability.as_ref().map_or(Json::Null, stability_to_json)),
+        ("deprecation", item.deprecation.as_ref().map_or(Json::Null, deprecation_to_json)),
+        ("non_exhaustive", item.is_non_exhaustive().to_json()),
+        ("inner", inner),
+    ]))
+}
+
+fn inner_to_json(inner: &clean::ItemEnum) -> Json {
+    use crate::clean::ItemEnum::*;
+
+    match *inner {
+        ModuleItem(ref m) => object(vec![
+            ("is_crate", m.is_crate.to_json()),
+            ("items", items_to_json(&m.items)),
+        ]),
+        ExternCrateItem(ref name, ref rename) => object(vec![
+            ("name", name.to_json()),
+            ("rename", rename.to_json()),
+        ]),
+        ImportItem(ref import) => import_to_json(import),
+        StructItem(ref s) => object(vec![
+            ("struct_type", struct_type_to_json(s.struct_type)),
+            ("generics", generics_to_json(&s.generics)),
+            ("fields", items_to_json(&s.fields)),
+            ("fields_stripped", s.fields_stripped.to_json()),
+        ]),
+        UnionItem(ref u) => object(vec![
+            ("struct_type", struct_type_to_json(u.struct_type)),
+            ("generics", generics_to_json(&u.generics)),
+            ("fields", items_to_json(&u.fields)),
+            ("fields_stripped", u.fields_stripped.to_json()),
+        ]),
+        EnumItem(ref e) => object(vec![
+            ("generics", generics_to_json(&e.generics)),
+            ("variants", items_to_json(e.variants.iter())),
+            ("variants_stripped", e.variants_stripped.to_json()),
+        ]),
+        VariantItem(ref v) => match v.kind {
+            clean::VariantKind::CLike => object(vec![
+                ("variant_kind", "plain".to_json()),
+            ]),
+            clean::VariantKind::Tuple(ref types) => object(vec![
+                ("variant_kind", "tuple".to_json()),
+                ("fields", Json::Array(types.iter().map(type_to_json).collect())),
+            ]),
+            clean::VariantKind::Struct(ref s) => object(vec![
+                ("variant_kind", "struct".to_json()),
+                ("fields", items_to_json(&s.fields)),
+                ("fields_stripped", s.fields_stripped.to_json()),
+            ]),
+        },
+        StructFieldItem(ref ty) => object(vec![("type", type_to_json(ty))]),
+        FunctionItem(ref f) | ForeignFunctionItem(ref f) => {
+            function_to_json(&f.decl, &f.generics, &f.header, true)
+        }
+        TyMethodItem(ref m) => function_to_json(&m.decl, &m.generics, &m.header, false),
+        MethodItem(ref m) => function_to_json(&m.decl, &m.generics, &m.header, true),
+        TypedefItem(ref t, _) => object(vec![
+            ("type", type_to_json(&t.type_)),
+            ("generics", generics_to_json(&t.generics)),
+        ]),
+        OpaqueTyItem(ref t, _) => object(vec![
+            ("bounds", bounds_to_json(&t.bounds)),
+            ("generics", generics_to_json(&t.generics)),
+        ]),
+        StaticItem(ref s) | ForeignStaticItem(ref s) => object(vec![
+            ("type", type_to_json(&s.type_)),
+            ("mutable", (s.mutability == clean::Mutable).to_json()),
+            ("expr", s.expr.to_json()),
+        ]),
+        ConstantItem(ref c) => object(vec![
+            ("type", type_to_json(&c.type_)),
+            ("expr", c.expr.to_json()),
+        ]),
+        TraitItem(ref t) => object(vec![
+            ("is_auto", t.is_auto.to_json()),
+            ("is_unsafe", (t.unsafety == hir::Unsafety::Unsafe).to_json()),
+            ("generics", generics_to_json(&t.generics)),
+            ("bounds", bounds_to_json(&t.bounds)),
+            ("items", items_to_json(&t.items)),
+        ]),
+        TraitAliasItem(ref t) => object(vec![
+            ("generics", generics_to_json(&t.generics)),
+            ("bounds", bounds_to_json(&t.bounds)),
+        ]),
+        ImplItem(ref i) => impl_to_json(i),
+        AssocConstItem(ref ty, ref default) => object(vec![
+            ("type", type_to_json(ty)),
+            ("default", default.to_json()),
+        ]),
+        AssocTypeItem(ref bounds, ref default) => object(vec![
+            ("bounds", bounds_to_json(bounds)),
+            ("default", default.as_ref().map_or(Json::Null, type_to_json)),
+        ]),
+        ForeignTypeItem => object(vec![]),
+        MacroItem(ref m) => object(vec![("source", m.source.to_json())]),
+        ProcMacroItem(ref m) => object(vec![
+            ("macro_kind", m.kind.descr().to_json()),
+            ("helpers", m.helpers.to_json()),
+        ]),
+        PrimitiveItem(prim) => object(vec![("primitive", prim.as_str().to_json())]),
+        KeywordItem(ref keyword) => object(vec![("keyword", keyword.to_json())]),
+        StrippedItem(..) => unreachable!("stripped items are skipped by `item_to_json`"),
+    }
+}
+
+fn items_to_json<'a, I>(items: I) -> Json
+    where I: IntoIterator<Item = &'a clean::Item>
+{
+    Json::Array(items.into_iter().filter_map(item_to_json).collect())
+}
+
+fn impl_to_json(i: &clean::Impl) -> Json {
+    let mut provided_trait_methods = i.provided_trait_methods.iter().cloned().collect::<Vec<_>>();
+    provided_trait_methods.sort();
+
+    object(vec![
+        ("is_unsafe", (i.unsafety == hir::Unsafety::Unsafe).to_json()),
+        ("generics", generics_to_json(&i.generics)),
+        ("trait", i.trait_.as_ref().map_or(Json::Null, type_to_json)),
+        ("for", type_to_json(&i.for_)),
+        // The id of the `Self` type, if it's a path to an item.
+        ("for_id", match i.for_ {
+            clean::ResolvedPath { did, .. } => id(did),
+            _ => Json::Null,
+        }),
+        ("negative", (i.polarity == Some(clean::ImplPolarity::Negative)).to_json()),
+        // `true` for impls of auto traits that were synthesized by rustdoc.
+        ("synthetic", i.synthetic.to_json()),
+        // The type of the blanket impl this impl was derived from, if any.
+        ("blanket_impl", i.blanket_impl.as_ref().map_or(Json::Null, type_to_json)),
+        ("provided_trait_methods", provided_trait_methods.to_json()),
+        ("items", items_to_json(&i.items)),
+    ])
+}
+
+fn import_to_json(import: &clean::Import) -> Json {
+    let (name, source, glob) = match *import {
+        clean::Import::Simple(ref name, ref source) => (Some(name), source, false),
+        clean::Import::Glob(ref source) => (None, source, true),
+    };
+    object(vec![
+        ("source", format!("{:#}", source.path.print()).to_json()),
+        ("name", name.cloned().to_json()),
+        ("id", source.did.map_or(Json::Null, id)),
+        ("glob", glob.to_json()),
+    ])
+}
+
+fn function_to_json(
+    decl: &clean::FnDecl,
+    generics: &clean::Generics,
+    header: &hir::FnHeader,
+    has_body: bool,
+) -> Json {
+    let inputs = decl.inputs.values.iter().map(|arg| object(vec![
+        ("name", arg.name.to_json()),
+        ("type", type_to_json(&arg.type_)),
+    ])).collect();
+    let output = match decl.output {
+        clean::Return(ref ty) => type_to_json(ty),
+        clean::DefaultReturn => Json::Null,
+    };
+
+    object(vec![
+        ("decl", object(vec![
+            ("inputs", Json::Array(inputs)),
+            ("output", output),
+            ("c_variadic", decl.c_variadic.to_json()),
+        ])),
+        ("generics", generics_to_json(generics)),
+        ("header", object(vec![
+            ("is_unsafe", (header.unsafety == hir::Unsafety::Unsafe).to_json()),
+            ("is_const", (header.constness == hir::Constness::Const).to_json()),
+            ("is_async", (header.asyncness == hir::IsAsync::Async).to_json()),
+            ("abi", header.abi.name().to_json()),
+        ])),
+        ("has_body", has_body.to_json()),
+    ])
+}
+
+fn generics_to_json(generics: &clean::Generics) -> Json {
+    let params = generics.params.iter().map(|param| {
+        let mut fields = vec![("name", param.name.to_json())];
+        match param.kind {
+            clean::GenericParamDefKind::Lifetime => {
+                fields.push(("kind", "lifetime".to_json()));
+            }
+            clean::GenericParamDefKind::Type { ref bounds, ref default, ref synthetic, .. } => {
+                fields.push(("kind", "type".to_json()));
+                fields.push(("bounds", bounds_to_json(bounds)));
+                fields.push(("default", default.as_ref().map_or(Json::Null, type_to_json)));
+                // `impl Trait` in argument position.
+                fields.push(("synthetic", synthetic.is_some().to_json()));
+            }
+            clean::GenericParamDefKind::Const { ref ty, .. } => {
+                fields.push(("kind", "const".to_json()));
+                fields.push(("type", type_to_json(ty)));
+            }
+        }
+        object(fields)
+    }).collect();
+
+    let where_predicates = generics.where_predicates.iter().map(|pred| match *pred {
+        clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => object(vec![
+            ("kind", "bound".to_json()),
+            ("type", type_to_json(ty)),
+            ("bounds", bounds_to_json(bounds)),
+        ]),
+        clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => object(vec![
+            ("kind", "region".to_json()),
+            ("lifetime", lifetime.print().to_json()),
+            ("bounds", bounds_to_json(bounds)),
+        ]),
+        clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => object(vec![
+            ("kind", "eq".to_json()),
+            ("lhs", type_to_json(lhs)),
+            ("rhs", type_to_json(rhs)),
+        ]),
+    }).collect();
+
+    object(vec![
+        ("params", Json::Array(params)),
+        ("where_predicates", Json::Array(where_predicates)),
+    ])
+}
+
+fn bounds_to_json(bounds: &[clean::GenericBound]) -> Json {
+    Json::Array(bounds.iter().map(|b| format!("{:#}", b.print()).to_json()).collect())
+}
+
+fn type_to_json(ty: &clean::Type) -> Json {
+    format!("{:#}", ty.print()).to_json()
+}
+
+fn struct_type_to_json(struct_type: doctree::StructType) -> Json {
+    match struct_type {
+        doctree::Plain => "plain",
+        doctree::Tuple => "tuple",
+        doctree::Unit => "unit",
+    }.to_json()
+}
+
+fn visibility_to_json(vis: &clean::Visibility) -> Json {
+    match *vis {
+        clean::Public => "public".to_json(),
+        clean::Inherited => "default".to_json(),
+        clean::Visibility::Crate => "crate".to_json(),
+        clean::Visibility::Restricted(_, ref path) => object(vec![
+            ("restricted", format!("{:#}", path.print()).to_json()),
+        ]),
+    }
+}
+
+fn span_to_json(span: &clean::Span) -> Json {
+    if span.original.is_dummy() {
+        return Json::Null;
+    }
+    object(vec![
+        ("filename", span.filename.to_string().to_json()),
+        ("begin", vec![span.loline, span.locol].to_json()),
+        ("end", vec![span.hiline, span.hicol].to_json()),
+    ])
+}
+
+fn stability_to_json(stab: &clean::Stability) -> Json {
+    let level = match stab.level {
+        StabilityLevel::Stable => "stable",
+        StabilityLevel::Unstable => "unstable",
+    };
+    object(vec![
+        ("level", level.to_json()),
+        ("feature", stab.feature.to_json()),
+        ("since", stab.since.to_json()),
+        ("unstable_reason", stab.unstable_reason.to_json()),
+        ("issue", stab.issue.to_json()),
+    ])
+}
+
+fn deprecation_to_json(depr: &clean::Deprecation) -> Json {
+    object(vec![
+        ("since", depr.since.to_json()),
+        ("note", depr.note.to_json()),
+    ])
+}
+
+/// A crate-qualified id for `did`, e.g. `0:42`, used to refer to items across
+/// the document. The crate number is 0 for the documented crate, and a key of
+/// `external_crates` otherwise.
+fn id(did: DefId) -> Json {
+    format!("{}:{}", did.krate.as_u32(), did.index.as_u32()).to_json()
+}
+
+crate fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+}
@@ -52,12 +52,14 @@ use rustc::session::config::{ErrorOutputType, RustcOptGroup, make_crate_type_opt
 #[macro_use]
 mod externalfiles;
 
+mod api_diff;
 mod clean;
 mod config;
 mod core;
 mod docfs;
 mod doctree;
 mod fold;
+mod json;
 pub mod html {
     crate mod highlight;
     crate mod escape;
@@ -72,9 +74,11 @@ pub mod html {
 }
 mod markdown;
 mod passes;
+mod scrape_examples;
 mod visit_ast;
 mod visit_lib;
 mod test;
+mod text;
 mod theme;
 
 struct Output {
@@ -128,7 +132,8 @@ fn opts() -> Vec<RustcOptGroup> {
                      "[rust]")
         }),
         stable("w", |o| {
-            o.optopt("w", "output-format", "the output type to write", "[html]")
+            o.optopt("w", "output-format", "the output type to write",
+                      "[html|json|man|markdown]")
         }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
@@ -351,11 +356,56 @@ fn opts() -> Vec<RustcOptGroup> {
                       "generate-redirect-pages",
                       "Generate extra pages to support legacy URLs and tool links")
         }),
+        unstable("generate-link-to-definition", |o| {
+            o.optflag("",
+                      "generate-link-to-definition",
+                      "Make identifiers in the source pages link to their definitions")
+        }),
+        unstable("full-text-search", |o| {
+            o.optflag("",
+                      "full-text-search",
+                      "Make the search find items by the words of their documentation")
+        }),
         unstable("show-coverage", |o| {
             o.optflag("",
                       "show-coverage",
                       "calculate percentage of public items with documentation")
         }),
+        unstable("coverage-threshold", |o| {
+            o.optopt("",
+                     "coverage-threshold",
+                     "with `--show-coverage`, fail if less than this percentage of items are \
+                      documented",
+                     "PERCENT")
+        }),
+        unstable("api-diff", |o| {
+            o.optopt("",
+                     "api-diff",
+                     "compare the public API with the one described by a JSON file written by \
+                      `--output-format json` for a previous version, and report breaking changes",
+                     "PATH")
+        }),
+        unstable("scrape-examples-output-path", |o| {
+            o.optopt("",
+                     "scrape-examples-output-path",
+                     "write the calls made to the functions of the crates given by \
+                      `--scrape-examples-target-crate` to a file, instead of generating \
+                      documentation",
+                     "PATH")
+        }),
+        unstable("scrape-examples-target-crate", |o| {
+            o.optmulti("",
+                       "scrape-examples-target-crate",
+                       "crate whose functions `--scrape-examples-output-path` records calls to",
+                       "NAME")
+        }),
+        unstable("with-examples", |o| {
+            o.optmulti("",
+                       "with-examples",
+                       "show the calls in a file written by `--scrape-examples-output-path` on \
+                        the pages of the functions they call",
+                       "PATH")
+        }),
         unstable("enable-per-target-ignores", |o| {
             o.optflag("",
                       "enable-per-target-ignores",
@@ -373,6 +423,17 @@ fn opts() -> Vec<RustcOptGroup> {
                        "",
                        "One (of possibly many) arguments to pass to the runtool")
         }),
+        unstable("merge-doctests", |o| {
+            o.optflag("",
+                      "merge-doctests",
+                      "compile doctests into a single binary where possible, instead of one \
+                       binary per doctest")
+        }),
+        unstable("check-ignored-doctests", |o| {
+            o.optflag("",
+                      "check-ignored-doctests",
+                      "type-check the doctests marked `ignore` and report the ones that compile")
+        }),
         unstable("test-builder", |o| {
             o.optflag("",
                       "test-builder",
@@ -418,6 +479,10 @@ fn main_options(options: config::Options) -> i32 {
     match (options.should_test, options.markdown_input()) {
         (true, true) => return markdown::test(options, &diag),
         (true, false) => return test::run(options),
+        (false, true) if options.render_options.output_format != config::OutputFormat::Html => {
+            diag.struct_err("Markdown files can only be rendered to HTML").emit();
+            return rustc_driver::EXIT_FAILURE;
+        }
         (false, true) => return markdown::render(options.input,
                                                  options.render_options,
                                                  &diag,
@@ -432,24 +497,42 @@ fn main_options(options: config::Options) -> i32 {
                      options.debugging_options.ui_testing,
                      options.edition);
     let show_coverage = options.show_coverage;
+    let scrape_examples = options.scrape_examples_output.is_some();
+    let api_baseline = options.api_diff.clone();
     rust_input(options, move |out| {
         if show_coverage {
             // if we ran coverage, bail early, we don't need to also generate docs at this point
             // (also we didn't load in any of the useful passes)
             return rustc_driver::EXIT_SUCCESS;
         }
+        if scrape_examples {
+            // the calls file was written while the crate was compiled
+            return rustc_driver::EXIT_SUCCESS;
+        }
 
         let Output { krate, renderinfo, renderopts } = out;
-        info!("going to format");
         let (error_format, treat_err_as_bug, ui_testing, edition) = diag_opts;
         let diag = core::new_handler(error_format, None, treat_err_as_bug, ui_testing);
-        match html::render::run(
-            krate,
-            renderopts,
-            renderinfo,
-            &diag,
-            edition,
-        ) {
+        if let Some(baseline) = api_baseline {
+            return api_diff::run(&krate, &baseline, renderopts.output_format, &diag);
+        }
+
+        info!("going to format");
+        let res = match renderopts.output_format {
+            config::OutputFormat::Html => html::render::run(
+                krate,
+                renderopts,
+                renderinfo,
+                &diag,
+                edition,
+            ),
+            config::OutputFormat::Json => json::run(krate, renderopts),
+            config::OutputFormat::Man => text::run(krate, renderopts, text::Format::Man),
+            config::OutputFormat::Markdown => {
+                text::run(krate, renderopts, text::Format::Markdown)
+            }
+        };
+        match res {
             Ok(_) => rustc_driver::EXIT_SUCCESS,
             Err(e) => {
                 diag.struct_err(&format!("couldn't generate documentation: {}", e.error))
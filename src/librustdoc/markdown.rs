@@ -3,7 +3,6 @@ use std::io::prelude::*;
 use std::path::PathBuf;
 
 use errors;
-use testing;
 use syntax::edition::Edition;
 use syntax::source_map::DUMMY_SP;
 use syntax::feature_gate::UnstableFeatures;
@@ -13,7 +12,7 @@ use crate::config::{Options, RenderOptions};
 use crate::html::escape::Escape;
 use crate::html::markdown;
 use crate::html::markdown::{ErrorCodes, IdMap, Markdown, MarkdownWithToc, find_testable_code};
-use crate::test::{TestOptions, Collector};
+use crate::test::{TestOptions, Collector, run_tests};
 
 /// Separate any lines at the start of the file that begin with `# ` or `%`.
 fn extract_leading_metadata(s: &str) -> (Vec<&str>, &str) {
@@ -132,7 +131,7 @@ pub fn render(
 }
 
 /// Runs any tests/code examples in the markdown file `input`.
-pub fn test(mut options: Options, diag: &errors::Handler) -> i32 {
+pub fn test(options: Options, diag: &errors::Handler) -> i32 {
     let input_str = match load_string(&options.input, diag) {
         Ok(s) => s,
         Err(LoadStringError::ReadFail) => return 1,
@@ -150,8 +149,5 @@ pub fn test(mut options: Options, diag: &errors::Handler) -> i32 {
 
     find_testable_code(&input_str, &mut collector, codes, options.enable_per_target_ignores);
 
-    options.test_args.insert(0, "rustdoctest".to_string());
-    testing::test_main(&options.test_args, collector.tests,
-                       Some(testing::Options::new().display_output(options.display_warnings)));
-    0
+    run_tests(options.test_args, options.display_warnings, collector)
 }
@@ -1,8 +1,12 @@
 use crate::clean;
+use crate::config::OutputFormat;
 use crate::core::DocContext;
 use crate::fold::{self, DocFolder};
+use crate::html::markdown::{find_testable_code, ErrorCodes, Ignore, LangString};
+use crate::json::object;
 use crate::passes::Pass;
 
+use serialize::json::{Json, ToJson};
 use syntax::attr;
 use syntax_pos::FileName;
 use syntax::symbol::sym;
@@ -16,11 +20,25 @@ pub const CALCULATE_DOC_COVERAGE: Pass = Pass {
     description: "counts the number of items with and without documentation",
 };
 
-fn calculate_doc_coverage(krate: clean::Crate, _: &DocContext<'_>) -> clean::Crate {
+fn calculate_doc_coverage(krate: clean::Crate, ctx: &DocContext<'_>) -> clean::Crate {
     let mut calc = CoverageCalculator::default();
     let krate = calc.fold_crate(krate);
 
-    calc.print_results();
+    let renderinfo = ctx.renderinfo.borrow();
+    match renderinfo.output_format {
+        OutputFormat::Html | OutputFormat::Man | OutputFormat::Markdown => calc.print_results(),
+        OutputFormat::Json => println!("{}", calc.to_json()),
+    }
+
+    if let Some(threshold) = renderinfo.coverage_threshold {
+        // A crate without any items to document can't miss the threshold.
+        let percentage = calc.total().percentage();
+        if let Some(percentage) = percentage.filter(|&percentage| percentage < threshold) {
+            ctx.sess().struct_err(&format!("documentation coverage of {:.1}% is below the \
+                                            threshold of {:.1}%", percentage, threshold))
+                .emit();
+        }
+    }
 
     krate
 }
@@ -29,15 +47,19 @@ fn calculate_doc_coverage(krate: clean::Crate, _: &DocContext<'_>) -> clean::Cra
 struct ItemCount {
     total: u64,
     with_docs: u64,
+    with_examples: u64,
 }
 
 impl ItemCount {
-    fn count_item(&mut self, has_docs: bool) {
+    fn count_item(&mut self, has_docs: bool, has_examples: bool) {
         self.total += 1;
 
         if has_docs {
             self.with_docs += 1;
         }
+        if has_examples {
+            self.with_examples += 1;
+        }
     }
 
     fn percentage(&self) -> Option<f64> {
@@ -47,6 +69,24 @@ impl ItemCount {
             None
         }
     }
+
+    fn examples_percentage(&self) -> Option<f64> {
+        if self.total > 0 {
+            Some((self.with_examples as f64 * 100.0) / self.total as f64)
+        } else {
+            None
+        }
+    }
+}
+
+impl ToJson for ItemCount {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("total", self.total.to_json()),
+            ("with_docs", self.with_docs.to_json()),
+            ("with_examples", self.with_examples.to_json()),
+        ])
+    }
 }
 
 impl ops::Sub for ItemCount {
@@ -56,6 +96,7 @@ impl ops::Sub for ItemCount {
         ItemCount {
             total: self.total - rhs.total,
             with_docs: self.with_docs - rhs.with_docs,
+            with_examples: self.with_examples - rhs.with_examples,
         }
     }
 }
@@ -64,6 +105,7 @@ impl ops::AddAssign for ItemCount {
     fn add_assign(&mut self, rhs: Self) {
         self.total += rhs.total;
         self.with_docs += rhs.with_docs;
+        self.with_examples += rhs.with_examples;
     }
 }
 
@@ -73,21 +115,43 @@ struct CoverageCalculator {
 }
 
 impl CoverageCalculator {
+    fn total(&self) -> ItemCount {
+        let mut total = ItemCount::default();
+        for &count in self.items.values() {
+            total += count;
+        }
+        total
+    }
+
+    fn to_json(&self) -> Json {
+        let files = self.items.iter()
+            .filter(|(_, count)| count.total > 0)
+            .map(|(file, count)| (file.to_string(), count.to_json()))
+            .collect();
+
+        object(vec![
+            ("files", Json::Object(files)),
+            ("total", self.total().to_json()),
+        ])
+    }
+
     fn print_results(&self) {
         let mut total = ItemCount::default();
 
         fn print_table_line() {
-            println!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+", "");
+            println!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+", "");
         }
 
-        fn print_table_record(name: &str, count: ItemCount, percentage: f64) {
-            println!("| {:<35} | {:>10} | {:>10} | {:>9.1}% |",
-                     name, count.with_docs, count.total, percentage);
+        fn print_table_record(name: &str, count: ItemCount, percentage: f64,
+                              examples_percentage: f64) {
+            println!("| {:<35} | {:>10} | {:>10} | {:>9.1}% | {:>10} | {:>9.1}% |",
+                     name, count.with_docs, count.total, percentage,
+                     count.with_examples, examples_percentage);
         }
 
         print_table_line();
-        println!("| {:<35} | {:>10} | {:>10} | {:>10} |",
-                 "File", "Documented", "Total", "Percentage");
+        println!("| {:<35} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} |",
+                 "File", "Documented", "Total", "Percentage", "Examples", "Percentage");
         print_table_line();
 
         for (file, &count) in &self.items {
@@ -100,14 +164,16 @@ impl CoverageCalculator {
                     name = "...".to_string() + &name[name.len()-32..];
                 }
 
-                print_table_record(&name, count, percentage);
+                print_table_record(&name, count, percentage,
+                                   count.examples_percentage().unwrap_or(0.0));
 
                 total += count;
             }
         }
 
         print_table_line();
-        print_table_record("Total", total, total.percentage().unwrap_or(0.0));
+        print_table_record("Total", total, total.percentage().unwrap_or(0.0),
+                           total.examples_percentage().unwrap_or(0.0));
         print_table_line();
     }
 }
@@ -115,6 +181,11 @@ impl CoverageCalculator {
 impl fold::DocFolder for CoverageCalculator {
     fn fold_item(&mut self, i: clean::Item) -> Option<clean::Item> {
         let has_docs = !i.attrs.doc_strings.is_empty();
+        let has_examples = has_docs && i.attrs.collapsed_doc_value().map_or(false, |doc| {
+            let mut tests = Tests { found_examples: 0 };
+            find_testable_code(&doc, &mut tests, ErrorCodes::No, false);
+            tests.found_examples > 0
+        });
 
         match i.inner {
             _ if !i.def_id.is_local() => {
@@ -159,10 +230,23 @@ impl fold::DocFolder for CoverageCalculator {
                 debug!("counting {:?} {:?} in {}", i.type_(), i.name, i.source.filename);
                 self.items.entry(i.source.filename.clone())
                           .or_default()
-                          .count_item(has_docs);
+                          .count_item(has_docs, has_examples);
             }
         }
 
         self.fold_item_recur(i)
     }
 }
+
+/// Counts the code examples in some docs that are run as doctests.
+struct Tests {
+    found_examples: usize,
+}
+
+impl crate::test::Tester for Tests {
+    fn add_test(&mut self, _: String, config: LangString, _: usize) {
+        if config.ignore == Ignore::None {
+            self.found_examples += 1;
+        }
+    }
+}
@@ -7,7 +7,7 @@ use syntax_pos::{InnerSpan, FileName};
 use crate::clean;
 use crate::core::DocContext;
 use crate::fold::DocFolder;
-use crate::html::markdown::{self, RustCodeBlock};
+use crate::html::markdown::{self, CodeBlock};
 use crate::passes::Pass;
 
 pub const CHECK_CODE_BLOCK_SYNTAX: Pass = Pass {
@@ -25,7 +25,7 @@ struct SyntaxChecker<'a, 'tcx> {
 }
 
 impl<'a, 'tcx> SyntaxChecker<'a, 'tcx> {
-    fn check_rust_syntax(&self, item: &clean::Item, dox: &str, code_block: RustCodeBlock) {
+    fn check_rust_syntax(&self, item: &clean::Item, dox: &str, code_block: CodeBlock) {
         let sess = ParseSess::new(FilePathMapping::empty());
         let source_file = sess.source_map().new_source_file(
             FileName::Custom(String::from("doctest")),
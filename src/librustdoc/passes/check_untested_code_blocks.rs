@@ -0,0 +1,179 @@
+use errors::emitter::EmitterWriter;
+use errors::Handler;
+use rustc::hir::HirId;
+use rustc::lint;
+use rustc_data_structures::sync::Lrc;
+use std::io;
+use syntax::parse::lexer::{StringReader as Lexer};
+use syntax::parse::{ParseSess, token};
+use syntax::source_map::{FilePathMapping, SourceMap};
+use syntax::symbol::kw;
+use syntax::util::lev_distance::lev_distance;
+use syntax_pos::FileName;
+
+use crate::clean;
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::html::markdown::{self, CodeBlock, Ignore};
+use crate::passes::Pass;
+
+pub const CHECK_UNTESTED_CODE_BLOCKS: Pass = Pass {
+    name: "check-untested-code-blocks",
+    pass: check_untested_code_blocks,
+    description: "detects Rust code blocks that are `ignore`d or tagged with an unknown language",
+};
+
+/// The attributes of a code block that make it a doctest, which typos in tags are compared to.
+const DOCTEST_ATTRIBUTES: &[&str] = &[
+    "rust", "should_panic", "no_run", "ignore", "allow_fail", "test_harness", "compile_fail",
+    "edition2015", "edition2018",
+];
+
+/// Languages that code blocks in docs are commonly tagged with, and which aren't mistakes.
+const KNOWN_LANGUAGES: &[&str] = &[
+    "text", "txt", "plain", "none", "output", "console", "sh", "bash", "shell", "zsh", "fish",
+    "powershell", "cmd", "bat", "toml", "json", "yaml", "yml", "ini", "xml", "html", "css", "js",
+    "javascript", "ts", "typescript", "c", "h", "cpp", "cxx", "c++", "objc", "python", "py",
+    "java", "go", "ruby", "sql", "diff", "patch", "markdown", "md", "dot", "asm", "llvm", "wasm",
+    "wat", "ebnf", "grammar", "regex", "math", "latex", "tex", "mermaid",
+];
+
+pub fn check_untested_code_blocks(krate: clean::Crate, cx: &DocContext<'_>) -> clean::Crate {
+    UntestedCodeBlockLinter { cx }.fold_crate(krate)
+}
+
+struct UntestedCodeBlockLinter<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+}
+
+impl<'a, 'tcx> UntestedCodeBlockLinter<'a, 'tcx> {
+    fn check_code_block(&self, item: &clean::Item, dox: &str, code_block: CodeBlock) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            // If non-local, no need to check anything.
+            None => return,
+        };
+
+        let (message, help) = if code_block.lang_string.rust {
+            if code_block.lang_string.ignore != Ignore::All ||
+                !looks_like_rust(&dox[code_block.code.clone()])
+            {
+                return;
+            }
+            (
+                String::from("this Rust code block is marked `ignore`, so it is never tested"),
+                "if the example compiles, remove `ignore`, and use `no_run` if it shouldn't be \
+                 run; `rustdoc --test -Z unstable-options --check-ignored-doctests` lists the \
+                 ignored examples that compile today",
+            )
+        } else {
+            let syntax = code_block.syntax.as_ref().map(|s| &**s).unwrap_or("");
+            let tags = syntax
+                .split(|c: char| !(c == '_' || c == '-' || c == '+' || c.is_alphanumeric()))
+                .filter(|tag| !tag.is_empty());
+            let mut unknown = None;
+            for tag in tags {
+                if KNOWN_LANGUAGES.contains(&&*tag.to_lowercase()) {
+                    return;
+                }
+                if let Some(attr) = misspelled_attribute(tag) {
+                    let mut diag = self.lint(hir_id, item, dox, &code_block, &format!(
+                        "unknown attribute `{}` on code block, so it is never tested", tag));
+                    diag.help(&format!("did you mean `{}`?", attr));
+                    diag.emit();
+                    return;
+                }
+                unknown = unknown.or(Some(tag));
+            }
+            let unknown = match unknown {
+                Some(unknown) if looks_like_rust(&dox[code_block.code.clone()]) => unknown,
+                _ => return,
+            };
+            (
+                format!("code block tagged with unknown language `{}` looks like Rust, \
+                         but is never tested", unknown),
+                "tag the code block as `rust` if it is Rust code, or as `text` if it isn't",
+            )
+        };
+
+        let mut diag = self.lint(hir_id, item, dox, &code_block, &message);
+        diag.help(help);
+        diag.emit();
+    }
+
+    fn lint(
+        &self,
+        hir_id: HirId,
+        item: &clean::Item,
+        dox: &str,
+        code_block: &CodeBlock,
+        message: &str,
+    ) -> errors::DiagnosticBuilder<'tcx> {
+        let sp = super::source_span_for_markdown_range(self.cx, dox, &code_block.range,
+                                                       &item.attrs)
+            .or_else(|| super::span_of_attrs(&item.attrs))
+            .unwrap_or(item.source.span());
+        self.cx.tcx.struct_span_lint_hir(lint::builtin::UNTESTED_DOC_CODE_EXAMPLES,
+                                         hir_id, sp, message)
+    }
+}
+
+impl<'a, 'tcx> DocFolder for UntestedCodeBlockLinter<'a, 'tcx> {
+    fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
+        if let Some(dox) = &item.attrs.collapsed_doc_value() {
+            for code_block in markdown::code_blocks(&dox) {
+                self.check_code_block(&item, &dox, code_block);
+            }
+        }
+
+        self.fold_item_recur(item)
+    }
+}
+
+/// Returns the doctest attribute that `tag` is a misspelling of, like `no_run` for `no-run`.
+fn misspelled_attribute(tag: &str) -> Option<&'static str> {
+    let normalized = tag.to_lowercase().replace('-', "_");
+    DOCTEST_ATTRIBUTES.iter().cloned().filter(|attr| *attr != tag).find(|attr| {
+        normalized == *attr || (attr.len() > 4 && lev_distance(&normalized, attr) <= 2)
+    })
+}
+
+/// Whether `code` lexes as Rust and has something that's distinctly Rust in it: a keyword like
+/// `fn` or `let`, or a macro call like `println!(`.
+fn looks_like_rust(code: &str) -> bool {
+    // Blocks that don't lex are reported by the `check-code-block-syntax` pass, so errors are
+    // just thrown away.
+    let source_map = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+    let emitter = EmitterWriter::new(box io::sink(), None, false, false, false, None, false);
+    let sess = ParseSess::with_span_handler(Handler::with_emitter(false, None, box emitter),
+                                            source_map);
+    let source_file = sess.source_map().new_source_file(
+        FileName::Custom(String::from("doctest")),
+        code.to_owned(),
+    );
+
+    let mut lexer = Lexer::new(&sess, source_file, None);
+    let mut rusty = false;
+    let mut after_ident = false;
+    let mut after_macro_bang = false;
+    loop {
+        let kind = lexer.next_token().kind;
+        match kind {
+            token::Eof => break,
+            token::Whitespace | token::Comment => continue,
+            token::Unknown(..) => return false,
+            token::Ident(name, false) if [
+                kw::Fn, kw::Let, kw::Impl, kw::Struct, kw::Enum, kw::Trait, kw::Use, kw::Mod,
+                kw::Pub, kw::Match, kw::Extern, kw::Unsafe, kw::Mut, kw::Crate,
+            ].contains(&name) => rusty = true,
+            token::OpenDelim(_) if after_macro_bang => rusty = true,
+            _ => {}
+        }
+        after_macro_bang = after_ident && kind == token::Not;
+        after_ident = match kind {
+            token::Ident(..) => true,
+            _ => false,
+        };
+    }
+    rusty
+}
@@ -44,6 +44,9 @@ pub use self::collect_trait_impls::COLLECT_TRAIT_IMPLS;
 mod check_code_block_syntax;
 pub use self::check_code_block_syntax::CHECK_CODE_BLOCK_SYNTAX;
 
+mod check_untested_code_blocks;
+pub use self::check_untested_code_blocks::CHECK_UNTESTED_CODE_BLOCKS;
+
 mod calculate_doc_coverage;
 pub use self::calculate_doc_coverage::CALCULATE_DOC_COVERAGE;
 
@@ -69,6 +72,7 @@ pub const PASSES: &[Pass] = &[
     PROPAGATE_DOC_CFG,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
+    CHECK_UNTESTED_CODE_BLOCKS,
     COLLECT_TRAIT_IMPLS,
     CALCULATE_DOC_COVERAGE,
 ];
@@ -79,6 +83,7 @@ pub const DEFAULT_PASSES: &[Pass] = &[
     COLLAPSE_DOCS,
     UNINDENT_COMMENTS,
     CHECK_PRIVATE_ITEMS_DOC_TESTS,
+    CHECK_UNTESTED_CODE_BLOCKS,
     STRIP_HIDDEN,
     STRIP_PRIVATE,
     COLLECT_INTRA_DOC_LINKS,
@@ -92,6 +97,7 @@ pub const DEFAULT_PRIVATE_PASSES: &[Pass] = &[
     COLLAPSE_DOCS,
     UNINDENT_COMMENTS,
     CHECK_PRIVATE_ITEMS_DOC_TESTS,
+    CHECK_UNTESTED_CODE_BLOCKS,
     STRIP_PRIV_IMPORTS,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
@@ -0,0 +1,375 @@
+//! Scraping usage examples from the crate's examples.
+//!
+//! With `--scrape-examples-output-path`, rustdoc compiles an example crate and writes the calls it
+//! makes to the functions of the crates named by `--scrape-examples-target-crate` into a "calls
+//! file", along with a few lines of code around them. When the target crate is documented with
+//! `--with-examples`, the calls are matched with its functions and methods, and their pages show
+//! the snippets under "Examples found in repository". The source pages of the examples are read
+//! from the files the calls file points to.
+
+use crate::core::DocContext;
+use crate::externalfiles::load_string;
+use crate::json::object;
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::def::{DefKind, Res};
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::ty::{TyCtxt, TypeckTables};
+use rustc_data_structures::fx::FxHashMap;
+use serialize::json::{self, Json, ToJson};
+use syntax::source_map::SourceMap;
+use syntax_pos::{BytePos, FileName, Span};
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The version of the format of calls files. Bump it whenever the format changes.
+const FORMAT_VERSION: u64 = 2;
+
+/// The number of lines of code shown before and after the calls in a snippet.
+const CONTEXT_LINES: usize = 2;
+
+/// A snippet of an example that calls a documented function.
+#[derive(Clone, Debug)]
+crate struct ScrapedExample {
+    /// The name of the example crate.
+    crate krate: String,
+    /// The path of the example's source file, as it was given to rustdoc.
+    crate file: String,
+    /// The line of the file that the snippet starts on.
+    crate line: usize,
+    /// The lines of code around the calls.
+    crate code: String,
+    /// The byte ranges in `code` of the names of the called functions.
+    crate calls: Vec<(u32, u32)>,
+}
+
+/// The examples loaded with `--with-examples`.
+#[derive(Default)]
+crate struct ScrapedExamples {
+    /// The snippets that call each local function or method.
+    crate calls: FxHashMap<DefId, Vec<ScrapedExample>>,
+    /// The source of each example file that could be read, keyed by the name of its crate and its
+    /// path.
+    crate files: BTreeMap<(String, String), String>,
+}
+
+/// Identifies a function in calls files. It is the same in the crate that defines the function
+/// and in the crates that call it.
+fn function_key(tcx: TyCtxt<'_>, did: DefId) -> String {
+    format!("{}{}", tcx.crate_name(did.krate), tcx.def_path(did).to_string_no_crate())
+}
+
+/// Finds the calls that the local crate makes to the functions of `target_crates` and writes
+/// them to the calls file `output`.
+crate fn scrape(cx: &DocContext<'_>, target_crates: &[String], output: &Path) {
+    let tcx = cx.tcx;
+    let mut finder = CallFinder {
+        tcx,
+        target_crates,
+        tables: None,
+        enclosing_items: vec![],
+        calls: BTreeMap::new(),
+    };
+    intravisit::walk_crate(&mut finder, tcx.hir().krate());
+
+    let krate = tcx.crate_name(LOCAL_CRATE).to_string();
+    let source_map = cx.sess().source_map();
+    let mut files = BTreeMap::new();
+    let calls = finder.calls.into_iter().map(|(key, items)| {
+        let examples = items.into_iter().filter_map(|(item, calls)| {
+            let (path, examples) = snippets(source_map, &krate, item, calls)?;
+            if let Some(example) = examples.first() {
+                // Point to the file with a path that still works when the target crate is
+                // documented from another directory.
+                files.entry(example.file.clone()).or_insert_with(|| {
+                    env::current_dir().map_or(path.clone(), |dir| dir.join(&path))
+                        .display().to_string().to_json()
+                });
+            }
+            Some(examples)
+        }).flatten().map(|example| object(vec![
+            ("file", example.file.to_json()),
+            ("line", example.line.to_json()),
+            ("code", example.code.to_json()),
+            ("calls", Json::Array(example.calls.iter().map(|&(lo, hi)| {
+                Json::Array(vec![lo.to_json(), hi.to_json()])
+            }).collect())),
+        ])).collect();
+        (key, Json::Array(examples))
+    }).collect();
+
+    let calls_file = object(vec![
+        ("format_version", FORMAT_VERSION.to_json()),
+        ("crate", krate.to_json()),
+        ("files", Json::Object(files)),
+        ("calls", Json::Object(calls)),
+    ]);
+    if let Err(e) = fs::write(output, calls_file.to_string()) {
+        cx.sess().struct_err(&format!("couldn't write `{}`: {}", output.display(), e)).emit();
+    }
+}
+
+/// Cuts snippets out of the code of the function `item`, each with the lines of a group of nearby
+/// `calls` and `CONTEXT_LINES` lines around them. Returns the path of the file along with the
+/// snippets.
+fn snippets(
+    source_map: &SourceMap,
+    krate: &str,
+    item: Span,
+    mut calls: Vec<Span>,
+) -> Option<(PathBuf, Vec<ScrapedExample>)> {
+    let sf = source_map.lookup_byte_offset(item.lo()).sf;
+    let path = match sf.name {
+        FileName::Real(ref path) => path.clone(),
+        _ => return None,
+    };
+    let src = sf.src.as_ref()?;
+    let first_line = sf.lookup_line(item.lo())?;
+    let last_line = sf.lookup_line(item.hi())?;
+
+    // Calls expanded from macros may point outside of the item, and the offsets of the calls are
+    // taken from the start of its lines, so those are left out.
+    calls.retain(|call| item.contains(*call));
+
+    // Groups the calls whose context lines overlap, as ranges of lines.
+    calls.sort();
+    let mut groups: Vec<(usize, usize, Vec<Span>)> = vec![];
+    for call in calls {
+        let line = sf.lookup_line(call.lo())?;
+        let lo = line.saturating_sub(CONTEXT_LINES).max(first_line);
+        let hi = (line + CONTEXT_LINES).min(last_line);
+        match groups.last_mut() {
+            Some((_, group_hi, group_calls)) if lo <= *group_hi + 1 => {
+                *group_hi = hi.max(*group_hi);
+                group_calls.push(call);
+            }
+            _ => groups.push((lo, hi, vec![call])),
+        }
+    }
+
+    let file = path.display().to_string();
+    let examples = groups.into_iter().map(|(lo, hi, calls)| {
+        let BytePos(start) = sf.line_bounds(lo).0 - sf.start_pos;
+        let BytePos(end) = sf.line_bounds(hi).1 - sf.start_pos;
+        let code = &src[start as usize..end as usize];
+        let code = code.trim_end_matches(|c: char| c == '\n' || c == '\r');
+        let calls = calls.iter().map(|call| {
+            let BytePos(call_lo) = call.lo() - sf.start_pos;
+            let BytePos(call_hi) = call.hi() - sf.start_pos;
+            (call_lo - start, call_hi - start)
+        }).collect();
+        ScrapedExample {
+            krate: krate.to_string(),
+            file: file.clone(),
+            line: lo + 1,
+            code: code.to_string(),
+            calls,
+        }
+    }).collect();
+    Some((path, examples))
+}
+
+struct CallFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    target_crates: &'a [String],
+    /// The type-check results of the body being visited.
+    tables: Option<&'tcx TypeckTables<'tcx>>,
+    /// The spans of the functions being visited, innermost last.
+    enclosing_items: Vec<Span>,
+    /// The calls found for each function, grouped by the function that makes them.
+    calls: BTreeMap<String, Vec<(Span, Vec<Span>)>>,
+}
+
+impl<'a, 'tcx> CallFinder<'a, 'tcx> {
+    fn add_call(&mut self, call: Span, res: Res) {
+        let did = match res {
+            Res::Def(DefKind::Fn, did) | Res::Def(DefKind::Method, did) => did,
+            _ => return,
+        };
+        let krate = self.tcx.crate_name(did.krate);
+        if call.from_expansion() || !self.target_crates.iter().any(|c| *c == *krate.as_str()) {
+            return;
+        }
+        let item = match self.enclosing_items.last() {
+            Some(&item) if !item.from_expansion() => item,
+            _ => return,
+        };
+
+        let items = self.calls.entry(function_key(self.tcx, did)).or_default();
+        match items.iter_mut().find(|(span, _)| *span == item) {
+            Some((_, calls)) => calls.push(call),
+            None => items.push((item, vec![call])),
+        }
+    }
+
+    fn with_enclosing_item(&mut self, span: Span, f: impl FnOnce(&mut Self)) {
+        self.enclosing_items.push(span);
+        f(self);
+        self.enclosing_items.pop();
+    }
+}
+
+impl<'a, 'tcx> intravisit::Visitor<'tcx> for CallFinder<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::All(self.tcx.hir())
+    }
+
+    fn visit_nested_body(&mut self, id: hir::BodyId) {
+        let old_tables = self.tables.replace(self.tcx.body_tables(id));
+        let body = self.tcx.hir().body(id);
+        self.visit_body(body);
+        self.tables = old_tables;
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        self.with_enclosing_item(item.span, |this| intravisit::walk_item(this, item));
+    }
+
+    fn visit_trait_item(&mut self, item: &'tcx hir::TraitItem) {
+        self.with_enclosing_item(item.span, |this| intravisit::walk_trait_item(this, item));
+    }
+
+    fn visit_impl_item(&mut self, item: &'tcx hir::ImplItem) {
+        self.with_enclosing_item(item.span, |this| intravisit::walk_impl_item(this, item));
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let Some(tables) = self.tables {
+            match expr.kind {
+                hir::ExprKind::Call(ref callee, _) => {
+                    if let hir::ExprKind::Path(ref qpath) = callee.kind {
+                        let name = match *qpath {
+                            hir::QPath::Resolved(_, ref path) => path.segments.last(),
+                            hir::QPath::TypeRelative(_, ref segment) => Some(&**segment),
+                        };
+                        if let Some(name) = name {
+                            let res = tables.qpath_res(qpath, callee.hir_id);
+                            self.add_call(name.ident.span, res);
+                        }
+                    }
+                }
+                hir::ExprKind::MethodCall(ref segment, ..) => {
+                    if let Some((kind, did)) = tables.type_dependent_def(expr.hir_id) {
+                        self.add_call(segment.ident.span, Res::Def(kind, did));
+                    }
+                }
+                _ => {}
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
+/// Loads the calls files `paths` and matches their calls with the functions and methods of the
+/// local crate.
+crate fn load(cx: &DocContext<'_>, paths: &[PathBuf]) -> ScrapedExamples {
+    let diag = cx.sess().diagnostic();
+    let mut examples = ScrapedExamples::default();
+    let mut calls = FxHashMap::<String, Vec<ScrapedExample>>::default();
+    for path in paths {
+        let contents = match load_string(path, diag) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+        let mut files = vec![];
+        let parsed = json::from_str(&contents).map_err(|e| e.to_string()).and_then(|file| {
+            parse_calls_file(&file, &mut files, &mut calls)
+        });
+        if let Err(e) = parsed {
+            diag.struct_err(&format!("error parsing `{}`: {}", path.display(), e)).emit();
+            continue;
+        }
+        for (key, src_path) in files {
+            match fs::read_to_string(&src_path) {
+                Ok(src) => {
+                    examples.files.insert(key, src);
+                }
+                Err(e) => {
+                    diag.struct_warn(&format!("couldn't read example `{}`: {}",
+                                              src_path.display(), e))
+                        .note("its snippets won't link to its source")
+                        .emit();
+                }
+            }
+        }
+    }
+
+    let tcx = cx.tcx;
+    let krate = tcx.hir().krate();
+    let items = krate.items.values().filter_map(|item| match item.kind {
+        hir::ItemKind::Fn(..) => Some(item.hir_id),
+        _ => None,
+    });
+    let trait_items = krate.trait_items.values().filter_map(|item| match item.kind {
+        hir::TraitItemKind::Method(..) => Some(item.hir_id),
+        _ => None,
+    });
+    let impl_items = krate.impl_items.values().filter_map(|item| match item.kind {
+        hir::ImplItemKind::Method(..) => Some(item.hir_id),
+        _ => None,
+    });
+    for hir_id in items.chain(trait_items).chain(impl_items) {
+        let did = tcx.hir().local_def_id(hir_id);
+        if let Some(mut found) = calls.remove(&function_key(tcx, did)) {
+            found.sort_by(|a, b| (&a.krate, &a.file, a.line).cmp(&(&b.krate, &b.file, b.line)));
+            examples.calls.insert(did, found);
+        }
+    }
+    examples
+}
+
+/// Parses a calls file into the paths of its example files, keyed like `ScrapedExamples::files`,
+/// and its snippets, keyed by the function they call.
+fn parse_calls_file(
+    file: &Json,
+    files: &mut Vec<((String, String), PathBuf)>,
+    calls: &mut FxHashMap<String, Vec<ScrapedExample>>,
+) -> Result<(), String> {
+    let version = file.find("format_version").and_then(|v| v.as_u64());
+    if version != Some(FORMAT_VERSION) {
+        return Err(format!("expected calls file format version {}, found {}",
+                           FORMAT_VERSION, version.map_or("none".to_string(), |v| v.to_string())));
+    }
+    let malformed = || "malformed calls file".to_string();
+    let krate = file.find("crate").and_then(|c| c.as_string()).ok_or_else(malformed)?;
+
+    for (path, src_path) in file.find("files").and_then(|f| f.as_object()).ok_or_else(malformed)? {
+        let src_path = src_path.as_string().ok_or_else(malformed)?;
+        files.push(((krate.to_string(), path.clone()), PathBuf::from(src_path)));
+    }
+
+    for (key, examples) in file.find("calls").and_then(|c| c.as_object()).ok_or_else(malformed)? {
+        for example in examples.as_array().ok_or_else(malformed)? {
+            let string = |name| {
+                example.find(name).and_then(|s| s.as_string()).ok_or_else(malformed)
+            };
+            let line = example.find("line").and_then(|l| l.as_u64()).ok_or_else(malformed)?;
+            let code = string("code")?;
+            let ranges = example.find("calls").and_then(|c| c.as_array()).ok_or_else(malformed)?;
+            let ranges = ranges.iter().map(|range| {
+                match range.as_array().map(|r| (r.get(0).and_then(|lo| lo.as_u64()),
+                                                r.get(1).and_then(|hi| hi.as_u64()))) {
+                    // The ranges are used to slice the code, so they have to be within it.
+                    Some((Some(lo), Some(hi))) if lo <= hi && hi <= code.len() as u64 &&
+                        code.is_char_boundary(lo as usize) &&
+                        code.is_char_boundary(hi as usize) => Ok((lo as u32, hi as u32)),
+                    Some((Some(lo), Some(hi))) => {
+                        Err(format!("call {}..{} is outside of its snippet", lo, hi))
+                    }
+                    _ => Err(malformed()),
+                }
+            }).collect::<Result<_, _>>()?;
+            calls.entry(key.clone()).or_default().push(ScrapedExample {
+                krate: krate.to_string(),
+                file: string("file")?.to_string(),
+                line: line as usize,
+                code: code.to_string(),
+                calls: ranges,
+            });
+        }
+    }
+    Ok(())
+}
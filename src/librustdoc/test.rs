@@ -1,3 +1,4 @@
+use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sync::Lrc;
 use rustc_interface::interface;
 use rustc_target::spec::TargetTriple;
@@ -5,6 +6,7 @@ use rustc::hir;
 use rustc::hir::intravisit;
 use rustc::session::{self, config, DiagnosticOutput};
 use rustc::util::common::ErrorReported;
+use serialize::json::{self, Json, ToJson};
 use syntax::ast;
 use syntax::with_globals;
 use syntax::source_map::SourceMap;
@@ -13,17 +15,21 @@ use syntax::feature_gate::UnstableFeatures;
 use std::env;
 use std::io::{self, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use syntax::symbol::sym;
 use syntax_pos::{BytePos, DUMMY_SP, Pos, Span, FileName};
 use tempfile::Builder as TempFileBuilder;
 use testing;
 
 use crate::clean::Attributes;
-use crate::config::Options;
+use crate::config::{Options, OutputFormat};
 use crate::html::markdown::{self, ErrorCodes, LangString, Ignore};
+use crate::json::object;
 
 #[derive(Clone, Default)]
 pub struct TestOptions {
@@ -76,10 +82,10 @@ pub fn run(options: Options) -> i32 {
         lint_caps: Default::default(),
     };
 
-    let mut test_args = options.test_args.clone();
+    let test_args = options.test_args.clone();
     let display_warnings = options.display_warnings;
 
-    let tests = interface::run_compiler(config, |compiler| -> Result<_, ErrorReported> {
+    let collector = interface::run_compiler(config, |compiler| -> Result<_, ErrorReported> {
         let lower_to_hir = compiler.lower_to_hir()?;
 
         let mut opts = scrape_test_config(lower_to_hir.peek().0.borrow().krate());
@@ -110,18 +116,251 @@ pub fn run(options: Options) -> i32 {
             });
         });
 
-        Ok(collector.tests)
+        Ok(collector)
     }).expect("compiler aborted in rustdoc!");
 
+    run_tests(test_args, display_warnings, collector)
+}
+
+/// Runs the doctests found by `collector` with the libtest arguments `test_args`. With
+/// `--output-format json`, each doctest is reported as a line of JSON instead of libtest's usual
+/// output, followed by a summary line, and `--list` lists the doctests as JSON as well. With
+/// `--check-ignored-doctests`, the doctests marked `ignore` are type-checked while the others run,
+/// and the ones that compile are reported at the end.
+pub fn run_tests(mut test_args: Vec<String>, display_warnings: bool, collector: Collector) -> i32 {
     test_args.insert(0, "rustdoctest".to_string());
+    let test_options = testing::Options::new().display_output(display_warnings);
+    let json_format = collector.options.render_options.output_format == OutputFormat::Json;
 
-    testing::test_main(
-        &test_args,
-        tests,
-        Some(testing::Options::new().display_output(display_warnings))
-    );
+    if !json_format && !collector.options.check_ignored_doctests {
+        testing::test_main(&test_args, collector.tests, Some(test_options));
+        return 0;
+    }
+
+    let mut opts = match testing::parse_opts(&test_args) {
+        Some(Ok(opts)) => opts,
+        Some(Err(msg)) => {
+            eprintln!("error: {}", msg);
+            return 101;
+        }
+        None => return 0,
+    };
+    opts.options = test_options;
+
+    if !json_format {
+        if opts.list {
+            testing::test_main(&test_args, collector.tests, Some(test_options));
+            return 0;
+        }
+        let ignored_check = IgnoredDoctest::check_all(collector.ignored, collector.cratename,
+                                                      collector.options, collector.opts);
+        let res = testing::run_tests_console(&opts, collector.tests);
+        let checked = ignored_check.join().expect("the ignored doctests could not be checked");
+        let compiling = checked.iter().filter(|&&(_, compiles)| compiles).collect::<Vec<_>>();
+        if !compiling.is_empty() {
+            println!("note: {} of the {} doctests marked `ignore` compile today:",
+                     compiling.len(), checked.len());
+            for (name, _) in compiling {
+                println!("    {}", name);
+            }
+            println!();
+        } else if !checked.is_empty() {
+            println!("note: none of the {} doctests marked `ignore` compile today\n",
+                     checked.len());
+        }
+        return match res {
+            Ok(true) => 0,
+            Ok(false) => 101,
+            Err(e) => {
+                eprintln!("error: io error when running tests: {:?}", e);
+                101
+            }
+        };
+    }
+
+    let reports = collector.reports;
+    if opts.list {
+        for test in testing::filter_tests(&opts, collector.tests) {
+            let name = test.desc.name.as_slice();
+            if let Some(report) = reports.get(name) {
+                println!("{}", object(report.describe("listed", name)));
+            }
+        }
+        return 0;
+    }
+
+    let ignored_check = IgnoredDoctest::check_all(collector.ignored, collector.cratename,
+                                                  collector.options, collector.opts);
+    let (mut passed, mut failed, mut ignored, mut allowed_fail) = (0, 0, 0, 0);
+    let res = testing::run_tests(&opts, collector.tests, |event| {
+        if let testing::TestEvent::TeResult(desc, result, _, output) = event {
+            let result = match result {
+                testing::TrOk | testing::TrOkAfterRetries(_) | testing::TrBench(_) => {
+                    passed += 1;
+                    "ok"
+                }
+                testing::TrFailed | testing::TrFailedMsg(_) | testing::TrTimedFail => {
+                    failed += 1;
+                    "failed"
+                }
+                testing::TrIgnored => {
+                    ignored += 1;
+                    "ignored"
+                }
+                testing::TrAllowedFail => {
+                    allowed_fail += 1;
+                    "allowed_fail"
+                }
+            };
+            let name = desc.name.as_slice();
+            if let Some(report) = reports.get(name) {
+                println!("{}", report.to_json(name, result, &output));
+            }
+        }
+        Ok(())
+    });
+
+    let checked = ignored_check.join().expect("the ignored doctests could not be checked");
+    for (name, compiles) in checked {
+        println!("{}", object(vec![
+            ("type", "ignored_check".to_json()),
+            ("name", name.to_json()),
+            ("compiles", compiles.to_json()),
+        ]));
+    }
+    println!("{}", object(vec![
+        ("type", "summary".to_json()),
+        ("passed", passed.to_json()),
+        ("failed", failed.to_json()),
+        ("ignored", ignored.to_json()),
+        ("allowed_fail", allowed_fail.to_json()),
+    ]));
+    match res {
+        Ok(()) if failed == 0 => 0,
+        Ok(()) => 101,
+        Err(e) => {
+            eprintln!("error: io error when running tests: {:?}", e);
+            101
+        }
+    }
+}
 
-    0
+/// What `--output-format json` reports about a doctest, along with its result.
+struct DoctestReport {
+    filename: FileName,
+    line: usize,
+    /// The path of the item the doctest is documenting, or the headers of the sections that
+    /// contain it in a Markdown file.
+    item: String,
+    config: LangString,
+    edition: Edition,
+    /// Filled in when the doctest runs.
+    outcome: Arc<Mutex<DoctestOutcome>>,
+}
+
+/// How long a doctest took to compile and to run, and what the compiler reported if it didn't
+/// compile.
+#[derive(Default)]
+struct DoctestOutcome {
+    compile_time: Option<Duration>,
+    run_time: Option<Duration>,
+    /// The diagnostics of the compiler, as JSON lines.
+    diagnostics: Option<String>,
+}
+
+impl DoctestReport {
+    /// The fields that describe the doctest `name`, for a JSON object of type `ty`.
+    fn describe(&self, ty: &str, name: &str) -> Vec<(&'static str, Json)> {
+        vec![
+            ("type", ty.to_json()),
+            ("name", name.to_json()),
+            ("file", self.filename.to_string().to_json()),
+            ("line", self.line.to_json()),
+            ("item", self.item.to_json()),
+            ("attributes", object(vec![
+                ("edition", self.edition.to_string().to_json()),
+                ("ignore", (self.config.ignore != Ignore::None).to_json()),
+                ("should_panic", self.config.should_panic.to_json()),
+                ("no_run", self.config.no_run.to_json()),
+                ("compile_fail", self.config.compile_fail.to_json()),
+                ("test_harness", self.config.test_harness.to_json()),
+                ("error_codes", self.config.error_codes.to_json()),
+            ])),
+        ]
+    }
+
+    fn to_json(&self, name: &str, result: &str, output: &[u8]) -> Json {
+        let outcome = self.outcome.lock().unwrap();
+        let seconds = |time: Option<Duration>| time.map(|time| time.as_secs_f64());
+        let diagnostics = outcome.diagnostics.as_ref().map(|diagnostics| {
+            Json::Array(diagnostics.lines().filter_map(|line| json::from_str(line).ok()).collect())
+        });
+        let mut fields = self.describe("test", name);
+        fields.extend(vec![
+            ("result", result.to_json()),
+            ("compile_time", seconds(outcome.compile_time).to_json()),
+            ("run_time", seconds(outcome.run_time).to_json()),
+            ("diagnostics", diagnostics.to_json()),
+            ("output", String::from_utf8_lossy(output).to_json()),
+        ]);
+        object(fields)
+    }
+}
+
+/// A doctest marked `ignore`, which `--check-ignored-doctests` type-checks anyway.
+struct IgnoredDoctest {
+    name: String,
+    test: String,
+    edition: Edition,
+    as_test_harness: bool,
+}
+
+impl IgnoredDoctest {
+    /// Type-checks `tests` one after the other on a thread of their own, and returns the name of
+    /// each of them along with whether it compiled.
+    fn check_all(
+        tests: Vec<IgnoredDoctest>,
+        cratename: String,
+        options: Options,
+        opts: TestOptions,
+    ) -> thread::JoinHandle<Vec<(String, bool)>> {
+        thread::spawn(move || {
+            tests.into_iter().map(|test| {
+                let compiles = test.type_checks(&cratename, &options, &opts);
+                (test.name, compiles)
+            }).collect()
+        })
+    }
+
+    fn type_checks(&self, cratename: &str, options: &Options, opts: &TestOptions) -> bool {
+        let test = match panic::catch_unwind(|| {
+            make_test(&self.test, Some(cratename), self.as_test_harness, opts, self.edition)
+        }) {
+            Ok((test, _)) => test,
+            Err(_) => return false,
+        };
+
+        let outdir = TempFileBuilder::new()
+            .prefix("rustdoctest")
+            .tempdir()
+            .expect("rustdoc needs a tempdir");
+        let mut compiler = doctest_compiler(options, &options.target, self.edition,
+                                            &outdir.path().join("rust_out"));
+        if self.as_test_harness {
+            compiler.arg("--test");
+        }
+        compiler.arg("--emit=metadata");
+        compiler.arg("-");
+        compiler.stdin(Stdio::piped());
+        compiler.stdout(Stdio::null());
+        compiler.stderr(Stdio::null());
+
+        let mut child = compiler.spawn().expect("Failed to spawn rustc process");
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(test.as_bytes()).expect("could write out test sources");
+        }
+        child.wait().map(|status| status.success()).unwrap_or(false)
+    }
 }
 
 // Look for `#![doc(test(no_crate_inject))]`, used by crates in the std facade.
@@ -192,7 +431,9 @@ fn run_test(
     mut error_codes: Vec<String>,
     opts: &TestOptions,
     edition: Edition,
+    outcome: &mut DoctestOutcome,
 ) -> Result<(), TestFailure> {
+    let json_format = options.render_options.output_format == OutputFormat::Json;
     let (test, line_offset) = match panic::catch_unwind(|| {
         make_test(test, Some(cratename), as_test_harness, opts, edition)
     }) {
@@ -212,86 +453,43 @@ fn run_test(
         _ => PathBuf::from(r"doctest.rs"),
     };
 
-    enum DirState {
-        Temp(tempfile::TempDir),
-        Perm(PathBuf),
-    }
-
-    impl DirState {
-        fn path(&self) -> &std::path::Path {
-            match self {
-                DirState::Temp(t) => t.path(),
-                DirState::Perm(p) => p.as_path(),
-            }
-        }
-    }
-
-    let outdir = if let Some(mut path) = options.persist_doctests {
-        path.push(format!("{}_{}",
-            filename
-                .to_string()
-                .rsplit('/')
-                .next()
-                .unwrap()
-                .replace(".", "_"),
-                line)
-        );
-        std::fs::create_dir_all(&path)
-            .expect("Couldn't create directory for doctest executables");
-
-        DirState::Perm(path)
-    } else {
-        DirState::Temp(TempFileBuilder::new()
-                        .prefix("rustdoctest")
-                        .tempdir()
-                        .expect("rustdoc needs a tempdir"))
-    };
+    let outdir = DirState::new(&options, &format!("{}_{}",
+        filename
+            .to_string()
+            .rsplit('/')
+            .next()
+            .unwrap()
+            .replace(".", "_"),
+            line)
+    );
     let output_file = outdir.path().join("rust_out");
 
-    let rustc_binary = options.test_builder.as_ref().map(|v| &**v).unwrap_or_else(|| {
-        rustc_interface::util::rustc_path().expect("found rustc")
-    });
-    let mut compiler = Command::new(&rustc_binary);
-    compiler.arg("--crate-type").arg("bin");
-    for cfg in &options.cfgs {
-        compiler.arg("--cfg").arg(&cfg);
-    }
-    if let Some(sysroot) = options.maybe_sysroot {
-        compiler.arg("--sysroot").arg(sysroot);
-    }
-    compiler.arg("--edition").arg(&edition.to_string());
+    let mut compiler = doctest_compiler(&options, &target, edition, &output_file);
     compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
     compiler.env("UNSTABLE_RUSTDOC_TEST_LINE",
                  format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
     if as_test_harness {
         compiler.arg("--test");
     }
-    for lib_str in &options.lib_strs {
-        compiler.arg("-L").arg(&lib_str);
-    }
-    for extern_str in &options.extern_strs {
-        compiler.arg("--extern").arg(&extern_str);
-    }
-    compiler.arg("-Ccodegen-units=1");
-    for codegen_options_str in &options.codegen_options_strs {
-        compiler.arg("-C").arg(&codegen_options_str);
-    }
     if no_run {
         compiler.arg("--emit=metadata");
     }
-    compiler.arg("--target").arg(target.to_string());
+    if json_format {
+        compiler.arg("--error-format=json");
+    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
     compiler.stderr(Stdio::piped());
 
+    let start = Instant::now();
     let mut child = compiler.spawn().expect("Failed to spawn rustc process");
     {
         let stdin = child.stdin.as_mut().expect("Failed to open stdin");
         stdin.write_all(test.as_bytes()).expect("could write out test sources");
     }
     let output = child.wait_with_output().expect("Failed to read stdout");
+    outcome.compile_time = Some(start.elapsed());
 
     struct Bomb<'a>(&'a str);
     impl Drop for Bomb<'_> {
@@ -301,7 +499,11 @@ fn run_test(
     }
 
     let out = str::from_utf8(&output.stderr).unwrap();
-    let _bomb = Bomb(&out);
+    if !output.status.success() {
+        outcome.diagnostics = Some(out.to_string());
+    }
+    // The diagnostics are part of the JSON report, so they aren't printed along with it.
+    let _bomb = Bomb(if json_format { "" } else { &out });
     match (output.status.success(), compile_fail) {
         (true, true) => {
             return Err(TestFailure::UnexpectedCompilePass);
@@ -325,18 +527,102 @@ fn run_test(
         return Ok(());
     }
 
-    // Run the code!
+    run_test_binary(&output_file, None, runtool, runtool_args, should_panic, outcome)
+}
+
+/// Where a doctest binary is written to.
+enum DirState {
+    Temp(tempfile::TempDir),
+    Perm(PathBuf),
+}
+
+impl DirState {
+    /// Creates the directory `name` in the `--persist-doctests` directory, or a temporary
+    /// directory if the binaries aren't persisted.
+    fn new(options: &Options, name: &str) -> DirState {
+        if let Some(ref path) = options.persist_doctests {
+            let path = path.join(name);
+            std::fs::create_dir_all(&path)
+                .expect("Couldn't create directory for doctest executables");
+
+            DirState::Perm(path)
+        } else {
+            DirState::Temp(TempFileBuilder::new()
+                            .prefix("rustdoctest")
+                            .tempdir()
+                            .expect("rustdoc needs a tempdir"))
+        }
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            DirState::Temp(t) => t.path(),
+            DirState::Perm(p) => p.as_path(),
+        }
+    }
+}
+
+/// The rustc invocation that compiles a doctest binary to `output_file`, with the options shared
+/// by all doctests. The caller still needs to pass the input.
+fn doctest_compiler(
+    options: &Options,
+    target: &TargetTriple,
+    edition: Edition,
+    output_file: &Path,
+) -> Command {
+    let rustc_binary = options.test_builder.as_ref().map(|v| &**v).unwrap_or_else(|| {
+        rustc_interface::util::rustc_path().expect("found rustc")
+    });
+    let mut compiler = Command::new(&rustc_binary);
+    compiler.arg("--crate-type").arg("bin");
+    for cfg in &options.cfgs {
+        compiler.arg("--cfg").arg(&cfg);
+    }
+    if let Some(ref sysroot) = options.maybe_sysroot {
+        compiler.arg("--sysroot").arg(sysroot);
+    }
+    compiler.arg("--edition").arg(&edition.to_string());
+    compiler.arg("-o").arg(output_file);
+    for lib_str in &options.lib_strs {
+        compiler.arg("-L").arg(&lib_str);
+    }
+    for extern_str in &options.extern_strs {
+        compiler.arg("--extern").arg(&extern_str);
+    }
+    compiler.arg("-Ccodegen-units=1");
+    for codegen_options_str in &options.codegen_options_strs {
+        compiler.arg("-C").arg(&codegen_options_str);
+    }
+    compiler.arg("--target").arg(target.to_string());
+    compiler
+}
+
+/// Runs a doctest binary. Merged doctests are given the index of the doctest to run.
+fn run_test_binary(
+    binary: &Path,
+    merged_index: Option<usize>,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    should_panic: bool,
+    outcome: &mut DoctestOutcome,
+) -> Result<(), TestFailure> {
     let mut cmd;
 
     if let Some(tool) = runtool {
         cmd = Command::new(tool);
-        cmd.arg(output_file);
+        cmd.arg(binary);
         cmd.args(runtool_args);
     } else {
-        cmd = Command::new(output_file);
+        cmd = Command::new(binary);
+    }
+    if let Some(index) = merged_index {
+        cmd.arg(index.to_string());
     }
 
-    match cmd.output() {
+    let start = Instant::now();
+    let output = cmd.output();
+    outcome.run_time = Some(start.elapsed());
+    match output {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
         Ok(out) => {
             if should_panic && out.status.success() {
@@ -365,8 +651,46 @@ pub fn make_test(s: &str,
                  -> (String, usize) {
     let (crate_attrs, everything_else, crates) = partition_source(s);
     let everything_else = everything_else.trim();
-    let mut line_offset = 0;
     let mut prog = String::new();
+    let mut line_offset = push_crate_attrs(&mut prog, opts);
+
+    // Now push any outer attributes from the example, assuming they
+    // are intended to be crate attributes.
+    prog.push_str(&crate_attrs);
+    prog.push_str(&crates);
+
+    let (already_has_main, already_has_extern_crate) =
+        check_for_main_and_extern_crate(s, &crates, everything_else, cratename, edition);
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
+        if let Some(cratename) = cratename {
+            // Make sure its actually used if not included.
+            if s.contains(cratename) {
+                prog.push_str(&format!("extern crate {};\n", cratename));
+                line_offset += 1;
+            }
+        }
+    }
+
+    // FIXME: This code cannot yet handle no_std test cases yet
+    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
+        prog.push_str(everything_else);
+    } else {
+        prog.push_str(&wrap_in_fn("main", everything_else));
+        line_offset += 1;
+    }
+
+    debug!("final doctest:\n{}", prog);
+
+    (prog, line_offset)
+}
+
+/// Pushes the crate attributes that every doctest starts with, and returns the number of lines
+/// that were pushed.
+fn push_crate_attrs(prog: &mut String, opts: &TestOptions) -> usize {
+    let mut lines = 0;
 
     if opts.attrs.is_empty() && !opts.display_warnings {
         // If there aren't any attributes supplied by #![doc(test(attr(...)))], then allow some
@@ -374,29 +698,46 @@ pub fn make_test(s: &str,
         // commonly used to make tests fail in case they trigger warnings, so having this there in
         // that case may cause some tests to pass when they shouldn't have.
         prog.push_str("#![allow(unused)]\n");
-        line_offset += 1;
+        lines += 1;
     }
 
     // Next, any attributes that came from the crate root via #![doc(test(attr(...)))].
     for attr in &opts.attrs {
         prog.push_str(&format!("#![{}]\n", attr));
-        line_offset += 1;
+        lines += 1;
     }
 
-    // Now push any outer attributes from the example, assuming they
-    // are intended to be crate attributes.
-    prog.push_str(&crate_attrs);
-    prog.push_str(&crates);
+    lines
+}
 
-    // Uses libsyntax to parse the doctest and find if there's a main fn and the extern
-    // crate already is included.
+/// Wraps the statements of a doctest in the function `name`. If the doctest ends in `Ok(())`,
+/// the statements are allowed to use `?`.
+fn wrap_in_fn(name: &str, everything_else: &str) -> String {
+    let returns_result = everything_else.trim_end().ends_with("(())");
+    if returns_result {
+        format!("fn {}() {{ fn _inner() -> Result<(), impl core::fmt::Debug> {{{}}}\n\
+                 _inner().unwrap() }}", name, everything_else)
+    } else {
+        format!("fn {}() {{\n{}\n}}", name, everything_else)
+    }
+}
+
+/// Uses libsyntax to parse the doctest and find if there's a main fn and the extern crate
+/// already is included.
+fn check_for_main_and_extern_crate(
+    s: &str,
+    crates: &str,
+    everything_else: &str,
+    cratename: Option<&str>,
+    edition: Edition,
+) -> (bool, bool) {
     let (already_has_main, already_has_extern_crate, found_macro) = with_globals(edition, || {
         use crate::syntax::{parse::{self, ParseSess}, source_map::FilePathMapping};
         use errors::emitter::EmitterWriter;
         use errors::Handler;
 
         let filename = FileName::anon_source_code(s);
-        let source = crates + &everything_else;
+        let source = crates.to_owned() + everything_else;
 
         // Any errors in parsing should also appear when the doctest is compiled for real, so just
         // send all the errors that libsyntax emits directly into a `Sink` instead of stderr.
@@ -485,36 +826,39 @@ pub fn make_test(s: &str,
         already_has_main
     };
 
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
-        if let Some(cratename) = cratename {
-            // Make sure its actually used if not included.
-            if s.contains(cratename) {
-                prog.push_str(&format!("extern crate {};\n", cratename));
-                line_offset += 1;
-            }
-        }
-    }
+    (already_has_main, already_has_extern_crate)
+}
 
-    // FIXME: This code cannot yet handle no_std test cases yet
-    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
-        prog.push_str(everything_else);
-    } else {
-        let returns_result = everything_else.trim_end().ends_with("(())");
-        let (main_pre, main_post) = if returns_result {
-            ("fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {",
-             "}\n_inner().unwrap() }")
-        } else {
-            ("fn main() {\n", "\n}")
-        };
-        prog.extend([main_pre, everything_else, main_post].iter().cloned());
-        line_offset += 1;
+/// Turns a doctest into the function `name`, to be compiled into a single crate together with
+/// other doctests, and returns it with the number to add to a line of the function to get the line
+/// of the doctest it comes from. Returns `None` if the doctest has to be compiled on its own,
+/// because it has crate attributes, `extern crate` items or its own `fn main`.
+///
+/// # Panics
+///
+/// Like `make_test`, this function panics if the parser encounters a fatal error.
+fn make_merged_test(s: &str, name: &str, edition: Edition) -> Option<(String, isize)> {
+    let (crate_attrs, everything_else, crates) = partition_source(s);
+    if crate_attrs.lines().any(|line| line.trim().starts_with("#![")) ||
+        !crates.trim().is_empty()
+    {
+        return None;
     }
 
-    debug!("final doctest:\n{}", prog);
+    let skipped_lines = crate_attrs.lines().count() +
+        everything_else[..everything_else.len() - everything_else.trim_start().len()]
+            .matches('\n')
+            .count();
+    let everything_else = everything_else.trim();
+    let (has_main, _) = check_for_main_and_extern_crate(s, &crates, everything_else, None, edition);
+    if has_main {
+        return None;
+    }
 
-    (prog, line_offset)
+    let merged = wrap_in_fn(name, everything_else);
+    // Unless the doctest returns a `Result`, the function starts with a line of its own.
+    let header_lines = if merged.starts_with(&format!("fn {}() {{\n", name)) { 1 } else { 0 };
+    Some((merged, skipped_lines as isize - header_lines))
 }
 
 // FIXME(aburka): use a real parser to deal with multiline attributes
@@ -587,6 +931,139 @@ fn partition_source(s: &str) -> (String, String, String) {
     (before, after, crates)
 }
 
+/// Doctests of one edition that are compiled into a single binary, with `--merge-doctests`.
+///
+/// Each doctest is wrapped in a function `doctest_<index>`, and the `main` of the binary calls
+/// the one whose index it gets as its argument, so that every doctest still runs in its own
+/// process. `main` also installs a panic hook that reports panics in the doctests at their lines
+/// in the documented files, unless a backtrace is printed. The binary is built by
+/// the first of the doctests to run, when all of them have been collected. If it doesn't compile,
+/// each doctest is compiled on its own instead, which also reports the errors of the doctests that
+/// caused it.
+struct MergedDoctests {
+    edition: Edition,
+    cratename: String,
+    options: Options,
+    opts: TestOptions,
+    tests: Mutex<Vec<MergedDoctest>>,
+    /// The directory of the binary once it was built, or `None` if it failed to compile.
+    binary: Mutex<Option<Option<DirState>>>,
+}
+
+struct MergedDoctest {
+    /// The function the doctest was turned into.
+    source: String,
+    /// The file the doctest is in.
+    path: PathBuf,
+    /// The number to add to a line of `source` to get the line in `path` it comes from.
+    line_offset: isize,
+}
+
+impl MergedDoctests {
+    fn new(edition: Edition, cratename: String, options: Options, opts: TestOptions) -> Self {
+        MergedDoctests {
+            edition,
+            cratename,
+            options,
+            opts,
+            tests: Mutex::new(Vec::new()),
+            binary: Mutex::new(None),
+        }
+    }
+
+    /// Adds `test`, whose code block starts at `line` of `filename`, to the binary, and returns
+    /// its index, or `None` if the doctest has to be compiled on its own.
+    fn add(&self, test: &str, filename: &FileName, line: usize) -> Option<usize> {
+        let mut tests = self.tests.lock().unwrap();
+        let index = tests.len();
+        let name = format!("doctest_{}", index);
+        let edition = self.edition;
+        let (source, line_offset) =
+            match panic::catch_unwind(|| make_merged_test(test, &name, edition)) {
+                Ok(merged) => merged?,
+                // The error will be reported when the doctest is compiled on its own.
+                Err(cause) if cause.is::<errors::FatalErrorMarker>() => return None,
+                Err(cause) => panic::resume_unwind(cause),
+            };
+        // The same path `run_test` passes to rustc. The code starts on the line after `line`.
+        let path = match filename {
+            FileName::Real(path) => path.clone(),
+            _ => PathBuf::from("doctest.rs"),
+        };
+        tests.push(MergedDoctest { source, path, line_offset: line_offset + line as isize });
+        Some(index)
+    }
+
+    /// Returns the path to the binary, building it first if it hasn't been built yet, or `None`
+    /// if it doesn't compile.
+    fn binary(&self) -> Option<PathBuf> {
+        let mut binary = self.binary.lock().unwrap();
+        if binary.is_none() {
+            *binary = Some(self.build());
+        }
+        binary.as_ref().unwrap().as_ref().map(|dir| dir.path().join("rust_out"))
+    }
+
+    fn build(&self) -> Option<DirState> {
+        let tests = self.tests.lock().unwrap();
+
+        let mut prog = String::new();
+        push_crate_attrs(&mut prog, &self.opts);
+        if !self.opts.no_crate_inject && self.cratename != "std" &&
+            tests.iter().any(|test| test.source.contains(&self.cratename))
+        {
+            prog.push_str(&format!("extern crate {};\n", self.cratename));
+        }
+        // Each doctest is included from a file of its own, which starts with blank lines so that
+        // the doctest is on the lines it has in the documented file. The directory of that file
+        // is remapped to the documented file's, so that panics, `file!()` and `line!()` refer to
+        // the documented file.
+        let outdir = DirState::new(&self.options, &format!("merged_doctests_{}", self.edition));
+        let mut remapped_dirs = Vec::new();
+        for (index, test) in tests.iter().enumerate() {
+            let dir = outdir.path().join(format!("doctest_{}", index));
+            let file = dir.join(test.path.file_name().unwrap_or("doctest.rs".as_ref()));
+            let padding = "\n".repeat(test.line_offset.max(0) as usize);
+            std::fs::create_dir_all(&dir).expect("Couldn't create directory for merged doctests");
+            std::fs::write(&file, format!("{}{}\n", padding, test.source))
+                .expect("Couldn't write merged doctest");
+            prog.push_str(&format!("include!({:?});\n", file.display().to_string()));
+            let original_dir = test.path.parent().unwrap_or(Path::new(""));
+            remapped_dirs.push(format!("{}={}", dir.display(), original_dir.display()));
+        }
+        prog.push_str("fn main() {\n    match std::env::args().nth(1).as_ref().map(|s| &**s) {\n");
+        for index in 0..tests.len() {
+            prog.push_str(&format!("        Some(\"{0}\") => doctest_{0}(),\n", index));
+        }
+        prog.push_str("        _ => panic!(\"unknown doctest\"),\n    }\n}\n");
+        debug!("merged doctests:\n{}", prog);
+
+        let output_file = outdir.path().join("rust_out");
+        let mut compiler = doctest_compiler(&self.options, &self.options.target, self.edition,
+                                            &output_file);
+        for remapped_dir in &remapped_dirs {
+            compiler.arg("--remap-path-prefix").arg(remapped_dir);
+        }
+        compiler.arg("-");
+        compiler.stdin(Stdio::piped());
+        compiler.stderr(Stdio::piped());
+
+        let mut child = compiler.spawn().expect("Failed to spawn rustc process");
+        {
+            let stdin = child.stdin.as_mut().expect("Failed to open stdin");
+            stdin.write_all(prog.as_bytes()).expect("could write out test sources");
+        }
+        let output = child.wait_with_output().expect("Failed to read stdout");
+        if output.status.success() {
+            Some(outdir)
+        } else {
+            debug!("merged doctests failed to compile:\n{}",
+                   String::from_utf8_lossy(&output.stderr));
+            None
+        }
+    }
+}
+
 pub trait Tester {
     fn add_test(&mut self, test: String, config: LangString, line: usize);
     fn get_line(&self) -> usize {
@@ -628,6 +1105,12 @@ pub struct Collector {
     position: Span,
     source_map: Option<Lrc<SourceMap>>,
     filename: Option<PathBuf>,
+    /// The binaries doctests are merged into with `--merge-doctests`, one per edition.
+    merged: Vec<Arc<MergedDoctests>>,
+    /// The reports of `--output-format json`, keyed by the name of the test.
+    reports: FxHashMap<String, DoctestReport>,
+    /// The doctests marked `ignore`, with `--check-ignored-doctests`.
+    ignored: Vec<IgnoredDoctest>,
 }
 
 impl Collector {
@@ -645,6 +1128,9 @@ impl Collector {
             position: DUMMY_SP,
             source_map,
             filename,
+            merged: Vec::new(),
+            reports: FxHashMap::default(),
+            ignored: Vec::new(),
         }
     }
 
@@ -687,40 +1173,90 @@ impl Tester for Collector {
         let runtool_args = self.options.runtool_args.clone();
         let target = self.options.target.clone();
         let target_str = target.to_string();
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => {
+                ignores.iter().any(|s| target_str.contains(s))
+            },
+        };
+
+        // Doctests that check for compiler errors or need `--test` are compiled on their own.
+        let merged = if self.options.merge_doctests && !ignore && !config.compile_fail &&
+            !config.test_harness
+        {
+            let merged = match self.merged.iter().find(|merged| merged.edition == edition) {
+                Some(merged) => merged.clone(),
+                None => {
+                    let merged = Arc::new(MergedDoctests::new(edition, cratename.clone(),
+                                                              options.clone(), opts.clone()));
+                    self.merged.push(merged.clone());
+                    merged
+                }
+            };
+            merged.add(&test, &filename, line).map(|index| (merged, index))
+        } else {
+            None
+        };
+
+        if self.options.check_ignored_doctests && config.ignore == Ignore::All {
+            self.ignored.push(IgnoredDoctest {
+                name: name.clone(),
+                test: test.clone(),
+                edition,
+                as_test_harness: config.test_harness,
+            });
+        }
+
+        let report_outcome = Arc::new(Mutex::new(DoctestOutcome::default()));
+        self.reports.insert(name.clone(), DoctestReport {
+            filename: filename.clone(),
+            line,
+            item: self.names.join("::"),
+            config: config.clone(),
+            edition,
+            outcome: report_outcome.clone(),
+        });
 
         debug!("creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name.clone()),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => {
-                        ignores.iter().any(|s| target_str.contains(s))
-                    },
-                },
+                ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
+                timeout: None,
             },
             testfn: testing::DynTestFn(box move || {
-                let res = run_test(
-                    &test,
-                    &cratename,
-                    &filename,
-                    line,
-                    options,
-                    config.should_panic,
-                    config.no_run,
-                    config.test_harness,
-                    runtool,
-                    runtool_args,
-                    target,
-                    config.compile_fail,
-                    config.error_codes,
-                    &opts,
-                    edition,
-                );
+                let mut outcome = DoctestOutcome::default();
+                let binary = merged.as_ref().and_then(|(merged, _)| merged.binary());
+                let res = match (binary, &merged) {
+                    (Some(_), _) if config.no_run => Ok(()),
+                    (Some(binary), Some((_, index))) => {
+                        run_test_binary(&binary, Some(*index), runtool, runtool_args,
+                                        config.should_panic, &mut outcome)
+                    }
+                    _ => run_test(
+                        &test,
+                        &cratename,
+                        &filename,
+                        line,
+                        options,
+                        config.should_panic,
+                        config.no_run,
+                        config.test_harness,
+                        runtool,
+                        runtool_args,
+                        target,
+                        config.compile_fail,
+                        config.error_codes,
+                        &opts,
+                        edition,
+                        &mut outcome,
+                    ),
+                };
+                *report_outcome.lock().unwrap() = outcome;
 
                 if let Err(err) = res {
                     match err {
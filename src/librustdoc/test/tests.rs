@@ -1,4 +1,4 @@
-use super::{TestOptions, make_test};
+use super::{TestOptions, make_test, make_merged_test};
 use syntax::edition::DEFAULT_EDITION;
 
 #[test]
@@ -297,3 +297,66 @@ test_wrapper! {
     let output = make_test(input, Some("my_crate"), false, &opts, DEFAULT_EDITION);
     assert_eq!(output, (expected, 1));
 }
+
+#[test]
+fn make_merged_test_basic() {
+    let input =
+"use asdf::qwop;
+assert_eq!(2+2, 4);";
+    let expected =
+"fn doctest_3() {
+use asdf::qwop;
+assert_eq!(2+2, 4);
+}".to_string();
+
+    let output = make_merged_test(input, "doctest_3", DEFAULT_EDITION);
+    assert_eq!(output, Some((expected, -1)));
+}
+
+#[test]
+fn make_merged_test_returns_result() {
+    let input =
+"let x = \"4\".parse::<u32>()?;
+Ok::<(), std::num::ParseIntError>(())";
+    let expected =
+"fn doctest_0() { fn _inner() -> Result<(), impl core::fmt::Debug> {let x = \"4\".parse::<u32>()?;
+Ok::<(), std::num::ParseIntError>(())}
+_inner().unwrap() }".to_string();
+
+    let output = make_merged_test(input, "doctest_0", DEFAULT_EDITION);
+    assert_eq!(output, Some((expected, 0)));
+}
+
+#[test]
+fn make_merged_test_skipped_lines() {
+    // Lines before the code of the doctest are left out, but still count.
+    let input =
+"// A comment.
+
+assert_eq!(2+2, 4);";
+    let expected =
+"fn doctest_1() {
+assert_eq!(2+2, 4);
+}".to_string();
+
+    let output = make_merged_test(input, "doctest_1", DEFAULT_EDITION);
+    assert_eq!(output, Some((expected, 1)));
+}
+
+#[test]
+fn make_merged_test_standalone() {
+    // Crate attributes, `extern crate` and `fn main` can't be moved into a function.
+    let inputs = [
+"#![feature(never_type)]
+let x: ! = panic!();",
+"extern crate asdf;
+assert_eq!(2+2, 4);",
+"fn main() {
+    assert_eq!(2+2, 4);
+}",
+    ];
+
+    for input in &inputs {
+        assert_eq!(make_merged_test(input, "doctest_0", DEFAULT_EDITION), None);
+    }
+}
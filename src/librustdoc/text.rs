@@ -0,0 +1,769 @@
+//! Rustdoc's text backends, selected with `--output-format man` and `--output-format markdown`.
+//!
+//! Both backends write one page for each item that has a page of its own in the HTML docs. Fields,
+//! variants, associated items and implementations are described on the page of their parent, the
+//! same way. Every page is first written as CommonMark: the docs of the items are taken as they
+//! are after the passes ran, with their intra-doc links pointing to the other pages, and with
+//! their `doc(cfg)` and stability notes. The Markdown backend writes these pages as they are, in a
+//! tree of files laid out like the HTML docs. The man backend converts them to troff, in section 3
+//! of the manual, and refers to the man pages of the items it links to.
+//!
+//! Links to the items of other crates are left as they were written.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustc::hir::def_id::DefId;
+use rustc::middle::stability::StabilityLevel;
+use rustc_data_structures::fx::FxHashMap;
+use syntax::ext::base::MacroKind;
+
+use crate::clean::{self, GetDefId};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::doctree;
+use crate::html::format::{Function, PrintWithSpace, WhereClause};
+use crate::html::format::{print_abi_with_space, print_generic_bounds};
+use crate::html::item_type::ItemType;
+use crate::html::markdown::{plain_summary_line, text_markdown};
+use crate::html::render::{Error, item_ty_to_strs};
+
+mod man;
+
+/// The kind of text documentation to write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// A man page for each item, in `<output>/man3`.
+    Man,
+    /// A tree of CommonMark files in `<output>/<crate name>`.
+    Markdown,
+}
+
+/// Writes the pages of `krate` to the output directory, in `format`.
+pub fn run(krate: clean::Crate, options: RenderOptions, format: Format) -> Result<(), Error> {
+    let module = match krate.module {
+        Some(ref module) => module,
+        None => return Ok(()),
+    };
+
+    let mut cx = Context {
+        format,
+        pages: vec![],
+        page_ids: FxHashMap::default(),
+        impls: FxHashMap::default(),
+        implementors: FxHashMap::default(),
+    };
+    cx.collect(module, &[krate.name.clone()], false);
+    cx.name_pages();
+
+    let source = match krate.version {
+        Some(ref version) => format!("{} {}", krate.name, version),
+        None => krate.name.clone(),
+    };
+    for id in 0..cx.pages.len() {
+        let page = cx.render_page(id);
+        let contents = match format {
+            Format::Man => man::render(&cx.pages[id].name, &source, &page),
+            Format::Markdown => page.to_markdown(),
+        };
+        let file = options.output.join(&cx.pages[id].file);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::new(e, dir))?;
+        }
+        fs::write(&file, contents).map_err(|e| Error::new(e, &file))?;
+    }
+    Ok(())
+}
+
+/// An item with a page of its own.
+struct Page<'a> {
+    item: &'a clean::Item,
+    /// The path of the item, starting with the name of the crate.
+    path: Vec<String>,
+    /// The name of the man page, which is usually the path of the item.
+    name: String,
+    /// The file of the page, relative to the output directory.
+    file: PathBuf,
+}
+
+/// A page, written as CommonMark.
+crate struct Document {
+    /// What the item is, e.g. `Struct`.
+    crate kind: &'static str,
+    /// The path of the item.
+    crate path: String,
+    /// The first line of the docs of the item.
+    crate summary: String,
+    /// The declaration of the item, as Rust code.
+    crate declaration: Option<String>,
+    /// The notes, docs and sections of the page. Sections have level 2 headings.
+    crate body: String,
+}
+
+impl Document {
+    fn to_markdown(&self) -> String {
+        let mut md = format!("# {} {}\n\n", self.kind, self.path);
+        if let Some(ref declaration) = self.declaration {
+            md.push_str(&format!("```rust\n{}\n```\n\n", declaration));
+        }
+        md.push_str(&self.body);
+        md
+    }
+}
+
+struct Context<'a> {
+    format: Format,
+    pages: Vec<Page<'a>>,
+    page_ids: FxHashMap<DefId, usize>,
+    /// The impls of each type, by the `DefId` of the type.
+    impls: FxHashMap<DefId, Vec<(&'a clean::Item, &'a clean::Impl)>>,
+    /// The impls of each trait, by the `DefId` of the trait.
+    implementors: FxHashMap<DefId, Vec<(&'a clean::Item, &'a clean::Impl)>>,
+}
+
+impl<'a> Context<'a> {
+    /// Finds the pages and impls in `item` and the items nested in it. The impls of stripped
+    /// modules are still collected, since they can implement traits for public types.
+    fn collect(&mut self, item: &'a clean::Item, parent: &[String], stripped: bool) {
+        let (inner, stripped) = match item.inner {
+            clean::StrippedItem(box ref inner) => (inner, true),
+            ref inner => (inner, stripped),
+        };
+        match *inner {
+            clean::ImplItem(ref i) => {
+                if let Some(did) = i.for_.def_id() {
+                    self.impls.entry(did).or_default().push((item, i));
+                }
+                if let Some(did) = i.trait_.def_id() {
+                    self.implementors.entry(did).or_default().push((item, i));
+                }
+                return;
+            }
+            clean::ExternCrateItem(..) | clean::ImportItem(..) => return,
+            _ => {}
+        }
+
+        let mut path = parent.to_vec();
+        // The crate root is named after the crate, which is already in `parent`.
+        match item.name {
+            Some(ref name) if !name.is_empty() => path.push(name.clone()),
+            _ => {}
+        }
+        if !stripped && has_page(item) {
+            self.page_ids.entry(item.def_id).or_insert(self.pages.len());
+            self.pages.push(Page {
+                item,
+                path: path.clone(),
+                name: String::new(),
+                file: PathBuf::new(),
+            });
+        }
+        if let clean::ModuleItem(ref m) = *inner {
+            for child in &m.items {
+                self.collect(child, &path, stripped);
+            }
+        }
+    }
+
+    /// Decides on the names and files of the pages. An item whose man page would have the same
+    /// name as another item's, like a function next to a module of the same name, gets its kind
+    /// appended to the name. Names that only differ in case count as the same, since they would
+    /// be the same file on case-insensitive file systems.
+    fn name_pages(&mut self) {
+        // The number of pages, and of module pages, with each name.
+        let mut counts: FxHashMap<String, (usize, usize)> = FxHashMap::default();
+        for page in &self.pages {
+            let count = counts.entry(page.path.join("::").to_lowercase()).or_insert((0, 0));
+            count.0 += 1;
+            if page.item.type_() == ItemType::Module {
+                count.1 += 1;
+            }
+        }
+        for page in &mut self.pages {
+            let ty = page.item.type_();
+            let name = page.path.join("::");
+            let (pages, modules) = counts[&name.to_lowercase()];
+            page.name = if pages > 1 && (ty != ItemType::Module || modules > 1) {
+                format!("{}.{}", name, ty.as_str())
+            } else {
+                name
+            };
+            page.file = match self.format {
+                Format::Man => {
+                    // Windows doesn't allow colons in file names. `-` can't be part of a path,
+                    // so it can stand in for `::` without clashing with another page.
+                    let file = if cfg!(windows) {
+                        page.name.replace("::", "-")
+                    } else {
+                        page.name.clone()
+                    };
+                    Path::new("man3").join(format!("{}.3", file))
+                }
+                Format::Markdown => {
+                    let (name, parent) = page.path.split_last().expect("pages have a path");
+                    let mut file = parent.iter().collect::<PathBuf>();
+                    if ty == ItemType::Module {
+                        file.push(name);
+                        file.push("index.md");
+                    } else {
+                        file.push(format!("{}.{}.md", ty.as_str(), name));
+                    }
+                    file
+                }
+            };
+        }
+    }
+
+    /// The destination of a link from the page `from` to the page `to`.
+    fn href(&self, from: usize, to: usize, fragment: Option<&str>) -> String {
+        match self.format {
+            Format::Man => format!("man:{}(3)", self.pages[to].name),
+            Format::Markdown => {
+                let mut href = if from == to && fragment.is_some() {
+                    String::new()
+                } else {
+                    relative_path(&self.pages[from].file, &self.pages[to].file)
+                };
+                if let Some(fragment) = fragment {
+                    href.push('#');
+                    href.push_str(fragment);
+                }
+                href
+            }
+        }
+    }
+
+    /// The intra-doc links of `item`, resolved for the page `from`.
+    fn links(&self, from: usize, item: &clean::Item) -> Vec<(String, String)> {
+        item.attrs.links.iter().filter_map(|&(ref ori, did, ref fragment)| {
+            let fragment = fragment.as_ref().map(|s| &**s);
+            let dest = match did {
+                Some(did) => self.href(from, *self.page_ids.get(&did)?, fragment),
+                None => {
+                    // A primitive type, which is documented by `std`.
+                    let fragment = fragment?;
+                    let tail = fragment.find('#').unwrap_or_else(|| fragment.len());
+                    format!("https://doc.rust-lang.org/nightly/std/primitive.{}.html{}",
+                            &fragment[..tail], &fragment[tail..])
+                }
+            };
+            Some((ori.clone(), dest))
+        }).collect()
+    }
+
+    /// The notes and docs of `item`, to be written to the page `from` with the headings of the
+    /// docs moved `heading_shift` levels down. The `doc(cfg)` note is left out if it is the same
+    /// as `parent_cfg`, the one of the page.
+    fn docs(
+        &self,
+        from: usize,
+        item: &clean::Item,
+        heading_shift: usize,
+        parent_cfg: Option<&clean::cfg::Cfg>,
+    ) -> String {
+        let mut notes = vec![];
+        if let Some(ref depr) = item.deprecation() {
+            let mut note = match depr.since {
+                Some(ref since) => format!("Deprecated since {}", since),
+                None => "Deprecated".to_string(),
+            };
+            if let Some(ref text) = depr.note {
+                note.push_str(": ");
+                note.push_str(text);
+            }
+            notes.push(note);
+        }
+        if let Some(ref stab) = item.stability {
+            let feature = stab.feature.as_ref().map(|s| &**s);
+            if stab.level == StabilityLevel::Unstable && feature != Some("rustc_private") {
+                let mut note = "This is a nightly-only experimental API.".to_string();
+                if let Some(feature) = feature {
+                    note.push_str(&format!(" (`{}`)", feature));
+                }
+                if let Some(ref reason) = stab.unstable_reason {
+                    note.push(' ');
+                    note.push_str(reason);
+                }
+                notes.push(note);
+            }
+        }
+        if let Some(ref cfg) = item.attrs.cfg {
+            if parent_cfg != Some(&**cfg) {
+                notes.push(cfg.render_long_markdown());
+            }
+        }
+
+        let mut docs = String::new();
+        for note in notes {
+            docs.push_str("> ");
+            docs.push_str(&note.replace('\n', "\n> "));
+            docs.push_str("\n\n");
+        }
+        if let Some(doc) = item.attrs.collapsed_doc_value() {
+            let links = self.links(from, item);
+            docs.push_str(&text_markdown(&doc, &links, heading_shift));
+            docs.push('\n');
+        }
+        docs
+    }
+
+    fn render_page(&self, id: usize) -> Document {
+        let page = &self.pages[id];
+        let item = page.item;
+        let cfg = item.attrs.cfg.as_ref().map(|cfg| &**cfg);
+
+        let mut body = self.docs(id, item, 1, None);
+        match item.inner {
+            clean::ModuleItem(ref m) => self.module_sections(id, &m.items, &mut body),
+            clean::StructItem(clean::Struct { ref fields, .. }) |
+            clean::UnionItem(clean::Union { ref fields, .. }) => {
+                let fields = fields.iter().filter(|f| !f.is_stripped()).collect::<Vec<_>>();
+                if !fields.is_empty() {
+                    body.push_str("## Fields\n\n");
+                    for field in fields {
+                        self.member(id, field, 3, cfg, &mut body);
+                    }
+                }
+            }
+            clean::EnumItem(ref e) => {
+                if !e.variants.is_empty() {
+                    body.push_str("## Variants\n\n");
+                    for variant in &e.variants {
+                        self.member(id, variant, 3, cfg, &mut body);
+                    }
+                }
+            }
+            clean::TraitItem(ref t) => {
+                for &(ty, title) in &[(ItemType::AssocType, "Associated Types"),
+                                      (ItemType::AssocConst, "Associated Constants"),
+                                      (ItemType::TyMethod, "Required Methods"),
+                                      (ItemType::Method, "Provided Methods")] {
+                    let items = t.items.iter().filter(|i| i.type_() == ty).collect::<Vec<_>>();
+                    if !items.is_empty() {
+                        body.push_str(&format!("## {}\n\n", title));
+                        for i in items {
+                            self.member(id, i, 3, cfg, &mut body);
+                        }
+                    }
+                }
+                if let Some(impls) = self.implementors.get(&item.def_id) {
+                    let impls = impls.iter().filter(|&&(_, i)| i.blanket_impl.is_none());
+                    let (auto, impls) = impls.partition::<Vec<_>, _>(|&&(_, i)| i.synthetic);
+                    impl_list("Implementors", &impls, &mut body);
+                    impl_list("Auto Implementors", &auto, &mut body);
+                }
+            }
+            _ => {}
+        }
+        if let Some(impls) = self.impls.get(&item.def_id) {
+            self.impl_sections(id, impls, cfg, &mut body);
+        }
+
+        let (kind, declaration) = declaration(item);
+        Document {
+            kind,
+            path: page.path.join("::"),
+            summary: item.doc_value().map(plain_summary_line).unwrap_or_default(),
+            declaration,
+            body,
+        }
+    }
+
+    /// Lists the items of a module, grouped by their kind.
+    fn module_sections(&self, id: usize, items: &[clean::Item], body: &mut String) {
+        const ORDER: &[ItemType] = &[
+            ItemType::Import, ItemType::Primitive, ItemType::Module, ItemType::Macro,
+            ItemType::Struct, ItemType::Union, ItemType::Enum, ItemType::Constant,
+            ItemType::Static, ItemType::Trait, ItemType::TraitAlias, ItemType::Function,
+            ItemType::Typedef, ItemType::OpaqueTy, ItemType::ForeignType,
+            ItemType::ProcAttribute, ItemType::ProcDerive, ItemType::Keyword,
+        ];
+
+        for &ty in ORDER {
+            let mut entries = items.iter().filter(|item| {
+                let item_ty = match item.type_() {
+                    ItemType::ExternCrate => ItemType::Import,
+                    item_ty => item_ty,
+                };
+                !item.is_stripped() && item_ty == ty
+            }).filter_map(|item| {
+                let entry = match item.inner {
+                    clean::ExternCrateItem(ref name, ref rename) => match *rename {
+                        Some(ref rename) => format!("`{:#}extern crate {} as {};`",
+                                                    item.visibility.print_with_space(),
+                                                    name, rename),
+                        None => format!("`{:#}extern crate {};`",
+                                        item.visibility.print_with_space(), name),
+                    },
+                    clean::ImportItem(ref import) => {
+                        format!("`{:#}{:#};`", item.visibility.print_with_space(), import.print())
+                    }
+                    _ => {
+                        let to = *self.page_ids.get(&item.def_id)?;
+                        let mut entry = format!("[`{}`]({})",
+                                                item.name.as_ref()?, self.href(id, to, None));
+                        let summary = item.doc_value().map(plain_summary_line)
+                            .unwrap_or_default();
+                        if !summary.is_empty() {
+                            entry.push_str(": ");
+                            entry.push_str(&summary);
+                        }
+                        entry
+                    }
+                };
+                Some((item.name.clone(), entry))
+            }).collect::<Vec<_>>();
+            if entries.is_empty() {
+                continue;
+            }
+            entries.sort();
+
+            body.push_str(&format!("## {}\n\n", item_ty_to_strs(&ty).1));
+            for (_, entry) in entries {
+                body.push_str(&format!("- {}\n", entry));
+            }
+            body.push('\n');
+        }
+    }
+
+    /// Describes the impls of a type: the items of the inherent impls in full, and the trait
+    /// impls as lists.
+    fn impl_sections(
+        &self,
+        id: usize,
+        impls: &[(&clean::Item, &clean::Impl)],
+        cfg: Option<&clean::cfg::Cfg>,
+        body: &mut String,
+    ) {
+        let (inherent, traits) = impls.iter().partition::<Vec<_>, _>(|&&(_, i)| {
+            i.trait_.is_none()
+        });
+        if !inherent.is_empty() {
+            body.push_str("## Implementations\n\n");
+            for &(item, i) in inherent {
+                body.push_str(&format!("### `{}`\n\n", one_line(&format!("{:#}", i.print()))));
+                body.push_str(&self.docs(id, item, 3, cfg));
+                for member in i.items.iter().filter(|i| !i.is_stripped()) {
+                    self.member(id, member, 4, cfg, body);
+                }
+            }
+        }
+
+        let (blanket, traits) = traits.into_iter().partition::<Vec<_>, _>(|&&(_, i)| {
+            i.blanket_impl.is_some()
+        });
+        let (auto, traits) = traits.into_iter().partition::<Vec<_>, _>(|&&(_, i)| i.synthetic);
+        impl_list("Trait Implementations", &traits, body);
+        impl_list("Auto Trait Implementations", &auto, body);
+        impl_list("Blanket Implementations", &blanket, body);
+    }
+
+    /// Describes a field, variant or associated item of the page `id` under a heading of the
+    /// given level, with an anchor named like the ones in the HTML docs.
+    fn member(
+        &self,
+        id: usize,
+        item: &clean::Item,
+        level: usize,
+        cfg: Option<&clean::cfg::Cfg>,
+        body: &mut String,
+    ) {
+        let name = match item.name {
+            Some(ref name) => name,
+            None => return,
+        };
+        let anchor = match item.inner {
+            clean::TypedefItem(_, true) => "associatedtype",
+            _ => item.type_().as_str(),
+        };
+        body.push_str(&format!("{} <a id=\"{}.{}\"></a>`{}`\n\n",
+                               "#".repeat(level),
+                               anchor,
+                               name,
+                               one_line(&member_declaration(item))));
+        body.push_str(&self.docs(id, item, level, cfg));
+
+        // The fields of struct-like variants.
+        if let clean::VariantItem(clean::Variant {
+            kind: clean::VariantKind::Struct(ref s),
+        }) = item.inner {
+            for field in s.fields.iter().filter(|f| !f.is_stripped()) {
+                let mut entry = format!("- `{}`", member_declaration(field));
+                let summary = field.doc_value().map(plain_summary_line).unwrap_or_default();
+                if !summary.is_empty() {
+                    entry.push_str(": ");
+                    entry.push_str(&summary);
+                }
+                body.push_str(&entry);
+                body.push('\n');
+            }
+            body.push('\n');
+        }
+    }
+}
+
+/// Whether the HTML docs have a page for `item`.
+fn has_page(item: &clean::Item) -> bool {
+    match item.type_() {
+        ItemType::Module | ItemType::Struct | ItemType::Union | ItemType::Enum |
+        ItemType::Function | ItemType::Typedef | ItemType::OpaqueTy | ItemType::Static |
+        ItemType::Constant | ItemType::Trait | ItemType::TraitAlias | ItemType::Macro |
+        ItemType::ProcAttribute | ItemType::ProcDerive | ItemType::Primitive |
+        ItemType::ForeignType | ItemType::Keyword => true,
+        _ => false,
+    }
+}
+
+fn impl_list(title: &str, impls: &[&(&clean::Item, &clean::Impl)], body: &mut String) {
+    if impls.is_empty() {
+        return;
+    }
+    body.push_str(&format!("## {}\n\n", title));
+    for &&(_, i) in impls {
+        body.push_str(&format!("- `{}`\n", one_line(&format!("{:#}", i.print()))));
+    }
+    body.push('\n');
+}
+
+/// The path of the file `to`, relative to the directory of the file `from`.
+fn relative_path(from: &Path, to: &Path) -> String {
+    let from = from.parent().map(|p| p.components().collect::<Vec<_>>()).unwrap_or_default();
+    let to = to.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut path = "../".repeat(from.len() - common);
+    let rest = to[common..].iter()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+    path.push_str(&rest.join("/"));
+    path
+}
+
+/// Joins the lines of a declaration, to write it inline.
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// What kind of item `item` is, as written in the title of its page, and its declaration.
+fn declaration(item: &clean::Item) -> (&'static str, Option<String>) {
+    let name = item.name.as_ref().map(|s| &**s).unwrap_or("");
+    let vis = format!("{:#}", item.visibility.print_with_space());
+    match item.inner {
+        clean::ModuleItem(ref m) => (if m.is_crate { "Crate" } else { "Module" }, None),
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            ("Function", Some(fn_declaration(&vis, name, &f.header, &f.generics, &f.decl)))
+        }
+        clean::StructItem(ref s) => {
+            let decl = struct_declaration(&vis, "struct", name, &s.generics, s.struct_type,
+                                          &s.fields, s.fields_stripped);
+            ("Struct", Some(decl))
+        }
+        clean::UnionItem(ref u) => {
+            let decl = struct_declaration(&vis, "union", name, &u.generics, u.struct_type,
+                                          &u.fields, u.fields_stripped);
+            ("Union", Some(decl))
+        }
+        clean::EnumItem(ref e) => {
+            let mut decl = format!("{}enum {}{:#}{:#} {{\n", vis, name, e.generics.print(),
+                                   WhereClause { gens: &e.generics, indent: 0, end_newline: true });
+            for variant in &e.variants {
+                decl.push_str(&format!("    {},\n", member_declaration(variant)));
+            }
+            if e.variants_stripped {
+                decl.push_str("    // some variants omitted\n");
+            }
+            decl.push('}');
+            ("Enum", Some(decl))
+        }
+        clean::TraitItem(ref t) => {
+            let mut decl = format!("{}{}{}trait {}{:#}",
+                                   vis,
+                                   t.unsafety.print_with_space(),
+                                   if t.is_auto { "auto " } else { "" },
+                                   name,
+                                   t.generics.print());
+            if !t.bounds.is_empty() {
+                decl.push_str(&format!(": {:#}", print_generic_bounds(&t.bounds)));
+            }
+            let where_clause = WhereClause { gens: &t.generics, indent: 0, end_newline: true };
+            decl.push_str(&format!("{:#} {{\n", where_clause));
+            for i in &t.items {
+                let body = if i.type_() == ItemType::Method { " { ... }" } else { ";" };
+                decl.push_str(&format!("    {}{}\n", member_declaration(i), body));
+            }
+            decl.push('}');
+            ("Trait", Some(decl))
+        }
+        clean::TraitAliasItem(ref t) => {
+            ("Trait Alias", Some(format!("trait {}{:#}{:#} = {:#};",
+                                         name,
+                                         t.generics.print(),
+                                         WhereClause { gens: &t.generics, indent: 0,
+                                                       end_newline: false },
+                                         print_generic_bounds(&t.bounds))))
+        }
+        clean::TypedefItem(ref t, _) => {
+            ("Type Definition", Some(format!("{}type {}{:#}{:#} = {:#};",
+                                             vis,
+                                             name,
+                                             t.generics.print(),
+                                             WhereClause { gens: &t.generics, indent: 0,
+                                                           end_newline: false },
+                                             t.type_.print())))
+        }
+        clean::OpaqueTyItem(ref t, _) => {
+            ("Type Definition", Some(format!("type {}{:#}{:#} = impl {:#};",
+                                             name,
+                                             t.generics.print(),
+                                             WhereClause { gens: &t.generics, indent: 0,
+                                                           end_newline: false },
+                                             print_generic_bounds(&t.bounds))))
+        }
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => {
+            ("Static", Some(format!("{}static {}{}: {:#} = {};",
+                                    vis,
+                                    s.mutability.print_with_space(),
+                                    name,
+                                    s.type_.print(),
+                                    s.expr)))
+        }
+        clean::ConstantItem(ref c) => {
+            ("Constant", Some(format!("{}const {}: {:#} = {};",
+                                      vis, name, c.type_.print(), c.expr)))
+        }
+        clean::MacroItem(ref m) => ("Macro", Some(m.source.clone())),
+        clean::ProcMacroItem(ref m) => match m.kind {
+            MacroKind::Bang => ("Macro", Some(format!("{}!() {{ /* proc-macro */ }}", name))),
+            MacroKind::Attr => ("Attribute Macro", Some(format!("#[{}]", name))),
+            MacroKind::Derive => {
+                let mut decl = format!("#[derive({})]", name);
+                if !m.helpers.is_empty() {
+                    decl.push_str(&format!("\n\n// Helper attributes: {}", m.helpers.join(", ")));
+                }
+                ("Derive Macro", Some(decl))
+            }
+        },
+        clean::ForeignTypeItem => {
+            ("Foreign Type", Some(format!("extern {{\n    {}type {};\n}}", vis, name)))
+        }
+        clean::PrimitiveItem(..) => ("Primitive Type", None),
+        clean::KeywordItem(..) => ("Keyword", None),
+        _ => ("Item", None),
+    }
+}
+
+fn fn_declaration(
+    vis: &str,
+    name: &str,
+    header: &rustc::hir::FnHeader,
+    generics: &clean::Generics,
+    decl: &clean::FnDecl,
+) -> String {
+    let head = format!("{}{}{}{}{:#}fn {}{:#}",
+                       vis,
+                       header.constness.print_with_space(),
+                       header.unsafety.print_with_space(),
+                       header.asyncness.print_with_space(),
+                       print_abi_with_space(header.abi),
+                       name,
+                       generics.print());
+    let decl = Function {
+        decl,
+        header_len: head.len(),
+        indent: 0,
+        asyncness: header.asyncness,
+    };
+    // `Function` pads the arguments with HTML spaces when it breaks them into lines, even in
+    // the plain form.
+    format!("{}{:#}{:#}",
+            head,
+            decl.print(),
+            WhereClause { gens: generics, indent: 0, end_newline: false }).replace("&nbsp;", " ")
+}
+
+fn struct_declaration(
+    vis: &str,
+    keyword: &str,
+    name: &str,
+    generics: &clean::Generics,
+    struct_type: doctree::StructType,
+    fields: &[clean::Item],
+    fields_stripped: bool,
+) -> String {
+    let mut decl = format!("{}{} {}{:#}", vis, keyword, name, generics.print());
+    match struct_type {
+        doctree::Plain => {
+            decl.push_str(&format!("{:#} {{\n",
+                                   WhereClause { gens: generics, indent: 0, end_newline: true }));
+            for field in fields.iter().filter(|f| !f.is_stripped()) {
+                decl.push_str(&format!("    {},\n", member_declaration(field)));
+            }
+            if fields_stripped {
+                decl.push_str("    // some fields omitted\n");
+            }
+            decl.push('}');
+        }
+        doctree::Tuple => {
+            let fields = fields.iter().map(|field| match field.inner {
+                clean::StructFieldItem(ref ty) => {
+                    format!("{:#}{:#}", field.visibility.print_with_space(), ty.print())
+                }
+                _ => "_".to_string(),
+            }).collect::<Vec<_>>();
+            decl.push_str(&format!("({}){:#};",
+                                   fields.join(", "),
+                                   WhereClause { gens: generics, indent: 0, end_newline: false }));
+        }
+        doctree::Unit => {
+            decl.push_str(&format!("{:#};",
+                                   WhereClause { gens: generics, indent: 0, end_newline: false }));
+        }
+    }
+    decl
+}
+
+/// The declaration of a field, variant or associated item, without a trailing `;`.
+fn member_declaration(item: &clean::Item) -> String {
+    let name = item.name.as_ref().map(|s| &**s).unwrap_or("");
+    let vis = format!("{:#}", item.visibility.print_with_space());
+    match item.inner {
+        clean::StructFieldItem(ref ty) => format!("{}{}: {:#}", vis, name, ty.print()),
+        clean::VariantItem(ref v) => match v.kind {
+            clean::VariantKind::CLike => name.to_string(),
+            clean::VariantKind::Tuple(ref tys) => {
+                let tys = tys.iter().map(|ty| format!("{:#}", ty.print())).collect::<Vec<_>>();
+                format!("{}({})", name, tys.join(", "))
+            }
+            clean::VariantKind::Struct(ref s) => {
+                let mut fields = s.fields.iter()
+                    .filter(|f| !f.is_stripped())
+                    .map(member_declaration)
+                    .collect::<Vec<_>>();
+                if s.fields_stripped {
+                    fields.push("..".to_string());
+                }
+                format!("{} {{ {} }}", name, fields.join(", "))
+            }
+        },
+        clean::TyMethodItem(ref m) => fn_declaration(&vis, name, &m.header, &m.generics, &m.decl),
+        clean::MethodItem(ref m) => fn_declaration(&vis, name, &m.header, &m.generics, &m.decl),
+        clean::AssocConstItem(ref ty, ref default) => {
+            let mut decl = format!("const {}: {:#}", name, ty.print());
+            if let Some(ref default) = *default {
+                decl.push_str(&format!(" = {}", default));
+            }
+            decl
+        }
+        clean::AssocTypeItem(ref bounds, ref default) => {
+            let mut decl = format!("type {}", name);
+            if !bounds.is_empty() {
+                decl.push_str(&format!(": {:#}", print_generic_bounds(bounds)));
+            }
+            if let Some(ref default) = *default {
+                decl.push_str(&format!(" = {:#}", default.print()));
+            }
+            decl
+        }
+        clean::TypedefItem(ref t, true) => format!("type {} = {:#}", name, t.type_.print()),
+        _ => name.to_string(),
+    }
+}
@@ -0,0 +1,277 @@
+//! Converts the pages of the text backends to man pages, written with the `man` macros of troff.
+
+use pulldown_cmark::{Event, Tag};
+
+use crate::html::markdown;
+use super::Document;
+
+/// Renders `page` as the man page `name`. `source` names the crate, and is shown in the footer.
+crate fn render(name: &str, source: &str, page: &Document) -> String {
+    let mut man = Troff::default();
+    man.request(&format!(".TH {} 3 \"\" {} \"Rust Library Documentation\"",
+                         quote(name), quote(source)));
+    man.request(".SH NAME");
+    man.text(name);
+    man.out.push_str(" \\- ");
+    if page.summary.is_empty() {
+        man.text(&format!("{} {}", page.kind, page.path));
+    } else {
+        man.text(&page.summary);
+    }
+    if let Some(ref declaration) = page.declaration {
+        man.request(".SH SYNOPSIS");
+        man.request(".nf");
+        man.code(declaration);
+        man.request(".fi");
+    }
+    if !page.body.starts_with("## ") {
+        man.request(".SH DESCRIPTION");
+    }
+    man.markdown(&page.body);
+    man.newline();
+    man.out
+}
+
+#[derive(Default)]
+struct Troff {
+    out: String,
+    /// The fonts that were changed by the inline elements being written, innermost last.
+    fonts: Vec<char>,
+    /// The next number of each list being written, or `None` for bulleted lists.
+    lists: Vec<Option<u64>>,
+    /// Whether the next paragraph starts a list item, and so doesn't need a break.
+    in_item: bool,
+    in_code_block: bool,
+    /// The destinations of the links being written, and where their text starts in `out`, or in
+    /// the heading if they are in one.
+    links: Vec<(String, usize)>,
+    /// The level and text of the heading being written.
+    heading: Option<(i32, String)>,
+    table_cell: usize,
+}
+
+impl Troff {
+    fn newline(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    /// Writes a request, like `.PP`, on a line of its own.
+    fn request(&mut self, request: &str) {
+        self.newline();
+        self.out.push_str(request);
+        self.out.push('\n');
+    }
+
+    /// Writes text, escaping what troff would otherwise interpret.
+    fn text(&mut self, text: &str) {
+        if let Some((_, ref mut heading)) = self.heading {
+            heading.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if self.out.is_empty() || self.out.ends_with('\n') {
+                if c == '.' || c == '\'' {
+                    self.out.push_str("\\&");
+                }
+            }
+            match c {
+                '\\' => self.out.push_str("\\e"),
+                '-' if self.in_code_block || self.fonts.last() == Some(&'B') => {
+                    self.out.push_str("\\-")
+                }
+                c => self.out.push(c),
+            }
+        }
+    }
+
+    /// Writes a block of code, which must already be in a `.nf` block.
+    fn code(&mut self, code: &str) {
+        let in_code_block = self.in_code_block;
+        self.in_code_block = true;
+        self.text(code);
+        self.in_code_block = in_code_block;
+    }
+
+    /// The text written so far, in the heading being written if there is one.
+    fn written(&self) -> &str {
+        match self.heading {
+            Some((_, ref heading)) => heading,
+            None => &self.out,
+        }
+    }
+
+    /// Changes the font. Headings are written in their own font, so this does nothing in them.
+    fn push_font(&mut self, font: char) {
+        if self.heading.is_some() {
+            return;
+        }
+        self.fonts.push(font);
+        self.out.push_str(&format!("\\f{}", font));
+    }
+
+    fn pop_font(&mut self) {
+        if self.heading.is_some() {
+            return;
+        }
+        self.fonts.pop();
+        let font = self.fonts.last().cloned().unwrap_or('R');
+        self.out.push_str(&format!("\\f{}", font));
+    }
+
+    fn markdown(&mut self, md: &str) {
+        for event in markdown::events(md) {
+            match event {
+                Event::Start(tag) => self.start(tag),
+                Event::End(tag) => self.end(tag),
+                Event::Text(text) => {
+                    if self.in_code_block {
+                        self.code(&text);
+                    } else {
+                        self.text(&text);
+                    }
+                }
+                Event::Code(code) => {
+                    self.push_font('B');
+                    self.text(&code);
+                    self.pop_font();
+                }
+                Event::SoftBreak => self.text(" "),
+                Event::HardBreak => self.request(".br"),
+                Event::FootnoteReference(name) => self.text(&format!("[{}]", name)),
+                _ => {}
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph => {
+                if self.in_item {
+                    self.in_item = false;
+                    self.newline();
+                } else {
+                    self.request(".PP");
+                }
+            }
+            Tag::Header(level) => self.heading = Some((level, String::new())),
+            Tag::BlockQuote => self.request(".RS 4"),
+            Tag::CodeBlock(_) => {
+                if !self.in_item {
+                    self.request(".PP");
+                }
+                self.in_item = false;
+                self.request(".RS 4");
+                self.request(".nf");
+                self.in_code_block = true;
+            }
+            Tag::List(start) => {
+                if !self.lists.is_empty() {
+                    self.request(".RS 4");
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!(".IP {}. 4", *n - 1)
+                    }
+                    _ => ".IP \\(bu 2".to_string(),
+                };
+                self.request(&marker);
+                self.in_item = true;
+            }
+            Tag::FootnoteDefinition(name) => {
+                self.request(".PP");
+                self.text(&format!("[{}] ", name));
+            }
+            Tag::Table(_) => self.request(".PP"),
+            Tag::TableHead | Tag::TableRow => {
+                self.request(".br");
+                self.table_cell = 0;
+            }
+            Tag::TableCell => {
+                if self.table_cell > 0 {
+                    self.text(" | ");
+                }
+                self.table_cell += 1;
+            }
+            Tag::Emphasis => self.push_font('I'),
+            Tag::Strong => self.push_font('B'),
+            Tag::Link(_, dest, _) => {
+                let start = self.written().len();
+                self.links.push((dest.to_string(), start));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph | Tag::Item | Tag::TableHead | Tag::TableRow => {
+                self.in_item = false;
+                self.newline();
+            }
+            Tag::Header(_) => {
+                let (level, heading) = self.heading.take().expect("a heading was started");
+                match level {
+                    1 | 2 => self.request(&format!(".SH {}", quote(&heading.to_uppercase()))),
+                    3 => self.request(&format!(".SS {}", quote(&heading))),
+                    _ => {
+                        self.request(".PP");
+                        self.push_font('B');
+                        self.text(&heading);
+                        self.pop_font();
+                        self.newline();
+                    }
+                }
+            }
+            Tag::BlockQuote => self.request(".RE"),
+            Tag::CodeBlock(_) => {
+                self.in_code_block = false;
+                self.request(".fi");
+                self.request(".RE");
+            }
+            Tag::List(_) => {
+                self.lists.pop();
+                if !self.lists.is_empty() {
+                    self.request(".RE");
+                }
+            }
+            Tag::Emphasis | Tag::Strong => self.pop_font(),
+            Tag::Link(..) => {
+                let (dest, start) = self.links.pop().expect("a link was started");
+                if dest.starts_with("man:") {
+                    // Another man page, written as `name(3)`.
+                    let page = &dest["man:".len()..];
+                    let (name, section) = page.split_at(page.rfind('(').unwrap_or(page.len()));
+                    self.text(" (");
+                    self.push_font('B');
+                    self.text(name);
+                    self.pop_font();
+                    self.text(&format!("{})", section));
+                } else if (dest.starts_with("http://") || dest.starts_with("https://")) &&
+                          self.written()[start..] != dest {
+                    self.text(&format!(" <{}>", dest));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Quotes an argument of a request.
+fn quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' => quoted.push_str("\\(dq"),
+            '\\' => quoted.push_str("\\e"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
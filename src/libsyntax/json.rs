@@ -13,7 +13,7 @@ use crate::source_map::{SourceMap, FilePathMapping};
 
 use errors::registry::Registry;
 use errors::{SubDiagnostic, CodeSuggestion, SourceMapper};
-use errors::{DiagnosticId, Applicability};
+use errors::{DiagnosticId, Applicability, FutureIncompatLint};
 use errors::emitter::{Emitter, HumanReadableErrorType};
 
 use syntax_pos::{MacroBacktrace, Span, SpanLabel, MultiSpan};
@@ -113,6 +113,28 @@ impl Emitter for JsonEmitter {
         }
     }
 
+    fn emit_future_incompat_report(&mut self, lints: &[FutureIncompatLint]) {
+        let data = FutureIncompatReport {
+            future_incompat_report: lints.iter().map(|lint| {
+                FutureIncompatReportLint {
+                    lint: &lint.lint,
+                    level: &lint.level,
+                    reference: &lint.reference,
+                    message: &lint.message,
+                    spans: DiagnosticSpan::from_multispan(&lint.span, self),
+                }
+            }).collect(),
+        };
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", as_pretty_json(&data))
+        } else {
+            writeln!(&mut self.dst, "{}", as_json(&data))
+        };
+        if let Err(e) = result {
+            panic!("failed to print future-incompat report: {:?}", e);
+        }
+    }
+
     fn should_show_explain(&self) -> bool {
         match self.json_rendered {
             HumanReadableErrorType::Short(_) => false,
@@ -204,6 +226,25 @@ struct ArtifactNotification<'a> {
     emit: &'a str,
 }
 
+#[derive(RustcEncodable)]
+struct FutureIncompatReport<'a> {
+    /// The future-incompatible lints emitted in the crate, at any level.
+    future_incompat_report: Vec<FutureIncompatReportLint<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct FutureIncompatReportLint<'a> {
+    /// The name of the lint.
+    lint: &'a str,
+    /// The level the lint was emitted at: "allow", "warn", "deny" or "forbid".
+    level: &'a str,
+    /// Where to learn more, usually a URL for the tracking issue.
+    reference: &'a str,
+    /// The message of the lint.
+    message: &'a str,
+    spans: Vec<DiagnosticSpan>,
+}
+
 impl Diagnostic {
     fn from_errors_diagnostic(db: &errors::Diagnostic,
                                je: &JsonEmitter)
@@ -465,8 +465,10 @@ impl<'a> TraitDef<'a> {
                 attrs.extend(item.attrs
                     .iter()
                     .filter(|a| {
-                        [sym::allow, sym::warn, sym::deny, sym::forbid, sym::stable, sym::unstable]
-                            .contains(&a.name_or_empty())
+                        [
+                            sym::allow, sym::expect, sym::warn, sym::deny, sym::forbid,
+                            sym::stable, sym::unstable,
+                        ].contains(&a.name_or_empty())
                     })
                     .cloned());
                 push(Annotatable::Item(P(ast::Item { attrs: attrs, ..(*newitem).clone() })))
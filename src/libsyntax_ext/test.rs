@@ -170,6 +170,18 @@ pub fn expand_test_or_bench(
                     field("ignore", cx.expr_bool(sp, should_ignore(&item))),
                     // allow_fail: true | false
                     field("allow_fail", cx.expr_bool(sp, should_fail(&item))),
+                    // timeout: Some(seconds) | None
+                    field("timeout", match test_timeout(cx, &item) {
+                        // std::option::Option::Some(...)
+                        Some(secs) => cx.expr_some(sp, cx.expr_lit(sp, ast::LitKind::Int(
+                            secs as u128,
+                            ast::LitIntType::Unsigned(ast::UintTy::U64),
+                        ))),
+                        // std::option::Option::None
+                        None => cx.expr_path(
+                            cx.path_global(sp, cx.std_path(&[sym::option, sym::Option, sym::None]))
+                        ),
+                    }),
                     // should_panic: ...
                     field("should_panic", match should_panic(cx, &item) {
                         // test::ShouldPanic::No
@@ -228,6 +240,28 @@ fn should_fail(i: &ast::Item) -> bool {
     attr::contains_name(&i.attrs, sym::allow_fail)
 }
 
+fn test_timeout(cx: &ExtCtxt<'_>, i: &ast::Item) -> Option<u64> {
+    let attr = attr::find_by_name(&i.attrs, sym::test_timeout)?;
+    let secs = attr.meta_item_list()
+        .filter(|list| list.len() == 1)
+        .and_then(|list| match list[0].literal() {
+            Some(ast::Lit { kind: ast::LitKind::Int(secs, _), .. }) => Some(*secs),
+            _ => None,
+        });
+
+    match secs {
+        Some(secs) if secs > 0 && secs <= u64::max_value() as u128 => Some(secs as u64),
+        _ => {
+            cx.parse_sess.span_diagnostic.span_err(
+                attr.span,
+                "argument must be of the form: `#[test_timeout(seconds)]` \
+                 with a positive number of seconds"
+            );
+            None
+        }
+    }
+}
+
 fn should_panic(cx: &ExtCtxt<'_>, i: &ast::Item) -> ShouldPanic {
     match attr::find_by_name(&i.attrs, sym::should_panic) {
         Some(attr) => {
@@ -278,6 +278,7 @@ symbols! {
         exhaustive_integer_patterns,
         exhaustive_patterns,
         existential_type,
+        expect,
         expected,
         export_name,
         expr,
@@ -667,6 +668,7 @@ symbols! {
         test_case,
         test_removed_feature,
         test_runner,
+        test_timeout,
         then_with,
         thread_local,
         tool_attributes,
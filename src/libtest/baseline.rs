@@ -0,0 +1,137 @@
+//! Benchmark baselines: saving the results of a `--bench` run to disk with
+//! `--save-baseline`, and comparing a later run against them with `--baseline`.
+
+use crate::stats;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+const HEADER: &str = "# libtest benchmark baseline, format version 1";
+
+/// The statistics kept for each benchmark in a baseline, in ns/iter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchStats {
+    pub median: f64,
+    pub median_abs_dev: f64,
+}
+
+impl<'a> From<&'a stats::Summary> for BenchStats {
+    fn from(summary: &'a stats::Summary) -> BenchStats {
+        BenchStats {
+            median: summary.median,
+            median_abs_dev: summary.median_abs_dev,
+        }
+    }
+}
+
+/// Benchmark statistics keyed by benchmark name.
+pub type Baseline = BTreeMap<String, BenchStats>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    NoChange,
+}
+
+/// The result of comparing one benchmark against its baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub name: String,
+    pub old: BenchStats,
+    pub new: BenchStats,
+    /// Change of the median, in percent of the old median.
+    pub change_pct: f64,
+    pub verdict: Verdict,
+    /// Whether this is a regression larger than the `--regression-threshold`.
+    pub exceeds_threshold: bool,
+}
+
+/// Whether `name` can name a baseline: it must be a plain file name, so that
+/// the baseline stays in the baseline directory.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(file_name)), None) => file_name == name,
+        _ => false,
+    }
+}
+
+/// The file in `dir` that the baseline `name` is stored in.
+pub fn path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.txt", name))
+}
+
+pub fn save(path: &Path, baseline: &Baseline) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut contents = String::new();
+    contents.push_str(HEADER);
+    contents.push('\n');
+    for (name, stats) in baseline {
+        // The name goes last, so that it may contain spaces.
+        contents.push_str(&format!("{} {} {}\n", stats.median, stats.median_abs_dev, name));
+    }
+    fs::write(path, contents)
+}
+
+pub fn load(path: &Path) -> io::Result<Baseline> {
+    let contents = fs::read_to_string(path)?;
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let mut lines = contents.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(invalid("not a benchmark baseline".to_string()));
+    }
+
+    let mut baseline = Baseline::new();
+    for line in lines {
+        let mut parts = line.splitn(3, ' ');
+        let mut number = || parts.next().and_then(|s| s.parse::<f64>().ok());
+        let stats = match (number(), number()) {
+            (Some(median), Some(median_abs_dev)) => BenchStats { median, median_abs_dev },
+            _ => return Err(invalid(format!("malformed line `{}`", line))),
+        };
+        match parts.next() {
+            Some(name) if !name.is_empty() => baseline.insert(name.to_string(), stats),
+            _ => return Err(invalid(format!("malformed line `{}`", line))),
+        };
+    }
+    Ok(baseline)
+}
+
+/// Compares the benchmarks that appear in both `old` and `new`.
+///
+/// A change of the median is only considered significant when it is larger
+/// than the sum of the median absolute deviations of both runs, i.e. when the
+/// noise ranges of the two runs don't overlap.
+pub fn compare(old: &Baseline, new: &Baseline, regression_threshold: f64) -> Vec<Comparison> {
+    new.iter()
+        .filter_map(|(name, new)| old.get(name).map(|old| (name, *old, *new)))
+        .map(|(name, old, new)| {
+            let diff = new.median - old.median;
+            let change_pct = if old.median == 0.0 { 0.0 } else { diff / old.median * 100.0 };
+            let verdict = if diff.abs() <= old.median_abs_dev + new.median_abs_dev {
+                Verdict::NoChange
+            } else if diff > 0.0 {
+                Verdict::Regressed
+            } else {
+                Verdict::Improved
+            };
+
+            Comparison {
+                name: name.clone(),
+                old,
+                new,
+                change_pct,
+                verdict,
+                exceeds_threshold: verdict == Verdict::Regressed
+                    && change_pct > regression_threshold,
+            }
+        })
+        .collect()
+}
@@ -9,6 +9,11 @@ impl<T: Write> JsonFormatter<T> {
         Self { out }
     }
 
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
     fn writeln_message(&mut self, s: &str) -> io::Result<()> {
         assert!(!s.contains('\n'));
 
@@ -58,10 +63,19 @@ impl<T: Write> JsonFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+    ) -> io::Result<()> {
+        let shuffle_seed_json = if let Some(shuffle_seed) = shuffle_seed {
+            format!(r#", "shuffle_seed": {}"#, shuffle_seed)
+        } else {
+            String::new()
+        };
         self.writeln_message(&*format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
-            test_count
+            r#"{{ "type": "suite", "event": "started", "test_count": {}{} }}"#,
+            test_count, shuffle_seed_json
         ))
     }
 
@@ -80,7 +94,11 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()> {
-        let stdout = if (state.options.display_output || *result != TrOk) && stdout.len() > 0 {
+        let passed = match *result {
+            TrOk | TrOkAfterRetries(_) => true,
+            _ => false,
+        };
+        let stdout = if (state.options.display_output || !passed) && stdout.len() > 0 {
             Some(String::from_utf8_lossy(stdout))
         } else {
             None
@@ -90,6 +108,15 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 self.write_event("test", desc.name.as_slice(), "ok", exec_time, stdout, None)
             }
 
+            TrOkAfterRetries(retries) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ok",
+                exec_time,
+                stdout,
+                Some(&*format!(r#""retries": {}"#, retries)),
+            ),
+
             TrFailed => {
                 self.write_event("test", desc.name.as_slice(), "failed", exec_time, stdout, None)
             }
@@ -103,6 +130,15 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 Some(&*format!(r#""message": "{}""#, EscapedString(m))),
             ),
 
+            TrTimedFail => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                Some(r#""reason": "time limit exceeded""#),
+            ),
+
             TrIgnored => {
                 self.write_event("test", desc.name.as_slice(), "ignored", exec_time, stdout, None)
             }
@@ -146,18 +182,49 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
         ))
     }
 
+    fn write_bench_comparison(
+        &mut self,
+        baseline: &str,
+        comparisons: &[baseline::Comparison],
+    ) -> io::Result<()> {
+        for c in comparisons {
+            self.writeln_message(&*format!(
+                "{{ \"type\": \"bench_comparison\", \
+                 \"name\": \"{}\", \
+                 \"baseline\": \"{}\", \
+                 \"baseline_median\": {}, \
+                 \"median\": {}, \
+                 \"change_pct\": {:.2}, \
+                 \"verdict\": \"{}\" }}",
+                EscapedString(&c.name),
+                EscapedString(baseline),
+                c.old.median as usize,
+                c.new.median as usize,
+                c.change_pct,
+                match c.verdict {
+                    baseline::Verdict::Regressed => "regressed",
+                    baseline::Verdict::Improved => "improved",
+                    baseline::Verdict::NoChange => "no_change",
+                }
+            ))?;
+        }
+        Ok(())
+    }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         self.writeln_message(&*format!(
             "{{ \"type\": \"suite\", \
              \"event\": \"{}\", \
              \"passed\": {}, \
+             \"passed_after_retry\": {}, \
              \"failed\": {}, \
              \"allowed_fail\": {}, \
              \"ignored\": {}, \
              \"measured\": {}, \
              \"filtered_out\": {} }}",
-            if state.failed == 0 { "ok" } else { "failed" },
+            if state.failed == 0 && state.bench_regressions == 0 { "ok" } else { "failed" },
             state.passed,
+            state.passed_after_retry,
             state.failed + state.allowed_fail,
             state.allowed_fail,
             state.ignored,
@@ -165,7 +232,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
             state.filtered_out
         ))?;
 
-        Ok(state.failed == 0)
+        Ok(state.failed == 0 && state.bench_regressions == 0)
     }
 }
 
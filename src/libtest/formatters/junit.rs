@@ -0,0 +1,207 @@
+use super::*;
+
+pub(crate) struct JunitFormatter<T> {
+    out: OutputLocation<T>,
+    suite_name: String,
+    results: Vec<(TestDesc, TestResult, Option<TestExecTime>, Vec<u8>)>,
+}
+
+impl<T: Write> JunitFormatter<T> {
+    pub fn new(out: OutputLocation<T>, suite_name: String) -> Self {
+        Self {
+            out,
+            suite_name,
+            results: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_message(&mut self, s: &str) -> io::Result<()> {
+        assert!(!s.contains('\n'));
+
+        self.out.write_all(s.as_ref())
+    }
+
+    fn write_testcase(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&TestExecTime>,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        // Split `module::path::test_name` into a JUnit class name and a
+        // test case name, so that dashboards can group tests per module.
+        let name = desc.name.as_slice();
+        let (class_name, test_name) = match name.rfind("::") {
+            Some(idx) => (&name[..idx], &name[idx + 2..]),
+            None => (&*self.suite_name, name),
+        };
+        let time = exec_time.map(|t| t.0.as_secs_f64()).unwrap_or(0.0);
+
+        self.write_message(&*format!(
+            r#"<testcase classname="{}" name="{}" time="{:.3}""#,
+            EscapedXml(class_name),
+            EscapedXml(test_name),
+            time
+        ))?;
+
+        match *result {
+            TrOk | TrOkAfterRetries(_) | TrBench(_) if stdout.is_empty() => {
+                return self.write_message("/>");
+            }
+            TrOk | TrOkAfterRetries(_) | TrBench(_) => self.write_message(">")?,
+            TrIgnored => self.write_message("><skipped/>")?,
+            // Allowed failures don't fail the run, so they aren't reported as
+            // failures, like in the other formats.
+            TrAllowedFail => {
+                self.write_message(r#"><skipped message="failed (allowed)"/>"#)?;
+            }
+            TrFailed => {
+                self.write_message(r#"><failure type="assert"/>"#)?;
+            }
+            TrTimedFail => {
+                self.write_message(r#"><failure type="timeout" message="time limit exceeded"/>"#)?;
+            }
+            TrFailedMsg(ref m) => {
+                self.write_message(&*format!(
+                    r#"><failure type="assert" message="{}"/>"#,
+                    EscapedXml(m)
+                ))?;
+            }
+        }
+
+        if !stdout.is_empty() {
+            self.write_message(&*format!(
+                "<system-out>{}</system-out>",
+                EscapedXml(String::from_utf8_lossy(stdout))
+            ))?;
+        }
+        self.write_message("</testcase>")
+    }
+}
+
+impl<T: Write> OutputFormatter for JunitFormatter<T> {
+    fn write_run_start(
+        &mut self,
+        _test_count: usize,
+        _shuffle_seed: Option<u64>,
+    ) -> io::Result<()> {
+        // The whole report is written at the end of the run, once the
+        // per-suite totals are known.
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // Like the other formatters, only report the output of successful
+        // tests with `--show-output`.
+        let stdout = match *result {
+            TrOk | TrOkAfterRetries(_) if !state.options.display_output => Vec::new(),
+            _ => stdout.to_vec(),
+        };
+        self.results.push((desc.clone(), result.clone(), exec_time.cloned(), stdout));
+        Ok(())
+    }
+
+    fn write_bench_comparison(
+        &mut self,
+        _baseline: &str,
+        _comparisons: &[baseline::Comparison],
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let time: f64 = self
+            .results
+            .iter()
+            .filter_map(|(_, _, exec_time, _)| exec_time.as_ref())
+            .map(|t| t.0.as_secs_f64())
+            .sum();
+
+        self.write_message(r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        self.write_message("<testsuites>")?;
+        self.write_message(&*format!(
+            r#"<testsuite name="{}" package="{}" id="0" errors="0" failures="{}" tests="{}" skipped="{}" time="{:.3}">"#,
+            EscapedXml(&self.suite_name),
+            EscapedXml(&self.suite_name),
+            state.failed,
+            state.total,
+            state.ignored + state.allowed_fail,
+            time
+        ))?;
+
+        let results = std::mem::replace(&mut self.results, Vec::new());
+        for (desc, result, exec_time, stdout) in &results {
+            self.write_testcase(desc, result, exec_time.as_ref(), stdout)?;
+        }
+
+        self.write_message("<system-out/>")?;
+        self.write_message("<system-err/>")?;
+        self.write_message("</testsuite>")?;
+        self.write_message("</testsuites>")?;
+        self.out.write_all(b"\n")?;
+
+        Ok(state.failed == 0 && state.bench_regressions == 0)
+    }
+}
+
+/// A formatting utility used to print strings with characters in need of escaping
+/// in XML attribute values and text nodes.
+struct EscapedXml<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> ::std::fmt::Display for EscapedXml<S> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let mut start = 0;
+
+        for (i, c) in self.0.as_ref().char_indices() {
+            let escaped = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                '\n' => "&#10;",
+                '\r' => "&#13;",
+                '\t' => "&#9;",
+                // Other control characters are not allowed in XML 1.0 documents.
+                c if (c as u32) < 0x20 => "\u{FFFD}",
+                _ => {
+                    continue;
+                }
+            };
+
+            if start < i {
+                f.write_str(&self.0.as_ref()[start..i])?;
+            }
+
+            f.write_str(escaped)?;
+
+            start = i + c.len_utf8();
+        }
+
+        if start != self.0.as_ref().len() {
+            f.write_str(&self.0.as_ref()[start..])?;
+        }
+
+        Ok(())
+    }
+}
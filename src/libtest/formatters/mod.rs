@@ -2,14 +2,20 @@ use super::*;
 
 mod pretty;
 mod json;
+mod junit;
 mod terse;
 
 pub(crate) use self::pretty::PrettyFormatter;
 pub(crate) use self::json::JsonFormatter;
+pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()>;
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+    ) -> io::Result<()>;
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()>;
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()>;
     fn write_result(
@@ -20,9 +26,46 @@ pub(crate) trait OutputFormatter {
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()>;
+    fn write_bench_comparison(
+        &mut self,
+        baseline: &str,
+        comparisons: &[baseline::Comparison],
+    ) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
 }
 
+/// Writes the comparison of benchmarks against a baseline for the console
+/// formatters, through `write`, which colors its text if it's given a color.
+pub(crate) fn write_bench_comparison<F>(
+    baseline: &str,
+    comparisons: &[baseline::Comparison],
+    mut write: F,
+) -> io::Result<()>
+where
+    F: FnMut(&str, Option<term::color::Color>) -> io::Result<()>,
+{
+    write(&format!("\nbenchmarks compared to baseline `{}`:\n", baseline), None)?;
+    for c in comparisons {
+        write(&format!(
+            "    {}: {} -> {} ns/iter ({:+.2}%) ",
+            c.name,
+            fmt_thousands_sep(c.old.median as usize, ','),
+            fmt_thousands_sep(c.new.median as usize, ','),
+            c.change_pct
+        ), None)?;
+        match c.verdict {
+            baseline::Verdict::Regressed if c.exceeds_threshold => {
+                write("regressed beyond threshold", Some(term::color::RED))?
+            }
+            baseline::Verdict::Regressed => write("regressed", Some(term::color::YELLOW))?,
+            baseline::Verdict::Improved => write("improved", Some(term::color::GREEN))?,
+            baseline::Verdict::NoChange => write("no significant change", None)?,
+        }
+        write("\n", None)?;
+    }
+    Ok(())
+}
+
 pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &TestName) {
     match test_output.last() {
         Some(b'\n') => (),
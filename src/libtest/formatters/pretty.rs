@@ -34,6 +34,16 @@ impl<T: Write> PrettyFormatter<T> {
         self.write_short_result("ok", term::color::GREEN, exec_time)
     }
 
+    pub fn write_ok_after_retries(
+        &mut self,
+        retries: usize,
+        exec_time: Option<&TestExecTime>,
+    ) -> io::Result<()> {
+        let noun = if retries != 1 { "retries" } else { "retry" };
+        let result = format!("ok (after {} {})", retries, noun);
+        self.write_short_result(&result, term::color::YELLOW, exec_time)
+    }
+
     pub fn write_failed(&mut self, exec_time: Option<&TestExecTime>) -> io::Result<()> {
         self.write_short_result("FAILED", term::color::RED, exec_time)
     }
@@ -46,6 +56,10 @@ impl<T: Write> PrettyFormatter<T> {
         self.write_short_result("FAILED (allowed)", term::color::YELLOW, exec_time)
     }
 
+    pub fn write_time_failed(&mut self, exec_time: Option<&TestExecTime>) -> io::Result<()> {
+        self.write_short_result("FAILED (time limit exceeded)", term::color::RED, exec_time)
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
@@ -149,9 +163,18 @@ impl<T: Write> PrettyFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for PrettyFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+    ) -> io::Result<()> {
         let noun = if test_count != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", test_count, noun))
+        let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
+            format!(" (shuffle seed: {})", shuffle_seed)
+        } else {
+            String::new()
+        };
+        self.write_plain(&format!("\nrunning {} {}{}\n", test_count, noun, shuffle_seed_msg))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -180,9 +203,11 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
 
         match *result {
             TrOk => self.write_ok(exec_time),
+            TrOkAfterRetries(retries) => self.write_ok_after_retries(retries, exec_time),
             TrFailed | TrFailedMsg(_) => self.write_failed(exec_time),
             TrIgnored => self.write_ignored(exec_time),
             TrAllowedFail => self.write_allowed_fail(exec_time),
+            TrTimedFail => self.write_time_failed(exec_time),
             TrBench(ref bs) => {
                 self.write_bench()?;
                 self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
@@ -201,12 +226,23 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
         ))
     }
 
+    fn write_bench_comparison(
+        &mut self,
+        baseline: &str,
+        comparisons: &[baseline::Comparison],
+    ) -> io::Result<()> {
+        write_bench_comparison(baseline, comparisons, |s, color| match color {
+            Some(color) => self.write_pretty(s, color),
+            None => self.write_plain(s),
+        })
+    }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         if state.options.display_output {
             self.write_successes(state)?;
         }
-        let success = state.failed == 0;
-        if !success {
+        let success = state.failed == 0 && state.bench_regressions == 0;
+        if state.failed != 0 {
             self.write_failures(state)?;
         }
 
@@ -219,10 +255,15 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let passed = if state.passed_after_retry > 0 {
+            format!("{} passed ({} after retry)", state.passed, state.passed_after_retry)
+        } else {
+            format!("{} passed", state.passed)
+        };
         let s = if state.allowed_fail > 0 {
             format!(
-                ". {} passed; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
-                state.passed,
+                ". {}; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
+                passed,
                 state.failed + state.allowed_fail,
                 state.allowed_fail,
                 state.ignored,
@@ -231,8 +272,8 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             )
         } else {
             format!(
-                ". {} passed; {} failed; {} ignored; {} measured; {} filtered out\n\n",
-                state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+                ". {}; {} failed; {} ignored; {} measured; {} filtered out\n\n",
+                passed, state.failed, state.ignored, state.measured, state.filtered_out
             )
         };
 
@@ -46,17 +46,17 @@ pub use self::TestResult::*;
 use std::any::Any;
 use std::borrow::Cow;
 use std::cmp;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fmt;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
 use std::panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
-use std::process::{ExitStatus, Command, Termination};
-use std::sync::mpsc::{channel, Sender};
+use std::process::{Child, ExitStatus, Command, Stdio, Termination};
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -81,14 +81,18 @@ pub mod test {
         assert_test_result, filter_tests, parse_opts, run_test, test_main, test_main_static,
         Bencher, DynTestFn, DynTestName, Metric, MetricMap, Options, RunIgnored, RunStrategy,
         ShouldPanic, StaticBenchFn, StaticTestFn, StaticTestName, TestDesc, TestDescAndFn, TestName,
-        TestOpts, TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk,
+        TestOpts, TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk, TrOkAfterRetries,
+        TrTimedFail,
     };
 }
 
+mod baseline;
 mod formatters;
 pub mod stats;
 
-use crate::formatters::{JsonFormatter, OutputFormatter, PrettyFormatter, TerseFormatter};
+use crate::formatters::{
+    JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter,
+};
 
 /// Whether to execute tests concurrently or not
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -232,6 +236,9 @@ pub struct TestDesc {
     pub ignore: bool,
     pub should_panic: ShouldPanic,
     pub allow_fail: bool,
+    /// Number of seconds after which the test is stopped and reported as
+    /// failed, set with `#[test_timeout(seconds)]`. Overrides `--test-timeout`.
+    pub timeout: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -281,6 +288,11 @@ impl Options {
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Options>) {
+    // If we're being run in SpawnedSecondary mode, run the test here. run_test
+    // will then exit the process.
+    let mut tests = tests;
+    run_spawned_test_if_requested(&mut tests, options.map_or(false, |o| o.panic_abort));
+
     let mut opts = match parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => {
@@ -329,26 +341,30 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=abort.
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
-    // If we're being run in SpawnedSecondary mode, run the test here. run_test
-    // will then exit the process.
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
+
+/// Runs the test named by `SECONDARY_TEST_INVOKER_VAR` and exits the process,
+/// if the variable is set. This is how a subprocess spawned by
+/// `RunStrategy::SpawnPrimary` runs its test.
+fn run_spawned_test_if_requested(tests: &mut Vec<TestDescAndFn>, panic_abort: bool) {
     if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
-        let test = tests
+        // Don't let test binaries spawned by the test mistake themselves for
+        // a secondary process.
+        env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+        let idx = tests
             .iter()
-            .filter(|test| test.desc.name.as_slice() == name)
-            .map(make_owned_test)
-            .next()
+            .position(|test| test.desc.name.as_slice() == name)
             .expect("couldn't find a test with the provided name");
-        let TestDescAndFn { desc, testfn } = test;
+        let TestDescAndFn { desc, testfn } = tests.swap_remove(idx);
         let testfn = match testfn {
             StaticTestFn(f) => f,
             _ => panic!("only static tests are supported"),
         };
-        run_test_in_spawned_subprocess(desc, Box::new(testfn));
+        run_test_in_spawned_subprocess(desc, Box::new(testfn), panic_abort);
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -394,6 +410,7 @@ pub enum OutputFormat {
     Pretty,
     Terse,
     Json,
+    Junit,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -419,6 +436,19 @@ pub struct TestOpts {
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
     pub report_time: bool,
+    pub shuffle: bool,
+    pub shuffle_seed: Option<u64>,
+    pub test_timeout: Option<Duration>,
+    pub isolate: bool,
+    /// Only run the tests of shard `index` (counting from 1) out of `total`.
+    pub shard: Option<(usize, usize)>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub baseline_dir: PathBuf,
+    /// Regressions against `baseline` larger than this percentage fail the run.
+    pub regression_threshold: f64,
+    /// Number of times a failed test is run again before it is reported as failed.
+    pub retries: usize,
     pub options: Options,
 }
 
@@ -487,8 +517,9 @@ fn optgroups() -> getopts::Options {
             "Configure formatting of output:
             pretty = Print verbose output;
             terse  = Display one character per test;
-            json   = Output a json document",
-            "pretty|terse|json",
+            json   = Output a json document
+            junit  = Output a JUnit XML document",
+            "pretty|terse|json|junit",
         )
         .optflag(
             "",
@@ -506,6 +537,71 @@ fn optgroups() -> getopts::Options {
             "",
             "report-time",
             "Show execution time of each test. Not available for --format=terse"
+        )
+        .optflag(
+            "",
+            "shuffle",
+            "Run tests in random order"
+        )
+        .optopt(
+            "",
+            "shuffle-seed",
+            "Run tests in random order; seed the random number generator with SEED",
+            "SEED",
+        )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests that run for longer than SECS seconds. Tests run in \
+             a subprocess are killed, other tests are left running in the \
+             background",
+            "SECS",
+        )
+        .optflag(
+            "",
+            "isolate",
+            "Run each test in its own process, so that a crashing test \
+             only fails itself"
+        )
+        .optopt(
+            "",
+            "shard",
+            "Split the tests into TOTAL shards and only run shard INDEX \
+             (counting from 1)",
+            "INDEX/TOTAL",
+        )
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the benchmark results as the baseline NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the benchmark results against the baseline NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline-dir",
+            "Directory that benchmark baselines are stored in \
+             (default: bench-baselines)",
+            "PATH",
+        )
+        .optopt(
+            "",
+            "regression-threshold",
+            "Fail when a benchmark regressed by more than PCT percent \
+             against the baseline (default: 5)",
+            "PCT",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Run failed tests again up to N times before reporting them \
+             as failed. Only applies to statically defined tests",
+            "N",
         );
     return opts;
 }
@@ -613,6 +709,131 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         ));
     }
 
+    let mut shuffle = matches.opt_present("shuffle");
+    if !allow_unstable && shuffle {
+        return Some(Err(
+            "The \"shuffle\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(n_str) => match n_str.parse::<u64>() {
+            Ok(n) => Some(n),
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --shuffle-seed must be a number \
+                     (error: {})",
+                    e
+                )));
+            }
+        },
+        None => None,
+    };
+    if !allow_unstable && shuffle_seed.is_some() {
+        return Some(Err(
+            "The \"shuffle-seed\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+    if shuffle_seed.is_some() {
+        shuffle = true;
+    }
+
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(n_str) => match n_str.parse::<u64>() {
+            Ok(0) => return Some(Err("argument for --test-timeout must not be 0".to_string())),
+            Ok(n) => Some(Duration::from_secs(n)),
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --test-timeout must be a number > 0 \
+                     (error: {})",
+                    e
+                )));
+            }
+        },
+        None => None,
+    };
+    if !allow_unstable && test_timeout.is_some() {
+        return Some(Err(
+            "The \"test-timeout\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let isolate = matches.opt_present("isolate");
+    if !allow_unstable && isolate {
+        return Some(Err(
+            "The \"isolate\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let shard = match matches.opt_str("shard") {
+        Some(shard_str) => match parse_shard(&shard_str) {
+            Ok(shard) => Some(shard),
+            Err(e) => return Some(Err(e)),
+        },
+        None => None,
+    };
+    if !allow_unstable && shard.is_some() {
+        return Some(Err(
+            "The \"shard\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let save_baseline = matches.opt_str("save-baseline");
+    let baseline = matches.opt_str("baseline");
+    let baseline_dir = matches.opt_str("baseline-dir");
+    for (flag, name) in &[("save-baseline", &save_baseline), ("baseline", &baseline)] {
+        if let Some(name) = name {
+            if !baseline::is_valid_name(name) {
+                return Some(Err(format!(
+                    "argument for --{} must be a file name without a directory (was {})",
+                    flag, name
+                )));
+            }
+        }
+    }
+    let regression_threshold = match matches.opt_str("regression-threshold") {
+        Some(pct_str) => match pct_str.parse::<f64>() {
+            Ok(pct) if pct >= 0.0 => Some(pct),
+            _ => {
+                return Some(Err(format!(
+                    "argument for --regression-threshold must be a non-negative \
+                     number (was {})",
+                    pct_str
+                )));
+            }
+        },
+        None => None,
+    };
+    if !allow_unstable
+        && (save_baseline.is_some()
+            || baseline.is_some()
+            || baseline_dir.is_some()
+            || regression_threshold.is_some())
+    {
+        return Some(Err(
+            "The benchmark baseline flags are only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let retries = match matches.opt_str("retries") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --retries must be a number \
+                     (error: {})",
+                    e
+                )));
+            }
+        },
+        None => 0,
+    };
+    if !allow_unstable && retries > 0 {
+        return Some(Err(
+            "The \"retries\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
     let run_ignored = match (include_ignored, matches.opt_present("ignored")) {
         (true, true) => {
             return Some(Err(
@@ -682,10 +903,18 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
             }
             OutputFormat::Json
         }
+        Some("junit") => {
+            if !allow_unstable {
+                return Some(Err(
+                    "The \"junit\" format is only accepted on the nightly compiler".into(),
+                ));
+            }
+            OutputFormat::Junit
+        }
 
         Some(v) => {
             return Some(Err(format!(
-                "argument for --format must be pretty, terse, or json (was \
+                "argument for --format must be pretty, terse, json, or junit (was \
                  {})",
                 v
             )));
@@ -707,12 +936,40 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         test_threads,
         skip: matches.opt_strs("skip"),
         report_time,
+        shuffle,
+        shuffle_seed,
+        test_timeout,
+        isolate,
+        shard,
+        save_baseline,
+        baseline,
+        baseline_dir: PathBuf::from(baseline_dir.unwrap_or_else(|| "bench-baselines".to_string())),
+        regression_threshold: regression_threshold.unwrap_or(5.0),
+        retries,
         options: Options::new().display_output(matches.opt_present("show-output")),
     };
 
     Some(Ok(test_opts))
 }
 
+// Parses the `INDEX/TOTAL` argument of `--shard`.
+fn parse_shard(shard: &str) -> Result<(usize, usize), String> {
+    let err = || {
+        format!(
+            "argument for --shard must be of the form INDEX/TOTAL, with \
+             0 < INDEX <= TOTAL (was {})",
+            shard
+        )
+    };
+    let mut parts = shard.splitn(2, '/');
+    let index = parts.next().and_then(|s| s.parse::<usize>().ok()).ok_or_else(err)?;
+    let total = parts.next().and_then(|s| s.parse::<usize>().ok()).ok_or_else(err)?;
+    if index == 0 || index > total {
+        return Err(err());
+    }
+    Ok((index, total))
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
@@ -722,10 +979,13 @@ pub struct BenchSamples {
 #[derive(Clone, PartialEq)]
 pub enum TestResult {
     TrOk,
+    /// Passed after failing, and being run again, the given number of times.
+    TrOkAfterRetries(usize),
     TrFailed,
     TrFailedMsg(String),
     TrIgnored,
     TrAllowedFail,
+    TrTimedFail,
     TrBench(BenchSamples),
 }
 
@@ -766,12 +1026,16 @@ struct ConsoleTestState {
     log_out: Option<File>,
     total: usize,
     passed: usize,
+    /// Tests that passed after being retried. These are also counted in `passed`.
+    passed_after_retry: usize,
     failed: usize,
     ignored: usize,
     allowed_fail: usize,
     filtered_out: usize,
     measured: usize,
     metrics: MetricMap,
+    benches: baseline::Baseline,
+    bench_regressions: usize,
     failures: Vec<(TestDesc, Vec<u8>)>,
     not_failures: Vec<(TestDesc, Vec<u8>)>,
     options: Options,
@@ -788,12 +1052,15 @@ impl ConsoleTestState {
             log_out,
             total: 0,
             passed: 0,
+            passed_after_retry: 0,
             failed: 0,
             ignored: 0,
             allowed_fail: 0,
             filtered_out: 0,
             measured: 0,
             metrics: MetricMap::new(),
+            benches: baseline::Baseline::new(),
+            bench_regressions: 0,
             failures: Vec::new(),
             not_failures: Vec::new(),
             options: opts.options,
@@ -826,10 +1093,13 @@ impl ConsoleTestState {
             "{} {}",
             match *result {
                 TrOk => "ok".to_owned(),
+                TrOkAfterRetries(1) => "ok (after 1 retry)".to_owned(),
+                TrOkAfterRetries(retries) => format!("ok (after {} retries)", retries),
                 TrFailed => "failed".to_owned(),
                 TrFailedMsg(ref msg) => format!("failed: {}", msg),
                 TrIgnored => "ignored".to_owned(),
                 TrAllowedFail => "failed (allowed)".to_owned(),
+                TrTimedFail => "failed (time limit exceeded)".to_owned(),
                 TrBench(ref bs) => fmt_bench_samples(bs),
             },
             test.name,
@@ -840,6 +1110,14 @@ impl ConsoleTestState {
         self.write_log(|| "\n")
     }
 
+    // The output of successful tests is only kept around when it is going to
+    // be shown at the end of the run.
+    fn push_success(&mut self, test: TestDesc, stdout: Vec<u8>) {
+        if self.options.display_output {
+            self.not_failures.push((test, stdout));
+        }
+    }
+
     fn current_test_count(&self) -> usize {
         self.passed + self.failed + self.ignored + self.measured + self.allowed_fail
     }
@@ -958,9 +1236,9 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         out: &mut dyn OutputFormatter,
     ) -> io::Result<()> {
         match (*event).clone() {
-            TeFiltered(ref filtered_tests) => {
+            TeFiltered(ref filtered_tests, shuffle_seed) => {
                 st.total = filtered_tests.len();
-                out.write_run_start(filtered_tests.len())
+                out.write_run_start(filtered_tests.len(), shuffle_seed)
             }
             TeFilteredOut(filtered_out) => Ok(st.filtered_out = filtered_out),
             TeWait(ref test) => out.write_test_start(test),
@@ -971,7 +1249,12 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                 match result {
                     TrOk => {
                         st.passed += 1;
-                        st.not_failures.push((test, stdout));
+                        st.push_success(test, stdout);
+                    }
+                    TrOkAfterRetries(_) => {
+                        st.passed += 1;
+                        st.passed_after_retry += 1;
+                        st.push_success(test, stdout);
                     }
                     TrIgnored => st.ignored += 1,
                     TrAllowedFail => st.allowed_fail += 1,
@@ -981,6 +1264,10 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                             bs.ns_iter_summ.median,
                             bs.ns_iter_summ.max - bs.ns_iter_summ.min,
                         );
+                        st.benches.insert(
+                            test.name.as_slice().to_owned(),
+                            baseline::BenchStats::from(&bs.ns_iter_summ),
+                        );
                         st.measured += 1
                     }
                     TrFailed => {
@@ -993,6 +1280,12 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         stdout.extend_from_slice(format!("note: {}", msg).as_bytes());
                         st.failures.push((test, stdout));
                     }
+                    TrTimedFail => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(b"note: test exceeded its time limit");
+                        st.failures.push((test, stdout));
+                    }
                 }
                 Ok(())
             }
@@ -1026,8 +1319,30 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             is_multithreaded,
         )),
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
+        OutputFormat::Junit => Box::new(JunitFormatter::new(output, suite_name())),
     };
     let mut st = ConsoleTestState::new(opts)?;
+
+    if !opts.bench_benchmarks && (opts.baseline.is_some() || opts.save_baseline.is_some()) {
+        eprintln!("warning: --baseline and --save-baseline have no effect without --bench");
+    }
+    // Read the baseline before running the benchmarks, so that a missing one
+    // is reported right away.
+    let old_baseline = match opts.baseline {
+        Some(ref name) if opts.bench_benchmarks => {
+            let path = baseline::path(&opts.baseline_dir, name);
+            match baseline::load(&path) {
+                Ok(old) => Some((name, old)),
+                Err(e) => {
+                    let path = path.display();
+                    eprintln!("error: couldn't read benchmark baseline `{}`: {}", path, e);
+                    return Ok(false);
+                }
+            }
+        }
+        _ => None,
+    };
+
     fn len_if_padded(t: &TestDescAndFn) -> usize {
         match t.testfn.padding() {
             PadNone => 0,
@@ -1039,7 +1354,36 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
 
     assert!(st.current_test_count() == st.total);
 
-    return out.write_run_finish(&st);
+    if let Some((name, old)) = old_baseline {
+        let comparisons = baseline::compare(&old, &st.benches, opts.regression_threshold);
+        st.bench_regressions = comparisons.iter().filter(|c| c.exceeds_threshold).count();
+        out.write_bench_comparison(name, &comparisons)?;
+    }
+    let mut saved_baseline = true;
+    if opts.bench_benchmarks {
+        if let Some(ref name) = opts.save_baseline {
+            let path = baseline::path(&opts.baseline_dir, name);
+            if let Err(e) = baseline::save(&path, &st.benches) {
+                eprintln!("error: couldn't save benchmark baseline `{}`: {}", path.display(), e);
+                saved_baseline = false;
+            }
+        }
+    }
+
+    // The run is still summarized when the baseline couldn't be saved, but it
+    // counts as failed.
+    let success = out.write_run_finish(&st)?;
+    Ok(success && saved_baseline)
+}
+
+// The name of the JUnit test suite, taken from the name of the test binary.
+fn suite_name() -> String {
+    env::args()
+        .next()
+        .as_ref()
+        .and_then(|arg0| Path::new(arg0).file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "test".to_owned())
 }
 
 fn use_color(opts: &TestOpts) -> bool {
@@ -1083,7 +1427,7 @@ fn stdout_isatty() -> bool {
 
 #[derive(Clone)]
 pub enum TestEvent {
-    TeFiltered(Vec<TestDesc>),
+    TeFiltered(Vec<TestDesc>, Option<u64>),
     TeWait(TestDesc),
     TeResult(TestDesc, TestResult, Option<TestExecTime>, Vec<u8>),
     TeTimeout(TestDesc),
@@ -1102,7 +1446,7 @@ impl Write for Sink {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RunStrategy {
     /// Runs the test in the current process, and sends the result back over the
     /// supplied channel.
@@ -1118,7 +1462,7 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
 where
     F: FnMut(TestEvent) -> io::Result<()>,
 {
-    use std::collections::{self, HashMap};
+    use std::collections;
     use std::hash::BuildHasherDefault;
     use std::sync::mpsc::RecvTimeoutError;
     // Use a deterministic hasher
@@ -1144,9 +1488,19 @@ where
     let filtered_out = tests_len - filtered_tests.len();
     callback(TeFilteredOut(filtered_out))?;
 
+    let shuffle_seed = if opts.shuffle {
+        Some(opts.shuffle_seed.unwrap_or_else(get_shuffle_seed))
+    } else {
+        None
+    };
+    let filtered_tests = match shuffle_seed {
+        Some(seed) => shuffle_tests(seed, filtered_tests),
+        None => filtered_tests,
+    };
+
     let filtered_descs = filtered_tests.iter().map(|t| t.desc.clone()).collect();
 
-    callback(TeFiltered(filtered_descs))?;
+    callback(TeFiltered(filtered_descs, shuffle_seed))?;
 
     let (filtered_tests, filtered_benchs): (Vec<_>, _) =
         filtered_tests.into_iter().partition(|e| match e.testfn {
@@ -1161,13 +1515,17 @@ where
     let mut pending = 0;
 
     let (tx, rx) = channel::<MonitorMsg>();
-    let run_strategy = if opts.options.panic_abort {
-        RunStrategy::SpawnPrimary
-    } else {
-        RunStrategy::InProcess
-    };
 
+    let mut retries = Retries::new(opts.retries, &remaining);
     let mut running_tests: TestMap = HashMap::default();
+    // Time limits of the in-process tests that are currently running.
+    let mut test_deadlines: TestMap = HashMap::default();
+    // The runs of the in-process tests with time limits that are currently running.
+    let mut timed_runs: HashMap<TestDesc, Arc<Mutex<TimedRun>>> = HashMap::default();
+    // In-process tests that were reported as having exceeded their time limit
+    // after their result was already sent, which has to be dropped. Tests that
+    // are still running keep running in the background without a result.
+    let mut timed_out_tests: Vec<TestDesc> = Vec::new();
 
     fn get_timed_out_tests(running_tests: &mut TestMap) -> Vec<TestDesc> {
         let now = Instant::now();
@@ -1187,8 +1545,8 @@ where
         timed_out
     };
 
-    fn calc_timeout(running_tests: &TestMap) -> Option<Duration> {
-        running_tests.values().min().map(|next_timeout| {
+    fn calc_timeout(running_tests: &TestMap, test_deadlines: &TestMap) -> Option<Duration> {
+        running_tests.values().chain(test_deadlines.values()).min().map(|next_timeout| {
             let now = Instant::now();
             if *next_timeout >= now {
                 *next_timeout - now
@@ -1198,46 +1556,153 @@ where
         })
     };
 
+    // Receives the next test result, dropping the late results of tests that
+    // were already reported as timed out.
+    fn recv_result(
+        rx: &Receiver<MonitorMsg>,
+        timed_out_tests: &mut Vec<TestDesc>,
+        deadline: Option<Instant>,
+    ) -> Result<MonitorMsg, RecvTimeoutError> {
+        loop {
+            let res = match deadline {
+                Some(deadline) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match res {
+                Ok((ref desc, ..)) if timed_out_tests.contains(desc) => {
+                    timed_out_tests.retain(|test| test != desc);
+                }
+                res => return res,
+            }
+        }
+    }
+
     if concurrency == 1 {
         while !remaining.is_empty() {
             let test = remaining.pop().unwrap();
-            callback(TeWait(test.desc.clone()))?;
-            run_test(opts, !opts.run_tests, test, run_strategy, tx.clone(), Concurrent::No);
-            let (test, result, exec_time, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, exec_time, stdout))?;
+            let run_strategy = get_run_strategy(opts, &test);
+            if !retries.is_retry(&test.desc) {
+                callback(TeWait(test.desc.clone()))?;
+            }
+            match deadline_after(test_time_limit(opts, &test.desc, run_strategy)) {
+                Some(deadline) => {
+                    // Run the test on its own thread, so that we can stop waiting
+                    // for it once it runs out of time.
+                    let desc = test.desc.clone();
+                    let run = run_timed_test(opts, test, run_strategy, tx.clone());
+                    match recv_result(&rx, &mut timed_out_tests, Some(deadline)) {
+                        Ok((test, result, exec_time, stdout)) => {
+                            match retries.next_attempt(&test, result) {
+                                Ok(result) => {
+                                    callback(TeResult(test, result, exec_time, stdout))?
+                                }
+                                Err(retry) => remaining.push(retry),
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            if abandon_timed_test(&run) {
+                                timed_out_tests.push(desc.clone());
+                            }
+                            match retries.next_attempt(&desc, TrTimedFail) {
+                                Ok(result) => callback(TeResult(desc, result, None, Vec::new()))?,
+                                Err(retry) => remaining.push(retry),
+                            }
+                        }
+                        Err(e) => panic!("failed to receive test result: {:?}", e),
+                    }
+                }
+                None => {
+                    run_test(opts, !opts.run_tests, test, run_strategy, tx.clone(), Concurrent::No);
+                    let (test, result, exec_time, stdout) =
+                        recv_result(&rx, &mut timed_out_tests, None).unwrap();
+                    match retries.next_attempt(&test, result) {
+                        Ok(result) => callback(TeResult(test, result, exec_time, stdout))?,
+                        Err(retry) => remaining.push(retry),
+                    }
+                }
+            }
         }
     } else {
         while pending > 0 || !remaining.is_empty() {
             while pending < concurrency && !remaining.is_empty() {
                 let test = remaining.pop().unwrap();
+                let run_strategy = get_run_strategy(opts, &test);
                 let timeout = Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S);
                 running_tests.insert(test.desc.clone(), timeout);
-                callback(TeWait(test.desc.clone()))?; //here no pad
-                run_test(opts, !opts.run_tests, test, run_strategy, tx.clone(), Concurrent::Yes);
+                let time_limit = test_time_limit(opts, &test.desc, run_strategy);
+                let deadline = deadline_after(time_limit);
+                if !retries.is_retry(&test.desc) {
+                    callback(TeWait(test.desc.clone()))?; //here no pad
+                }
+                match deadline {
+                    Some(deadline) => {
+                        test_deadlines.insert(test.desc.clone(), deadline);
+                        let desc = test.desc.clone();
+                        let run = run_timed_test(opts, test, run_strategy, tx.clone());
+                        timed_runs.insert(desc, run);
+                    }
+                    None => {
+                        let tx = tx.clone();
+                        run_test(opts, !opts.run_tests, test, run_strategy, tx, Concurrent::Yes);
+                    }
+                }
                 pending += 1;
             }
 
             let mut res;
             loop {
-                if let Some(timeout) = calc_timeout(&running_tests) {
-                    res = rx.recv_timeout(timeout);
+                if let Some(timeout) = calc_timeout(&running_tests, &test_deadlines) {
+                    res = recv_result(
+                        &rx,
+                        &mut timed_out_tests,
+                        Some(Instant::now() + timeout),
+                    );
                     for test in get_timed_out_tests(&mut running_tests) {
                         callback(TeTimeout(test))?;
                     }
-                    if res != Err(RecvTimeoutError::Timeout) {
+                    let hung_tests = get_timed_out_tests(&mut test_deadlines);
+                    let any_hung = !hung_tests.is_empty();
+                    for test in hung_tests {
+                        running_tests.remove(&test);
+                        let run = timed_runs.remove(&test);
+                        if run.map_or(false, |run| abandon_timed_test(&run)) {
+                            timed_out_tests.push(test.clone());
+                        }
+                        pending -= 1;
+                        match retries.next_attempt(&test, TrTimedFail) {
+                            Ok(result) => callback(TeResult(test, result, None, Vec::new()))?,
+                            Err(retry) => remaining.push(retry),
+                        }
+                    }
+                    if any_hung || res != Err(RecvTimeoutError::Timeout) {
                         break;
                     }
                 } else {
-                    res = rx.recv().map_err(|_| RecvTimeoutError::Disconnected);
+                    res = recv_result(&rx, &mut timed_out_tests, None);
                     break;
                 }
             }
 
-            let (desc, result, exec_time, stdout) = res.unwrap();
+            let (desc, result, exec_time, stdout) = match res {
+                Err(RecvTimeoutError::Timeout) => continue,
+                res => res.unwrap(),
+            };
+            if timed_out_tests.contains(&desc) {
+                // The test finished right as it was reported as timed out.
+                timed_out_tests.retain(|test| *test != desc);
+                continue;
+            }
             running_tests.remove(&desc);
-
-            callback(TeResult(desc, result, exec_time, stdout))?;
+            test_deadlines.remove(&desc);
+            timed_runs.remove(&desc);
             pending -= 1;
+
+            match retries.next_attempt(&desc, result) {
+                Ok(result) => callback(TeResult(desc, result, exec_time, stdout))?,
+                Err(retry) => remaining.push(retry),
+            }
         }
     }
 
@@ -1245,14 +1710,79 @@ where
         // All benchmarks run at the end, in serial.
         for b in filtered_benchs {
             callback(TeWait(b.desc.clone()))?;
+            let run_strategy = get_run_strategy(opts, &b);
             run_test(opts, false, b, run_strategy, tx.clone(), Concurrent::No);
-            let (test, result, exec_time, stdout) = rx.recv().unwrap();
+            let (test, result, exec_time, stdout) =
+                recv_result(&rx, &mut timed_out_tests, None).unwrap();
             callback(TeResult(test, result, exec_time, stdout))?;
         }
     }
     Ok(())
 }
 
+/// Keeps track of the tests that are run again after failing, for `--retries`.
+struct Retries {
+    max_retries: usize,
+    /// The static test functions, which can be run more than once.
+    test_fns: HashMap<TestDesc, fn()>,
+    /// The number of times each failed test was retried so far.
+    attempts: HashMap<TestDesc, usize>,
+}
+
+impl Retries {
+    fn new(max_retries: usize, tests: &[TestDescAndFn]) -> Retries {
+        let test_fns = if max_retries > 0 {
+            tests
+                .iter()
+                .filter_map(|test| match test.testfn {
+                    StaticTestFn(f) => Some((test.desc.clone(), f)),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Retries { max_retries, test_fns, attempts: HashMap::new() }
+    }
+
+    /// Whether `desc` already failed and is being run again.
+    fn is_retry(&self, desc: &TestDesc) -> bool {
+        self.attempts.contains_key(desc)
+    }
+
+    /// Returns the result to report for `desc`, or the test to run again if
+    /// it failed and has retries left.
+    fn next_attempt(
+        &mut self,
+        desc: &TestDesc,
+        result: TestResult,
+    ) -> Result<TestResult, TestDescAndFn> {
+        let attempts = self.attempts.get(desc).cloned().unwrap_or(0);
+        match result {
+            TrFailed | TrFailedMsg(_) | TrTimedFail if attempts < self.max_retries => {
+                if let Some(&f) = self.test_fns.get(desc) {
+                    self.attempts.insert(desc.clone(), attempts + 1);
+                    return Err(TestDescAndFn { desc: desc.clone(), testfn: StaticTestFn(f) });
+                }
+                Ok(result)
+            }
+            TrOk if attempts > 0 => Ok(TrOkAfterRetries(attempts)),
+            result => Ok(result),
+        }
+    }
+}
+
+// Tests are run in a subprocess when the test binary is built with
+// panic=abort, or with `--isolate`. Dynamic tests can't be looked up by name
+// in the subprocess, so `--isolate` leaves them in the current process.
+fn get_run_strategy(opts: &TestOpts, test: &TestDescAndFn) -> RunStrategy {
+    match test.testfn {
+        _ if opts.options.panic_abort => RunStrategy::SpawnPrimary,
+        StaticTestFn(_) if opts.isolate => RunStrategy::SpawnPrimary,
+        _ => RunStrategy::InProcess,
+    }
+}
+
 #[allow(deprecated)]
 fn get_concurrency() -> usize {
     return match env::var("RUST_TEST_THREADS") {
@@ -1440,12 +1970,62 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         RunIgnored::No => {}
     }
 
+    // Only keep the tests of the requested shard
+    if let Some((index, total)) = opts.shard {
+        filtered.retain(|test| shard_of(test.desc.name.as_slice(), total) == index - 1);
+    }
+
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
     filtered
 }
 
+// Picks a seed for `--shuffle` when none was given with `--shuffle-seed`.
+fn get_shuffle_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs().wrapping_mul(1_000_000_000).wrapping_add(d.subsec_nanos() as u64))
+        .unwrap_or(0)
+}
+
+/// Reorders `tests` pseudo-randomly. The order only depends on `seed` and on
+/// the (sorted) list of tests, so a failing order can be replayed by passing
+/// the same seed to `--shuffle-seed`.
+pub fn shuffle_tests(seed: u64, tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
+    let mut tests = tests;
+    // Fisher-Yates shuffle, drawing random numbers from SplitMix64. Unlike
+    // the std hashers, its output is fully specified, so the order for a seed
+    // is the same on every platform and with every version of libtest.
+    let mut state = seed;
+    for i in (1..tests.len()).rev() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        let j = (z % (i as u64 + 1)) as usize;
+        tests.swap(i, j);
+    }
+    tests
+}
+
+// The shard (counting from 0) that the test `name` belongs to, out of `total`.
+// This only depends on the name of the test, so that each test ends up in
+// the same shard regardless of the other tests and filters, and on every
+// platform.
+fn shard_of(name: &str, total: usize) -> usize {
+    // 64-bit FNV-1a
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in name.as_bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    (hash % total as u64) as usize
+}
+
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     // convert benchmarks to tests, if we're not benchmarking them
     tests
@@ -1468,6 +2048,67 @@ pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAnd
         .collect()
 }
 
+// The time limit after which `desc` is reported as failed, if any.
+fn time_limit(opts: &TestOpts, desc: &TestDesc) -> Option<Duration> {
+    desc.timeout.map(Duration::from_secs).or(opts.test_timeout)
+}
+
+// The time limit the test runner has to enforce for `desc`. Tests run in a
+// subprocess enforce their own time limit instead, by killing the subprocess.
+fn test_time_limit(opts: &TestOpts, desc: &TestDesc, strategy: RunStrategy) -> Option<Duration> {
+    match strategy {
+        RunStrategy::InProcess => time_limit(opts, desc),
+        RunStrategy::SpawnPrimary => None,
+    }
+}
+
+// The time at which a test that starts now runs out of `time_limit`. A limit
+// too far in the future to be represented is the same as no limit.
+fn deadline_after(time_limit: Option<Duration>) -> Option<Instant> {
+    time_limit.and_then(|time_limit| Instant::now().checked_add(time_limit))
+}
+
+#[derive(Default)]
+struct TimedRun {
+    /// Set once the test ran out of time and was reported as timed out.
+    abandoned: bool,
+    /// Set once the result of the test was passed on to the test runner.
+    reported: bool,
+}
+
+// Runs a test that has a time limit on its own thread. Its result is passed on
+// to `monitor_ch` unless the test is abandoned first, so that the late result
+// of a test that ran out of time can't be mistaken for the result of its retry.
+fn run_timed_test(
+    opts: &TestOpts,
+    test: TestDescAndFn,
+    strategy: RunStrategy,
+    monitor_ch: Sender<MonitorMsg>,
+) -> Arc<Mutex<TimedRun>> {
+    let run = Arc::new(Mutex::new(TimedRun::default()));
+    let (tx, rx) = channel();
+    run_test(opts, !opts.run_tests, test, strategy, tx, Concurrent::Yes);
+    let forward_run = run.clone();
+    thread::spawn(move || {
+        if let Ok(msg) = rx.recv() {
+            let mut run = forward_run.lock().unwrap();
+            if !run.abandoned {
+                run.reported = true;
+                let _ = monitor_ch.send(msg);
+            }
+        }
+    });
+    run
+}
+
+// Abandons a test that ran out of time. Returns whether its result was passed
+// on before that, in which case the test runner has to drop it.
+fn abandon_timed_test(run: &Mutex<TimedRun>) -> bool {
+    let mut run = run.lock().unwrap();
+    run.abandoned = true;
+    run.reported
+}
+
 pub fn run_test(
     opts: &TestOpts,
     force_ignore: bool,
@@ -1476,6 +2117,7 @@ pub fn run_test(
     monitor_ch: Sender<MonitorMsg>,
     concurrency: Concurrent,
 ) {
+    let time_limit = time_limit(opts, &test.desc);
     let TestDescAndFn { desc, testfn } = test;
 
     let ignore_because_no_process_support = cfg!(target_arch = "wasm32")
@@ -1491,6 +2133,7 @@ pub fn run_test(
         desc: TestDesc,
         nocapture: bool,
         report_time: bool,
+        time_limit: Option<Duration>,
         strategy: RunStrategy,
         monitor_ch: Sender<MonitorMsg>,
         testfn: Box<dyn FnOnce() + Send>,
@@ -1502,7 +2145,8 @@ pub fn run_test(
             match strategy {
                 RunStrategy::InProcess =>
                     run_test_in_process(desc, nocapture, report_time, testfn, monitor_ch),
-                RunStrategy::SpawnPrimary => spawn_test_subprocess(desc, report_time, monitor_ch),
+                RunStrategy::SpawnPrimary =>
+                    spawn_test_subprocess(desc, report_time, time_limit, monitor_ch),
             }
         };
 
@@ -1540,6 +2184,7 @@ pub fn run_test(
                 desc,
                 opts.nocapture,
                 opts.report_time,
+                time_limit,
                 strategy,
                 monitor_ch,
                 Box::new(move || __rust_begin_short_backtrace(f)),
@@ -1550,6 +2195,7 @@ pub fn run_test(
             desc,
             opts.nocapture,
             opts.report_time,
+            time_limit,
             strategy,
             monitor_ch,
             Box::new(move || __rust_begin_short_backtrace(f)),
@@ -1642,7 +2288,12 @@ fn run_test_in_process(desc: TestDesc,
     monitor_ch.send((desc.clone(), test_result, exec_time, stdout)).unwrap();
 }
 
-fn spawn_test_subprocess(desc: TestDesc, report_time: bool, monitor_ch: Sender<MonitorMsg>) {
+fn spawn_test_subprocess(
+    desc: TestDesc,
+    report_time: bool,
+    time_limit: Option<Duration>,
+    monitor_ch: Sender<MonitorMsg>,
+) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
         let current_exe = &args[0];
@@ -1652,15 +2303,24 @@ fn spawn_test_subprocess(desc: TestDesc, report_time: bool, monitor_ch: Sender<M
         } else {
             None
         };
-        let output = match Command::new(current_exe)
+        let child = match Command::new(current_exe)
             .env(SECONDARY_TEST_INVOKER_VAR, desc.name.as_slice())
-            .output() {
-                Ok(out) => out,
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn() {
+                Ok(child) => child,
                 Err(e) => {
                     let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
                     return (TrFailed, err.into_bytes(), None);
                 }
             };
+        let (output, timed_out) = match wait_with_time_limit(child, time_limit) {
+            Ok(out) => out,
+            Err(e) => {
+                let err = format!("Failed to wait for child of test: {:?}", e);
+                return (TrFailed, err.into_bytes(), None);
+            }
+        };
         let exec_time = start.map(|start| {
             let duration = start.elapsed();
             TestExecTime(duration)
@@ -1671,6 +2331,10 @@ fn spawn_test_subprocess(desc: TestDesc, report_time: bool, monitor_ch: Sender<M
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
 
+        if timed_out {
+            return (TrTimedFail, test_output, exec_time);
+        }
+
         let result = match (|| -> Result<TestResult, String> {
             let exit_code = get_exit_code(status)?;
             Ok(get_result_from_exit_code(&desc, exit_code))
@@ -1688,35 +2352,113 @@ fn spawn_test_subprocess(desc: TestDesc, report_time: bool, monitor_ch: Sender<M
     monitor_ch.send((desc.clone(), result, exec_time, test_output)).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, testfn: Box<dyn FnOnce() + Send>) -> ! {
-    let builtin_panic_hook = panic::take_hook();
-    let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
-        let test_result = match panic_info {
-            Some(info) => calc_result(&desc, Err(info.payload())),
-            None => calc_result(&desc, Ok(())),
-        };
+// Waits for `child` to exit and collects its output, like `wait_with_output`,
+// but kills the child once `time_limit` has passed. The returned flag tells
+// whether the child had to be killed.
+fn wait_with_time_limit(
+    mut child: Child,
+    time_limit: Option<Duration>,
+) -> io::Result<(std::process::Output, bool)> {
+    let deadline = match deadline_after(time_limit) {
+        Some(deadline) => deadline,
+        None => return child.wait_with_output().map(|output| (output, false)),
+    };
 
+    // Drain the pipes on separate threads, so that the child cannot block on
+    // a full pipe while we are waiting for it.
+    fn read_all<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<Vec<u8>> {
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            let _ = tx.send(buf);
+        });
+        rx
+    }
+    let stdout = read_all(child.stdout.take());
+    let stderr = read_all(child.stderr.take());
+
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // The child may have exited in the meantime, in which case
+            // killing it fails and `wait` still returns its status.
+            let _ = child.kill();
+            timed_out = true;
+            break child.wait()?;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    // Processes spawned by a killed child may still hold on to its pipes, so
+    // only wait a little while for the rest of its output.
+    let collect = |rx: Receiver<Vec<u8>>| if timed_out {
+        rx.recv_timeout(Duration::from_secs(1)).unwrap_or_default()
+    } else {
+        rx.recv().unwrap_or_default()
+    };
+    let output = std::process::Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    };
+    Ok((output, timed_out))
+}
+
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    testfn: Box<dyn FnOnce() + Send>,
+    panic_abort: bool,
+) -> ! {
+    fn exit_with_result(test_result: TestResult) -> ! {
         // We don't support serializing TrFailedMsg, so just
         // print the message out to stderr.
         if let TrFailedMsg(msg) = &test_result {
             eprintln!("{}", msg);
         }
 
-        if let Some(info) = panic_info {
-            builtin_panic_hook(info);
-        }
-
         if let TrOk = test_result {
             process::exit(TR_OK);
         } else {
             process::exit(TR_FAILED);
         }
+    }
+
+    if !panic_abort {
+        // With `--isolate` and panic=unwind, a test may catch panics on
+        // purpose, so its result is whether it unwinds, as when it's run in
+        // the test runner's process.
+        let test_result = match catch_unwind(AssertUnwindSafe(testfn)) {
+            Ok(()) => calc_result(&desc, Ok(())),
+            Err(e) => calc_result(&desc, Err(e.as_ref())),
+        };
+        exit_with_result(test_result);
+    }
+
+    // With panic=abort, the first panic ends the test.
+    let builtin_panic_hook = panic::take_hook();
+    let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
+        let test_result = match panic_info {
+            Some(info) => calc_result(&desc, Err(info.payload())),
+            None => calc_result(&desc, Ok(())),
+        };
+
+        if let Some(info) = panic_info {
+            builtin_panic_hook(info);
+        }
+
+        exit_with_result(test_result);
     });
     let record_result2 = record_result.clone();
     panic::set_hook(Box::new(move |info| record_result2(Some(&info))));
     testfn();
     record_result(None);
-    unreachable!("panic=abort callback should have exited the process")
+    unreachable!("the test result callback should have exited the process")
 }
 
 #[cfg(not(unix))]
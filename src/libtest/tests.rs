@@ -24,6 +24,16 @@ impl TestOpts {
             test_threads: None,
             skip: vec![],
             report_time: false,
+            shuffle: false,
+            shuffle_seed: None,
+            test_timeout: None,
+            isolate: false,
+            shard: None,
+            save_baseline: None,
+            baseline: None,
+            baseline_dir: PathBuf::from("bench-baselines"),
+            regression_threshold: 5.0,
+            retries: 0,
             options: Options::new(),
         }
     }
@@ -37,6 +47,7 @@ fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move || {})),
         },
@@ -46,6 +57,7 @@ fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
                 ignore: false,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move || {})),
         },
@@ -63,6 +75,7 @@ pub fn do_not_run_ignored_tests() {
             ignore: true,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -81,6 +94,7 @@ pub fn ignored_tests_result_in_ignored() {
             ignore: true,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -101,6 +115,7 @@ fn test_should_panic() {
             ignore: false,
             should_panic: ShouldPanic::Yes,
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -121,6 +136,7 @@ fn test_should_panic_good_message() {
             ignore: false,
             should_panic: ShouldPanic::YesWithMessage("error message"),
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -143,6 +159,7 @@ fn test_should_panic_bad_message() {
             ignore: false,
             should_panic: ShouldPanic::YesWithMessage(expected),
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -161,6 +178,7 @@ fn test_should_panic_but_succeeds() {
             ignore: false,
             should_panic: ShouldPanic::Yes,
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -170,6 +188,187 @@ fn test_should_panic_but_succeeds() {
     assert!(res == TrFailedMsg("test did not panic as expected".to_string()));
 }
 
+#[test]
+fn test_time_limit_fails_hung_test() {
+    fn tests() -> Vec<TestDescAndFn> {
+        vec![
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName("hangs"),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: Some(1),
+                },
+                testfn: DynTestFn(Box::new(|| thread::sleep(Duration::from_secs(60)))),
+            },
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName("passes"),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(|| {})),
+            },
+        ]
+    }
+
+    for &test_threads in &[1, 2] {
+        let opts = TestOpts {
+            run_tests: true,
+            test_threads: Some(test_threads),
+            test_timeout: Some(Duration::from_secs(30)),
+            ..TestOpts::new()
+        };
+        let mut results = Vec::new();
+        run_tests(&opts, tests(), |event| {
+            if let TeResult(desc, result, ..) = event {
+                results.push((desc.name.to_string(), result));
+            }
+            Ok(())
+        }).unwrap();
+
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(results.len(), 2);
+        assert!(results[0] == ("hangs".to_string(), TrTimedFail));
+        assert!(results[1] == ("passes".to_string(), TrOk));
+    }
+}
+
+#[test]
+fn test_huge_time_limit_is_no_limit() {
+    fn tests() -> Vec<TestDescAndFn> {
+        vec![TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("passes"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                timeout: Some(u64::max_value()),
+            },
+            testfn: DynTestFn(Box::new(|| {})),
+        }]
+    }
+
+    for &test_threads in &[1, 2] {
+        let opts = TestOpts {
+            run_tests: true,
+            test_threads: Some(test_threads),
+            test_timeout: Some(Duration::from_secs(u64::max_value())),
+            ..TestOpts::new()
+        };
+        let mut results = Vec::new();
+        run_tests(&opts, tests(), |event| {
+            if let TeResult(_, result, ..) = event {
+                results.push(result);
+            }
+            Ok(())
+        }).unwrap();
+        assert!(results == [TrOk]);
+    }
+}
+
+#[test]
+fn test_retries_report_flaky_tests() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static FLAKY_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn flaky() {
+        // Fails the first two times it is run.
+        assert!(FLAKY_RUNS.fetch_add(1, Ordering::SeqCst) >= 2);
+    }
+    fn broken() {
+        panic!();
+    }
+    fn tests() -> Vec<TestDescAndFn> {
+        vec![("broken", broken as fn()), ("flaky", flaky as fn())]
+            .into_iter()
+            .map(|(name, f)| TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName(name),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: None,
+                },
+                testfn: StaticTestFn(f),
+            })
+            .collect()
+    }
+
+    for &test_threads in &[1, 2] {
+        FLAKY_RUNS.store(0, Ordering::SeqCst);
+        let opts = TestOpts {
+            run_tests: true,
+            test_threads: Some(test_threads),
+            retries: 3,
+            ..TestOpts::new()
+        };
+        let mut waits = 0;
+        let mut results = Vec::new();
+        run_tests(&opts, tests(), |event| {
+            match event {
+                TeWait(_) => waits += 1,
+                TeResult(desc, result, ..) => results.push((desc.name.to_string(), result)),
+                _ => {}
+            }
+            Ok(())
+        }).unwrap();
+
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(waits, 2);
+        assert_eq!(results.len(), 2);
+        assert!(results[0] == ("broken".to_string(), TrFailed));
+        assert!(results[1] == ("flaky".to_string(), TrOkAfterRetries(2)));
+    }
+}
+
+#[test]
+fn test_retries_rerun_timed_out_tests() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static SLOW_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn slow() {
+        // Runs out of time the first time it is run, and finishes after its
+        // retry did.
+        if SLOW_RUNS.fetch_add(1, Ordering::SeqCst) == 0 {
+            thread::sleep(Duration::from_secs(3));
+        }
+    }
+    fn tests() -> Vec<TestDescAndFn> {
+        vec![TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("slow"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                timeout: Some(1),
+            },
+            testfn: StaticTestFn(slow),
+        }]
+    }
+
+    for &test_threads in &[1, 2] {
+        SLOW_RUNS.store(0, Ordering::SeqCst);
+        let opts = TestOpts {
+            run_tests: true,
+            test_threads: Some(test_threads),
+            retries: 1,
+            ..TestOpts::new()
+        };
+        let mut results = Vec::new();
+        run_tests(&opts, tests(), |event| {
+            if let TeResult(_, result, ..) = event {
+                results.push(result);
+            }
+            Ok(())
+        }).unwrap();
+        assert!(results == [TrOkAfterRetries(1)]);
+    }
+}
+
 fn report_time_test_template(report_time: bool) -> Option<TestExecTime> {
     fn f() {}
     let desc = TestDescAndFn {
@@ -178,6 +377,7 @@ fn report_time_test_template(report_time: bool) -> Option<TestExecTime> {
             ignore: false,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -225,6 +425,95 @@ fn parse_show_output_flag() {
     assert!(opts.options.display_output);
 }
 
+#[test]
+fn parse_junit_format_requires_unstable_options() {
+    let args = vec![
+        "progname".to_string(),
+        "--format=junit".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--format=junit".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.format, OutputFormat::Junit);
+}
+
+#[test]
+fn parse_shuffle_seed_implies_shuffle() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--shuffle-seed=42".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert!(opts.shuffle);
+    assert_eq!(opts.shuffle_seed, Some(42));
+
+    let args = vec![
+        "progname".to_string(),
+        "--shuffle".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_test_timeout_flag() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--test-timeout=10".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.test_timeout, Some(Duration::from_secs(10)));
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--test-timeout=0".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn isolate_runs_static_tests_in_subprocesses() {
+    fn f() {}
+    let static_test = TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName("static"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            timeout: None,
+        },
+        testfn: StaticTestFn(f),
+    };
+    let dyn_test = TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName("dynamic"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            timeout: None,
+        },
+        testfn: DynTestFn(Box::new(f)),
+    };
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--isolate".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert!(opts.isolate);
+    assert_eq!(get_run_strategy(&opts, &static_test), RunStrategy::SpawnPrimary);
+    assert_eq!(get_run_strategy(&opts, &dyn_test), RunStrategy::InProcess);
+    assert_eq!(get_run_strategy(&TestOpts::new(), &static_test), RunStrategy::InProcess);
+}
+
 #[test]
 fn parse_include_ignored_flag() {
     let args = vec![
@@ -284,6 +573,7 @@ pub fn exclude_should_panic_option() {
             ignore: false,
             should_panic: ShouldPanic::Yes,
             allow_fail: false,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(move || {})),
     });
@@ -305,6 +595,7 @@ pub fn exact_filter_match() {
                     ignore: false,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move || {})),
             })
@@ -388,6 +679,80 @@ pub fn exact_filter_match() {
     assert_eq!(exact.len(), 1);
 }
 
+#[test]
+pub fn shard_filter_partitions_tests() {
+    fn tests() -> Vec<TestDescAndFn> {
+        (0..100)
+            .map(|i| TestDescAndFn {
+                desc: TestDesc {
+                    name: DynTestName(format!("base::test{}", i)),
+                    ignore: i % 10 == 0,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move || {})),
+            })
+            .collect()
+    }
+    fn names(tests: Vec<TestDescAndFn>) -> Vec<String> {
+        tests.into_iter().map(|t| t.desc.name.to_string()).collect()
+    }
+
+    // Every test ends up in exactly one shard.
+    let mut all = Vec::new();
+    for index in 1..=3 {
+        let shard = names(filter_tests(
+            &TestOpts { shard: Some((index, 3)), ..TestOpts::new() },
+            tests(),
+        ));
+        assert!(!shard.is_empty());
+        all.extend(shard);
+    }
+    all.sort();
+    assert_eq!(all, names(filter_tests(&TestOpts::new(), tests())));
+
+    // Sharding composes with the other filters: a test that is kept by a
+    // filter stays in the same shard.
+    let shard = names(filter_tests(&TestOpts { shard: Some((2, 3)), ..TestOpts::new() }, tests()));
+    let ignored_shard = names(filter_tests(
+        &TestOpts { shard: Some((2, 3)), run_ignored: RunIgnored::Only, ..TestOpts::new() },
+        tests(),
+    ));
+    assert!(!ignored_shard.is_empty());
+    assert!(ignored_shard.iter().all(|name| shard.contains(name)));
+
+    let skipped_shard = names(filter_tests(
+        &TestOpts {
+            shard: Some((2, 3)),
+            skip: vec!["base::test1".to_string()],
+            ..TestOpts::new()
+        },
+        tests(),
+    ));
+    assert!(skipped_shard.iter().all(|name| shard.contains(name) && !name.contains("test1")));
+}
+
+#[test]
+fn parse_shard_flag() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--shard=2/3".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.shard, Some((2, 3)));
+
+    for bad in &["0/3", "4/3", "1", "a/3", "1/b"] {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            format!("--shard={}", bad),
+        ];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+}
+
 #[test]
 pub fn sort_tests() {
     let mut opts = TestOpts::new();
@@ -416,6 +781,7 @@ pub fn sort_tests() {
                     ignore: false,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(testfn)),
             };
@@ -444,6 +810,47 @@ pub fn sort_tests() {
     }
 }
 
+#[test]
+pub fn shuffle_tests_is_reproducible() {
+    fn tests() -> Vec<TestDescAndFn> {
+        (0..20)
+            .map(|i| TestDescAndFn {
+                desc: TestDesc {
+                    name: DynTestName(format!("test{:02}", i)),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move || {})),
+            })
+            .collect()
+    }
+    fn names(tests: Vec<TestDescAndFn>) -> Vec<String> {
+        tests.into_iter().map(|t| t.desc.name.to_string()).collect()
+    }
+
+    let sorted = names(tests());
+    let first = names(shuffle_tests(1234, tests()));
+    let second = names(shuffle_tests(1234, tests()));
+    let other_seed = names(shuffle_tests(4321, tests()));
+
+    assert_eq!(first, second);
+    assert_ne!(first, sorted);
+    assert_ne!(first, other_seed);
+
+    let mut resorted = first.clone();
+    resorted.sort();
+    assert_eq!(resorted, sorted);
+
+    // The order for a seed must not change, or printed seeds couldn't be replayed.
+    let five = tests().into_iter().take(5).collect();
+    assert_eq!(
+        names(shuffle_tests(1234, five)),
+        ["test03", "test02", "test01", "test04", "test00"],
+    );
+}
+
 #[test]
 pub fn test_metricmap_compare() {
     let mut m1 = MetricMap::new();
@@ -492,6 +899,7 @@ pub fn test_bench_no_iter() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
     };
 
     crate::bench::benchmark(desc, tx, true, f);
@@ -511,6 +919,7 @@ pub fn test_bench_iter() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
     };
 
     crate::bench::benchmark(desc, tx, true, f);
@@ -524,6 +933,7 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
     };
 
     let test_b = TestDesc {
@@ -531,6 +941,7 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
     };
 
     let mut out = PrettyFormatter::new(Raw(Vec::new()), false, 10, false);
@@ -539,12 +950,15 @@ fn should_sort_failures_before_printing_them() {
         log_out: None,
         total: 0,
         passed: 0,
+        passed_after_retry: 0,
         failed: 0,
         ignored: 0,
         allowed_fail: 0,
         filtered_out: 0,
         measured: 0,
         metrics: MetricMap::new(),
+        benches: baseline::Baseline::new(),
+        bench_regressions: 0,
         failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
         options: Options::new(),
         not_failures: Vec::new(),
@@ -560,3 +974,237 @@ fn should_sort_failures_before_printing_them() {
     let bpos = s.find("b").unwrap();
     assert!(apos < bpos);
 }
+
+#[test]
+fn junit_formatter_reports_failures_and_escapes_output() {
+    let passing = TestDesc {
+        name: StaticTestName("module::passing"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        timeout: None,
+    };
+
+    let failing = TestDesc {
+        name: StaticTestName("failing"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        timeout: None,
+    };
+
+    let mut out = JunitFormatter::new(Raw(Vec::new()), "suite".to_string());
+
+    let st = ConsoleTestState {
+        log_out: None,
+        total: 2,
+        passed: 1,
+        passed_after_retry: 0,
+        failed: 1,
+        ignored: 0,
+        allowed_fail: 0,
+        filtered_out: 0,
+        measured: 0,
+        metrics: MetricMap::new(),
+        benches: baseline::Baseline::new(),
+        bench_regressions: 0,
+        failures: Vec::new(),
+        options: Options::new(),
+        not_failures: Vec::new(),
+    };
+
+    let exec_time = TestExecTime(Duration::from_millis(1500));
+    out.write_result(&passing, &TrOk, Some(&exec_time), b"", &st).unwrap();
+    out.write_result(&failing, &TrFailedMsg("a < b".to_string()), None, b"<out>", &st).unwrap();
+    assert!(!out.write_run_finish(&st).unwrap());
+
+    let s = match out.output_location() {
+        &Raw(ref m) => String::from_utf8_lossy(&m[..]),
+        &Pretty(_) => unreachable!(),
+    };
+
+    assert!(s.contains(r#"<testsuite name="suite" package="suite" id="0" errors="0" failures="1" tests="2" skipped="0" time="1.500">"#));
+    assert!(s.contains(r#"<testcase classname="module" name="passing" time="1.500"/>"#));
+    assert!(s.contains(
+        r#"<testcase classname="suite" name="failing" time="0.000"><failure type="assert" message="a &lt; b"/><system-out>&lt;out&gt;</system-out></testcase>"#
+    ));
+}
+
+#[test]
+fn junit_formatter_reports_allowed_failures_as_skipped() {
+    let desc = TestDesc {
+        name: StaticTestName("flaky"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: true,
+        timeout: None,
+    };
+
+    let mut out = JunitFormatter::new(Raw(Vec::new()), "suite".to_string());
+
+    let st = ConsoleTestState {
+        log_out: None,
+        total: 1,
+        passed: 0,
+        passed_after_retry: 0,
+        failed: 0,
+        ignored: 0,
+        allowed_fail: 1,
+        filtered_out: 0,
+        measured: 0,
+        metrics: MetricMap::new(),
+        benches: baseline::Baseline::new(),
+        bench_regressions: 0,
+        failures: Vec::new(),
+        options: Options::new(),
+        not_failures: Vec::new(),
+    };
+
+    out.write_result(&desc, &TrAllowedFail, None, b"", &st).unwrap();
+    assert!(out.write_run_finish(&st).unwrap());
+
+    let s = match out.output_location() {
+        &Raw(ref m) => String::from_utf8_lossy(&m[..]),
+        &Pretty(_) => unreachable!(),
+    };
+
+    assert!(s.contains(r#"failures="0" tests="1" skipped="1""#));
+    assert!(s.contains(
+        r#"<testcase classname="suite" name="flaky" time="0.000"><skipped message="failed (allowed)"/></testcase>"#
+    ));
+}
+
+#[test]
+#[cfg(unix)]
+fn wait_with_time_limit_kills_hung_child() {
+    let child = Command::new("sleep")
+        .arg("60")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let start = Instant::now();
+    let (output, timed_out) = wait_with_time_limit(child, Some(Duration::from_secs(1))).unwrap();
+    assert!(timed_out);
+    assert!(!output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
+
+#[test]
+fn bench_baseline_round_trip() {
+    let mut saved = baseline::Baseline::new();
+    saved.insert(
+        "bench with spaces".to_string(),
+        baseline::BenchStats { median: 1234.5, median_abs_dev: 10.25 },
+    );
+    saved.insert("b".to_string(), baseline::BenchStats { median: 0.5, median_abs_dev: 0.0 });
+
+    let dir = env::temp_dir().join(format!("libtest-baseline-{}", process::id()));
+    let path = baseline::path(&dir, "main");
+    baseline::save(&path, &saved).unwrap();
+    let loaded = baseline::load(&path);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(loaded.unwrap(), saved);
+}
+
+#[test]
+fn parse_baseline_names() {
+    for name in &["main", "before-refactor", "v1.2"] {
+        assert!(baseline::is_valid_name(name), "{}", name);
+    }
+    for name in &["", ".", "..", "../x", "a/b", "/tmp/x", "a/"] {
+        assert!(!baseline::is_valid_name(name), "{}", name);
+    }
+
+    let args = vec![
+        "progname".to_string(),
+        "--bench".to_string(),
+        "-Zunstable-options".to_string(),
+        "--baseline=../x".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn bench_baseline_comparison() {
+    fn stats(median: f64, median_abs_dev: f64) -> baseline::BenchStats {
+        baseline::BenchStats { median, median_abs_dev }
+    }
+    let mut old = baseline::Baseline::new();
+    old.insert("noise".to_string(), stats(1000.0, 50.0));
+    old.insert("slower".to_string(), stats(1000.0, 10.0));
+    old.insert("slightly_slower".to_string(), stats(1000.0, 10.0));
+    old.insert("faster".to_string(), stats(1000.0, 10.0));
+    old.insert("removed".to_string(), stats(1000.0, 10.0));
+
+    let mut new = baseline::Baseline::new();
+    new.insert("noise".to_string(), stats(1080.0, 50.0));
+    new.insert("slower".to_string(), stats(1200.0, 10.0));
+    new.insert("slightly_slower".to_string(), stats(1030.0, 5.0));
+    new.insert("faster".to_string(), stats(500.0, 10.0));
+    new.insert("added".to_string(), stats(1000.0, 10.0));
+
+    let comparisons = baseline::compare(&old, &new, 5.0);
+    let verdicts: Vec<_> = comparisons
+        .iter()
+        .map(|c| (&*c.name, c.verdict, c.exceeds_threshold))
+        .collect();
+    assert_eq!(
+        verdicts,
+        vec![
+            ("faster", baseline::Verdict::Improved, false),
+            ("noise", baseline::Verdict::NoChange, false),
+            ("slightly_slower", baseline::Verdict::Regressed, false),
+            ("slower", baseline::Verdict::Regressed, true),
+        ]
+    );
+    assert_eq!(comparisons[3].change_pct, 20.0);
+}
+
+#[test]
+fn junit_formatter_shows_output_of_successful_tests() {
+    fn desc(name: &'static str) -> TestDesc {
+        TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            timeout: None,
+        }
+    }
+
+    for &display_output in &[false, true] {
+        let mut out = JunitFormatter::new(Raw(Vec::new()), "suite".to_string());
+        let st = ConsoleTestState {
+            log_out: None,
+            total: 2,
+            passed: 1,
+            passed_after_retry: 0,
+            failed: 1,
+            ignored: 0,
+            allowed_fail: 0,
+            filtered_out: 0,
+            measured: 0,
+            metrics: MetricMap::new(),
+            benches: baseline::Baseline::new(),
+            bench_regressions: 0,
+            failures: Vec::new(),
+            options: Options::new().display_output(display_output),
+            not_failures: Vec::new(),
+        };
+
+        out.write_result(&desc("passing"), &TrOk, None, b"hello", &st).unwrap();
+        out.write_result(&desc("failing"), &TrFailed, None, b"oops", &st).unwrap();
+        out.write_run_finish(&st).unwrap();
+        let s = match out.output_location() {
+            &Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+            &Pretty(_) => unreachable!(),
+        };
+
+        // The output of failures is always reported, and the output of
+        // successes only with `--show-output`.
+        assert!(s.contains("<failure type=\"assert\"/><system-out>oops</system-out>"));
+        assert_eq!(s.contains("<system-out>hello</system-out>"), display_output);
+    }
+}
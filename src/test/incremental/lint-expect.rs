@@ -0,0 +1,30 @@
+// Checks that an `#[expect]` is still fulfilled when the lint that fulfills it was emitted by a
+// query whose result is reused in the next session.
+
+// revisions: cfail1 cfail2
+// build-pass (FIXME(62277): could be check-pass?)
+
+#![feature(lint_reasons)]
+#![deny(unfulfilled_lint_expectations)]
+
+mod unchanged {
+    #[expect(unused_variables)]
+    pub fn unused() {
+        let x = 5;
+    }
+}
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 {
+    1
+}
+
+#[cfg(cfail2)]
+pub fn changed() -> u32 {
+    2
+}
+
+fn main() {
+    unchanged::unused();
+    changed();
+}
@@ -0,0 +1,7 @@
+// Calling a method on a raw pointer to an inferred type is future-incompatible. The lint is
+// emitted while the function is type-checked, which the incremental cache saves.
+pub fn is_null() -> bool {
+    let x = 0;
+    let y = &x as *const _;
+    y.is_null()
+}
@@ -0,0 +1,59 @@
+#!/usr/bin/env python
+
+# Checks the JSON that rustdoc writes for lib.rs.
+
+import json
+import sys
+
+with open(sys.argv[1]) as f:
+    krate = json.load(f)
+
+assert krate["format_version"] == 1, krate["format_version"]
+assert krate["name"] == "foobar", krate["name"]
+
+# Every item reachable from the root by path, which leaves out impls and their items.
+items = {}
+
+
+def collect(item, path):
+    if item["kind"] == "impl":
+        return
+    path = path + [item["name"]]
+    items["::".join(path)] = item
+    inner = item["inner"]
+    for key in ("items", "fields", "variants"):
+        for child in inner.get(key) or []:
+            collect(child, path)
+
+
+collect(krate["root"], [])
+ids = set(item["id"] for item in items.values())
+
+root = items["foobar"]
+assert root["kind"] == "mod" and root["inner"]["is_crate"], root
+assert root["docs"] == "The crate root, which links to [`Foo`].", root["docs"]
+
+foo = items["foobar::Foo"]
+assert foo["kind"] == "struct", foo["kind"]
+assert foo["inner"]["struct_type"] == "plain", foo["inner"]
+assert foo["inner"]["fields_stripped"], foo["inner"]
+assert [field["name"] for field in foo["inner"]["fields"]] == ["field"], foo["inner"]
+assert items["foobar::Foo::field"]["inner"]["type"] == "u32"
+
+function = items["foobar::module::function"]
+assert function["kind"] == "fn", function["kind"]
+assert function["inner"]["decl"]["output"] == "Foo", function["inner"]["decl"]
+assert "foobar::module::private_function" not in items
+assert "foobar::Trait::method" in items
+
+# Ids are unique, and look like `<crate number>:<index>`.
+assert len(ids) == len(items), (len(ids), len(items))
+for id in ids:
+    krate_num, index = id.split(":")
+    assert krate_num == "0", id
+    int(index)
+
+# Intra-doc links refer to the ids of the items they point to.
+assert root["links"] == {"`Foo`": foo["id"]}, root["links"]
+assert function["links"] == {"super::Foo": foo["id"]}, function["links"]
+assert foo["links"]["`Trait`"] == items["foobar::Trait"]["id"], foo["links"]
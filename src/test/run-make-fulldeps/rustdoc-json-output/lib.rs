@@ -0,0 +1,34 @@
+//! The crate root, which links to [`Foo`].
+
+/// A struct with a [`Foo::new`] constructor, implementing [`Trait`].
+pub struct Foo {
+    /// A public field.
+    pub field: u32,
+    private: (),
+}
+
+impl Foo {
+    /// Makes a `Foo`.
+    pub fn new() -> Foo {
+        Foo { field: 0, private: () }
+    }
+}
+
+pub trait Trait {
+    fn method(&self) -> Option<u8>;
+}
+
+impl Trait for Foo {
+    fn method(&self) -> Option<u8> {
+        None
+    }
+}
+
+pub mod module {
+    /// Returns a [`Foo`](super::Foo).
+    pub fn function() -> super::Foo {
+        super::Foo::new()
+    }
+
+    fn private_function() {}
+}
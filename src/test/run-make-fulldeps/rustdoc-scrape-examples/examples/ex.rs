@@ -0,0 +1,11 @@
+fn main() {
+    foobar::ok();
+
+    let foo = foobar::Foo;
+    foo.method();
+
+    let _ = 1;
+    let _ = 2;
+    let not_in_a_snippet = 3;
+    drop(not_in_a_snippet);
+}
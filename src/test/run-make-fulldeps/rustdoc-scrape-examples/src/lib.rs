@@ -0,0 +1,15 @@
+// ignore-tidy-linelength
+
+// @has foobar/fn.ok.html '//*[@class="docblock scraped-examples"]/h5' 'Examples found in repository'
+// @has - '//*[@class="scraped-example-title"]/a/@href' '../src/ex/examples/ex.rs.html#1-4'
+// @has - '//*[@class="scraped-example"]//a/@href' '../src/ex/examples/ex.rs.html#2'
+// @has src/ex/examples/ex.rs.html
+pub fn ok() {}
+
+pub struct Foo;
+
+impl Foo {
+    // @has foobar/struct.Foo.html '//*[@class="scraped-example-title"]/a/@href' '../src/ex/examples/ex.rs.html#3-7'
+    // @has - '//*[@class="scraped-example"]//a/@href' '../src/ex/examples/ex.rs.html#5'
+    pub fn method(&self) {}
+}
@@ -0,0 +1,53 @@
+#![feature(doc_cfg)]
+
+//! The crate links to [`Foo`] and to [the function](module::function).
+
+/// A struct, made with [`Foo::new`]. See also [`Bar`].
+///
+/// # Examples
+///
+/// ```
+/// # let hidden = ();
+/// let foo = foobar::Foo::new();
+/// ```
+pub struct Foo {
+    /// A field.
+    pub field: u32,
+}
+
+impl Foo {
+    /// Makes a [`Foo`].
+    pub fn new() -> Foo {
+        Foo { field: 0 }
+    }
+}
+
+/// An enum.
+pub enum Bar {
+    A,
+    B(u32),
+    C { x: u8 },
+}
+
+/// A trait.
+///
+/// # Using *it*, see <https://example.com>
+pub trait Trait {
+    fn required(&self);
+}
+
+impl Trait for Foo {
+    fn required(&self) {}
+}
+
+pub mod module {
+    /// A function for Unix, which uses [`Foo`](crate::Foo).
+    #[doc(cfg(unix))]
+    pub fn function() {}
+}
+
+/// Items whose names only differ in case.
+pub mod case {
+    pub struct Thing;
+    pub fn thing() {}
+}
@@ -0,0 +1,3 @@
+pub fn recorded() {
+    let x = 0;
+}
@@ -0,0 +1,8 @@
+const QUERY = 'parsing configuration';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'full_text', 'name': 'Parser' },
+        { 'path': 'full_text', 'name': 'load' },
+    ],
+};
@@ -0,0 +1,10 @@
+// compile-flags: -Z unstable-options --full-text-search
+
+/// Reads the configuration of the application from a file on disk.
+pub fn load() {}
+
+/// Writes a message to the terminal.
+pub fn print() {}
+
+/// Parses configuration files, and reports parsing errors.
+pub struct Parser;
@@ -0,0 +1,16 @@
+// build-pass (FIXME(62277): could be check-pass?)
+// compile-flags:--test -Z unstable-options --check-ignored-doctests --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+///
+/// ```ignore
+/// let answer: u32 = 42;
+/// ```
+///
+/// ```ignore
+/// let answer: u32 = "not a number";
+/// ```
+pub struct Foo;
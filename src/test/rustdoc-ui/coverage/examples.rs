@@ -0,0 +1,39 @@
+// compile-flags:-Z unstable-options --show-coverage
+// build-pass (FIXME(62277): could be check-pass?)
+
+//! The crate root has a code example.
+//!
+//! ```
+//! assert_eq!(examples::add(1, 2), 3);
+//! ```
+
+/// This function has a code example that runs as a doctest.
+///
+/// ```
+/// assert_eq!(examples::add(1, 2), 3);
+/// ```
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+/// This function's code example is ignored, so it isn't counted.
+///
+/// ```ignore
+/// sub(2, 1);
+/// ```
+pub fn sub(a: i32, b: i32) -> i32 {
+    a - b
+}
+
+/// This function only has a code block that isn't Rust.
+///
+/// ```text
+/// 2 * 3 = 6
+/// ```
+pub fn mul(a: i32, b: i32) -> i32 {
+    a * b
+}
+
+pub fn div(a: i32, b: i32) -> i32 {
+    a / b
+}
@@ -0,0 +1,14 @@
+// compile-flags:-Z unstable-options --show-coverage --output-format json
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// build-pass (FIXME(62277): could be check-pass?)
+
+//! Make sure to have some docs on your crate root
+
+/// This function is documented and has a code example.
+///
+/// ```
+/// json::documented();
+/// ```
+pub fn documented() {}
+
+pub fn undocumented() {}
@@ -0,0 +1,10 @@
+// compile-flags:-Z unstable-options --show-coverage --coverage-threshold 75
+
+//! Make sure to have some docs on your crate root
+
+/// This function is documented.
+pub fn documented() {}
+
+pub fn undocumented() {}
+
+pub fn also_undocumented() {}
@@ -0,0 +1,8 @@
+// build-pass (FIXME(62277): could be check-pass?)
+// compile-flags:--test -Z unstable-options --output-format json --test-args --list
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+pub struct Passes;
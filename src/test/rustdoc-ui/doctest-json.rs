@@ -0,0 +1,30 @@
+// build-pass (FIXME(62277): could be check-pass?)
+// compile-flags:--test -Z unstable-options --output-format json --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test: "(_time.):[0-9.e-]+" -> "$1:$$TIME"
+// normalize-stdout-test: "(diagnostics.:\[).*.code.:.(E[0-9]+).*(\],.file.:)" -> "$1$$DIAGNOSTICS $2$3"
+
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+pub struct Passes;
+
+/// ```should_panic
+/// assert_eq!(1 + 1, 3);
+/// ```
+pub struct Panics;
+
+/// ```no_run
+/// loop {}
+/// ```
+pub struct NoRun;
+
+/// ```ignore
+/// not rust
+/// ```
+pub struct Ignored;
+
+/// ```compile_fail,E0308
+/// let x: u32 = "not a number";
+/// ```
+pub struct CompileFails;
@@ -0,0 +1,12 @@
+// compile-flags:--test -Z unstable-options --merge-doctests
+// rustc-env:RUST_BACKTRACE=0
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+
+//! Panics in merged doctests are reported at their lines in this file.
+
+/// ```
+/// let answer = 41;
+/// panic!("oh no: {}", answer);
+/// ```
+pub struct Merged;
@@ -0,0 +1,39 @@
+// build-pass (FIXME(62277): could be check-pass?)
+// compile-flags:--test -Z unstable-options --merge-doctests --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+/// These doctests are compiled into a single binary:
+///
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+///
+/// ```should_panic
+/// panic!("merged doctests still run in their own process");
+/// ```
+///
+/// ```no_run
+/// loop {}
+/// ```
+///
+/// ```
+/// fn answer() -> u32 { 42 }
+/// assert_eq!(answer(), 42);
+/// Ok::<(), ()>(())
+/// ```
+pub struct Merged;
+
+/// These doctests are compiled on their own:
+///
+/// ```
+/// #![allow(dead_code)]
+/// ```
+///
+/// ```
+/// fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// let x: u32 = "not a number";
+/// ```
+pub struct Standalone;
@@ -0,0 +1,85 @@
+// compile-flags:-Z unstable-options --output-format json --edition 2018
+// build-pass (FIXME(62277): could be check-pass?)
+
+//! Make sure that all kinds of items can be written out as JSON.
+
+#![feature(trait_alias)]
+
+use std::fmt::Debug;
+
+/// A struct with a lifetime, a type parameter and a where clause.
+pub struct Plain<'a, T: Clone = u8> where T: Debug {
+    /// A documented field.
+    pub field: &'a T,
+    hidden: (),
+}
+
+pub struct Tuple(pub u32, ());
+
+pub struct Unit;
+
+pub enum Enum {
+    Unit,
+    Tuple(u8, String),
+    Struct { a: i32 },
+}
+
+pub union Union {
+    pub a: u32,
+    pub b: f32,
+}
+
+pub trait Trait<T>: Debug {
+    type Assoc: Clone;
+    const CONST: usize = 0;
+
+    fn required(&self, t: T) -> Self::Assoc;
+
+    fn provided(&self) {}
+}
+
+pub trait Alias = Clone + Debug;
+
+impl<'a, T: Clone + Debug> Plain<'a, T> {
+    pub const fn new(field: &'a T) -> Self {
+        Plain { field, hidden: () }
+    }
+
+    pub unsafe fn unsafe_method(&mut self, _: impl Iterator<Item = u8>) {}
+}
+
+#[derive(Debug)]
+pub struct Implementor;
+
+impl Trait<u8> for Implementor {
+    type Assoc = ();
+
+    fn required(&self, _: u8) {}
+}
+
+#[deprecated(since = "1.0.0", note = "use `Plain` instead")]
+pub type Typedef = Plain<'static, u16>;
+
+pub static mut STATIC: &str = "static";
+
+pub const CONST: [u8; 2] = [1, 2];
+
+pub async fn async_fn<T>() -> T where T: Default {
+    T::default()
+}
+
+pub mod module {
+    pub use super::Unit as Renamed;
+    pub use std::fmt::*;
+
+    pub(crate) fn crate_visible() {}
+}
+
+#[macro_export]
+macro_rules! mac {
+    () => {};
+}
+
+extern "C" {
+    pub fn foreign_fn(x: i32, ...) -> i32;
+}
@@ -0,0 +1,36 @@
+#![deny(untested_doc_code_examples)]
+
+/// ```ignore
+/// let x = foo();
+/// ```
+//~^^^ ERROR this Rust code block is marked `ignore`
+pub fn ignored() {}
+
+/// ```rustc
+/// let x = 5;
+/// ```
+//~^^^ ERROR code block tagged with unknown language `rustc` looks like Rust
+pub fn unknown_language() {}
+
+/// ```should-panic
+/// panic!("oh no");
+/// ```
+//~^^^ ERROR unknown attribute `should-panic` on code block
+pub fn misspelled_attribute() {}
+
+/// ```text
+/// let x = 5;
+/// ```
+///
+/// ```ignore
+/// some pseudo-code
+/// ```
+///
+/// ```sh
+/// cargo run
+/// ```
+///
+/// ```
+/// let x = 5;
+/// ```
+pub fn tested_or_not_rust() {}
@@ -0,0 +1,37 @@
+// ignore-tidy-linelength
+// compile-flags: -Z unstable-options --generate-link-to-definition
+
+#![crate_name = "foo"]
+
+// @has src/foo/link-to-definition.rs.html
+
+pub struct Bar;
+
+struct Private;
+
+impl Bar {
+    pub const fn new() -> Bar {
+        Bar
+    }
+
+    pub fn name(&self) -> String {
+        String::new()
+    }
+}
+
+// @has - '//a[@href="../../foo/struct.Bar.html"]' 'Bar'
+// @has - '//a[@href="../../foo/struct.Bar.html#method.new"]' 'new'
+// @has - '//a[@href="../../src/foo/link-to-definition.rs.html#10"]' 'Private'
+// @has - '//a[@href="https://doc.rust-lang.org/nightly/alloc/string/struct.String.html"]' 'String'
+pub const BAR: Bar = Bar::new();
+
+fn private(_: Private) {}
+
+// Function bodies are skipped, so they don't need to compile, and the names in them aren't linked.
+// @!has - '//a' 'OnlyInBody'
+pub fn bar_name(_: &Bar) -> String {
+    OnlyInBody;
+    this_function_does_not_exist()
+}
+
+struct OnlyInBody;
@@ -0,0 +1,5 @@
+// A module whose lints `lint-config.json` relaxes, included by `lint-config.rs`.
+
+pub struct lowercase;
+
+pub fn CamelCase() {}
@@ -0,0 +1,37 @@
+// check-pass
+
+#![feature(lint_reasons)]
+#![warn(unused)]
+
+#[expect(unused_variables)]
+fn fulfilled() {
+    let x = 5;
+}
+
+#[expect(unused_mut, reason = "`x` used to be changed here")]
+//~^ WARN this lint expectation is unfulfilled
+//~| NOTE no `unused_mut` lint was emitted in the scope of this attribute
+//~| NOTE `x` used to be changed here
+//~| NOTE `#[warn(unfulfilled_lint_expectations)]` on by default
+fn unfulfilled() -> i32 {
+    let x = 5;
+    x
+}
+
+#[expect(unused)]
+fn fulfilled_by_group() {
+    let x = 5;
+}
+
+#[expect(unfulfilled_lint_expectations)]
+mod nested {
+    #[expect(unused_variables)]
+    pub fn unfulfilled() {}
+}
+
+fn main() {
+    fulfilled();
+    unfulfilled();
+    fulfilled_by_group();
+    nested::unfulfilled();
+}
@@ -0,0 +1,13 @@
+// Future-incompatible lints are reported at the end of compilation with
+// `-Z emit-future-incompat-report`, even when `--cap-lints` silences them.
+
+// compile-flags: --error-format=json --cap-lints=allow -Z emit-future-incompat-report
+// check-pass
+
+#![deny(anonymous_parameters)]
+
+pub trait Foo {
+    fn foo(u8);
+}
+
+fn main() {}
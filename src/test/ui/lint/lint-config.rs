@@ -0,0 +1,20 @@
+// Lint levels from a `--lint-config` file, with an override for the module in
+// `auxiliary/lint-config-generated.rs`.
+
+// compile-flags: -Z unstable-options --lint-config {{src-base}}/lint/lint-config.json
+
+#![allow(dead_code)]
+
+#[path = "auxiliary/lint-config-generated.rs"]
+mod generated;
+
+mod inline {
+    pub struct lowercase; //~ WARN type `lowercase` should have an upper camel case name
+}
+
+#[allow(non_snake_case)]
+fn CamelCase() {}
+
+fn main() {
+    let _Unused = 0; //~ ERROR variable `_Unused` should have a snake case name
+}
@@ -0,0 +1,15 @@
+// Test that `-Z warnings-baseline` also suppresses the diagnostics of denied
+// lints, which then don't fail the build.
+
+// check-pass
+// compile-flags: -Z warnings-baseline={{src-base}}/lint/warnings-baseline-deny.json
+
+#![deny(unused_variables)]
+
+fn recorded() {
+    let x = 0;
+}
+
+fn main() {
+    recorded();
+}
@@ -0,0 +1,20 @@
+// Test that `-Z warnings-baseline` suppresses the lint diagnostics that are in
+// the baseline, and reports the new ones and the entries that no longer occur.
+
+// check-pass
+// compile-flags: -Z warnings-baseline={{src-base}}/lint/warnings-baseline.json
+
+#![warn(unused_variables)]
+
+fn new() {
+    let y = 0; //~ WARN unused variable: `y`
+}
+
+fn recorded() {
+    let x = 0;
+}
+
+fn main() {
+    new();
+    recorded();
+}
@@ -0,0 +1,12 @@
+// Test the output of `--error-format=sarif`.
+
+// compile-flags: --error-format=sarif -Z unstable-options
+
+#![warn(unused_variables)]
+
+fn main() {
+    let x = 0;
+    let s = String::new();
+    drop(s);
+    drop(s);
+}
@@ -0,0 +1,14 @@
+// compile-flags: --test
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout(0)] //~ ERROR argument must be of the form
+fn zero() {}
+
+#[test]
+#[test_timeout("10")] //~ ERROR argument must be of the form
+fn string() {}
+
+#[test]
+#[test_timeout(1, 2)] //~ ERROR argument must be of the form
+fn two() {}
@@ -0,0 +1,10 @@
+// run-pass
+// ignore-wasm32-bare compiled with panic=abort by default
+// compile-flags: --test
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout(60)]
+fn test1() {
+    assert!(true);
+}
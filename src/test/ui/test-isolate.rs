@@ -0,0 +1,41 @@
+// compile-flags: --test
+// run-flags: --test-threads=1 -Zunstable-options --isolate
+// run-fail
+// check-run-results
+
+// ignore-wasm no panic or subprocess support
+// ignore-emscripten no panic or subprocess support
+
+#![cfg(test)]
+
+use std::io::Write;
+
+#[test]
+fn it_works() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[test]
+#[should_panic]
+fn it_panics() {
+    assert_eq!(1 + 1, 4);
+}
+
+#[test]
+fn it_fails() {
+    println!("hello, world");
+    writeln!(std::io::stdout(), "testing123").unwrap();
+    writeln!(std::io::stderr(), "testing321").unwrap();
+    assert_eq!(1 + 1, 5);
+}
+
+#[test]
+fn it_exits() {
+    std::process::exit(123);
+}
+
+#[test]
+fn it_catches_panics() {
+    assert!(std::panic::catch_unwind(|| panic!("caught")).is_err());
+    assert!(std::thread::spawn(|| panic!("joined")).join().is_err());
+}
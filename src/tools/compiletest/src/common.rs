@@ -288,6 +288,18 @@ pub struct Config {
     /// mode describing what file the actual ui output will be compared to
     pub compare_mode: Option<CompareMode>,
 
+    /// The codegen backend to compile the tests with, passed to rustc as
+    /// `-Zcodegen-backend`. Either the name of a backend built with the compiler
+    /// or the path to a backend dylib. `None` uses the target's default backend.
+    pub codegen_backend: Option<String>,
+
+    /// Tests, relative to `src_base`, which are known to fail with
+    /// `codegen_backend`. These are run as if they were annotated `should-fail`.
+    pub backend_expected_failures: Vec<PathBuf>,
+
+    /// Where to write a JSON report of the tests that were run, see `report.rs`.
+    pub report: Option<PathBuf>,
+
     /// If true, this will generate a coverage file with UI test files that run `MachineApplicable`
     /// diagnostics but are missing `run-rustfix` annotations. The generated coverage file is
     /// created in `/<build_base>/rustfix_missing_coverage.txt`
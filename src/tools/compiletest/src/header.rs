@@ -138,6 +138,11 @@ impl EarlyProps {
                     props.ignore = Ignore::Ignore;
                 }
 
+                if config.codegen_backend_name() != "llvm" &&
+                   config.parse_needs_llvm_backend(ln) {
+                    props.ignore = Ignore::Ignore;
+                }
+
                 if config.target == "wasm32-unknown-unknown" && config.parse_check_run_results(ln) {
                     props.ignore = Ignore::Ignore;
                 }
@@ -834,6 +839,24 @@ impl Config {
         self.parse_name_directive(line, "needs-sanitizer-support")
     }
 
+    fn parse_needs_llvm_backend(&self, line: &str) -> bool {
+        self.parse_name_directive(line, "needs-llvm-backend")
+    }
+
+    /// The name of the codegen backend the tests are compiled with, e.g. `llvm`
+    /// or `cranelift`.
+    pub fn codegen_backend_name(&self) -> &str {
+        self.codegen_backend.as_ref().map_or("llvm", |b| util::codegen_backend_name(b))
+    }
+
+    /// Whether `testfile` is listed in `--backend-expected-failures`.
+    pub fn is_expected_backend_failure(&self, testfile: &Path) -> bool {
+        match testfile.strip_prefix(&self.src_base) {
+            Ok(relative) => self.backend_expected_failures.iter().any(|p| p == relative),
+            Err(_) => false,
+        }
+    }
+
     /// Parses a name-value directive which contains config-specific information, e.g., `ignore-x86`
     /// or `normalize-stderr-32bit`.
     fn parse_cfg_name_directive(&self, line: &str, prefix: &str) -> ParsedNameDirective {
@@ -849,6 +872,8 @@ impl Config {
                 name == util::get_arch(&self.target) ||             // architecture
                 name == util::get_pointer_width(&self.target) ||    // pointer width
                 name == self.stage_id.split('-').next().unwrap() || // stage
+                (name.starts_with("backend-") &&                    // codegen backend
+                    name["backend-".len()..] == *self.codegen_backend_name()) ||
                 (self.target != self.host && name == "cross-compile") ||
                 match self.compare_mode {
                     Some(CompareMode::Nll) => name == "compare-mode-nll",
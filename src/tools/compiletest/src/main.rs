@@ -15,7 +15,7 @@ use std::ffi::OsString;
 use std::fs;
 use std::io::{self, ErrorKind};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{self, Command};
 use std::time::SystemTime;
 use test::ColorConfig;
 use crate::util::logv;
@@ -35,6 +35,7 @@ pub mod header;
 mod json;
 mod raise_fd_limit;
 mod read2;
+mod report;
 pub mod runtest;
 pub mod util;
 
@@ -242,6 +243,25 @@ pub fn parse_config(args: Vec<String>) -> Config {
             "mode describing what file the actual ui output will be compared to",
             "COMPARE MODE",
         )
+        .optopt(
+            "",
+            "codegen-backend",
+            "the codegen backend to compile the tests with",
+            "NAME | PATH",
+        )
+        .optopt(
+            "",
+            "backend-expected-failures",
+            "file listing the tests that are known to fail with --codegen-backend",
+            "PATH",
+        )
+        .optopt(
+            "",
+            "report",
+            "write a JSON report of the outcome, wall time, compiler command line \
+             and output diffs of every test to PATH",
+            "PATH",
+        )
         .optflag(
             "",
             "rustfix-coverage",
@@ -302,6 +322,14 @@ pub fn parse_config(args: Vec<String>) -> Config {
         ),
     };
 
+    let backend_expected_failures = match matches.opt_str("backend-expected-failures") {
+        Some(path) => read_expected_failures(Path::new(&path)).unwrap_or_else(|e| {
+            eprintln!("error: couldn't read --backend-expected-failures file `{}`: {}", path, e);
+            process::exit(1)
+        }),
+        None => Vec::new(),
+    };
+
     let src_base = opt_path(matches, "src-base");
     let run_ignored = matches.opt_present("ignored");
     Config {
@@ -358,6 +386,9 @@ pub fn parse_config(args: Vec<String>) -> Config {
         color,
         remote_test_client: matches.opt_str("remote-test-client").map(PathBuf::from),
         compare_mode: matches.opt_str("compare-mode").map(CompareMode::parse),
+        codegen_backend: matches.opt_str("codegen-backend"),
+        backend_expected_failures,
+        report: matches.opt_str("report").map(|s| make_absolute(PathBuf::from(s))),
         rustfix_coverage: matches.opt_present("rustfix-coverage"),
 
         cc: matches.opt_str("cc").unwrap(),
@@ -422,6 +453,7 @@ pub fn log_config(config: &Config) {
         c,
         format!("adb_device_status: {}", config.adb_device_status),
     );
+    logv(c, format!("codegen_backend: {}", opt_str(&config.codegen_backend)));
     logv(c, format!("ar: {}", config.ar));
     logv(c, format!("linker: {:?}", config.linker));
     logv(c, format!("verbose: {}", config.verbose));
@@ -429,6 +461,18 @@ pub fn log_config(config: &Config) {
     logv(c, "\n".to_string());
 }
 
+/// Reads a list of test files, one per line and relative to `--src-base`.
+/// Empty lines and lines starting with `#` are skipped.
+fn read_expected_failures(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(PathBuf::from)
+        .collect())
+}
+
 pub fn opt_str(maybestr: &Option<String>) -> &str {
     match *maybestr {
         None => "(none)",
@@ -532,6 +576,11 @@ pub fn run_tests(config: &Config) {
     env::set_var("TARGET", &config.target);
 
     let res = test::run_tests_console(&opts, tests);
+    if let Some(ref path) = config.report {
+        if let Err(e) = report::write(path) {
+            panic!("failed to write report to {}: {}", path.display(), e);
+        }
+    }
     match res {
         Ok(true) => {}
         Ok(false) => panic!("Some tests failed"),
@@ -665,9 +714,13 @@ pub fn make_test(config: &Config, testpaths: &TestPaths) -> Vec<test::TestDescAn
     // The `should-fail` annotation doesn't apply to pretty tests,
     // since we run the pretty printer across all tests by default.
     // If desired, we could add a `should-fail-pretty` annotation.
+    // Tests that are known to fail with an alternative codegen backend are
+    // treated the same way.
+    let should_fail = early_props.should_fail
+        || config.is_expected_backend_failure(&testpaths.file);
     let should_panic = match config.mode {
         Pretty => test::ShouldPanic::No,
-        _ => if early_props.should_fail {
+        _ => if should_fail {
             test::ShouldPanic::Yes
         } else {
             test::ShouldPanic::No
@@ -704,7 +757,13 @@ pub fn make_test(config: &Config, testpaths: &TestPaths) -> Vec<test::TestDescAn
                     should_panic,
                     allow_fail: false,
                 },
-                testfn: make_test_closure(config, early_props.ignore, testpaths, revision),
+                testfn: make_test_closure(
+                    config,
+                    early_props.ignore,
+                    testpaths,
+                    revision,
+                    should_panic == test::ShouldPanic::Yes,
+                ),
             }
         })
         .collect()
@@ -838,6 +897,7 @@ fn make_test_closure(
     ignore: Ignore,
     testpaths: &TestPaths,
     revision: Option<&String>,
+    should_fail: bool,
 ) -> test::TestFn {
     let mut config = config.clone();
     if config.mode == DebugInfoGdbLldb {
@@ -853,7 +913,14 @@ fn make_test_closure(
     let testpaths = testpaths.clone();
     let revision = revision.cloned();
     test::DynTestFn(Box::new(move || {
-        runtest::run(config, &testpaths, revision.as_ref().map(|s| s.as_str()))
+        let revision = revision.as_ref().map(|s| s.as_str());
+        if config.report.is_some() {
+            report::run_recorded(&config, &testpaths, revision, should_fail, || {
+                runtest::run(config.clone(), &testpaths, revision)
+            })
+        } else {
+            runtest::run(config, &testpaths, revision)
+        }
     }))
 }
 
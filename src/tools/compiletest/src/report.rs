@@ -0,0 +1,152 @@
+//! The `--report` file: a machine-readable record of every test that was run,
+//! for tools that triage large numbers of failures.
+//!
+//! Each test runs on its own thread, so the details that only `runtest` knows
+//! about (the compiler command line, the output diffs) are collected in a
+//! thread-local record while the test runs, and moved into a global list once
+//! it finishes.
+
+use crate::common::{Config, TestPaths};
+use serde::Serialize;
+use serde_json;
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    Passed,
+    Failed,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OutputDiff {
+    /// The kind of output that differed, e.g. `stderr` or `run.stdout`.
+    pub kind: String,
+    /// The file the normalized diff was written to.
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TestRecord {
+    /// The test file, relative to `--src-base`.
+    pub file: PathBuf,
+    pub revision: Option<String>,
+    pub mode: String,
+    /// The outcome as reported by the test runner, i.e. a `should-fail` test
+    /// that failed has `Passed`.
+    pub outcome: Outcome,
+    pub should_fail: bool,
+    pub wall_time_secs: f64,
+    /// The first compiler invocation for the test file itself, auxiliary
+    /// builds excluded.
+    pub rustc_command: Option<String>,
+    pub diffs: Vec<OutputDiff>,
+}
+
+#[derive(Default)]
+struct RunningTest {
+    rustc_command: Option<String>,
+    diffs: Vec<OutputDiff>,
+}
+
+thread_local! {
+    static RUNNING_TEST: RefCell<Option<RunningTest>> = RefCell::new(None);
+}
+
+lazy_static! {
+    static ref RECORDS: Mutex<Vec<TestRecord>> = Mutex::new(Vec::new());
+}
+
+/// Runs `f` as the test `testpaths`/`revision`, and adds a record of it to the
+/// report. Panics from `f` are propagated to the test runner.
+pub fn run_recorded<F: FnOnce()>(
+    config: &Config,
+    testpaths: &TestPaths,
+    revision: Option<&str>,
+    should_fail: bool,
+    f: F,
+) {
+    let file = testpaths.file.strip_prefix(&config.src_base).unwrap_or(&testpaths.file);
+    let (record, result) =
+        record_test(file.to_path_buf(), revision, config.mode.to_string(), should_fail, f);
+    RECORDS.lock().unwrap().push(record);
+
+    if let Err(e) = result {
+        panic::resume_unwind(e);
+    }
+}
+
+/// Runs `f` as a test and returns its record, along with the panic of `f` if
+/// it panicked.
+fn record_test<F: FnOnce()>(
+    file: PathBuf,
+    revision: Option<&str>,
+    mode: String,
+    should_fail: bool,
+    f: F,
+) -> (TestRecord, thread::Result<()>) {
+    RUNNING_TEST.with(|t| *t.borrow_mut() = Some(RunningTest::default()));
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    let wall_time = start.elapsed();
+    let running = RUNNING_TEST.with(|t| t.borrow_mut().take()).unwrap_or_default();
+
+    let outcome = if result.is_err() == should_fail { Outcome::Passed } else { Outcome::Failed };
+    let record = TestRecord {
+        file,
+        revision: revision.map(str::to_owned),
+        mode,
+        outcome,
+        should_fail,
+        wall_time_secs: wall_time.as_secs_f64(),
+        rustc_command: running.rustc_command,
+        diffs: running.diffs,
+    };
+    (record, result)
+}
+
+/// Records the compiler command line of the running test, unless one was
+/// already recorded.
+pub fn record_rustc_command(cmdline: &str) {
+    RUNNING_TEST.with(|t| {
+        if let Some(ref mut t) = *t.borrow_mut() {
+            if t.rustc_command.is_none() {
+                t.rustc_command = Some(cmdline.to_owned());
+            }
+        }
+    });
+}
+
+/// Records that the `kind` output of the running test differed from the
+/// expected output, with the diff saved to `path`.
+pub fn record_diff(kind: &str, path: &Path) {
+    RUNNING_TEST.with(|t| {
+        if let Some(ref mut t) = *t.borrow_mut() {
+            t.diffs.push(OutputDiff { kind: kind.to_owned(), path: path.to_path_buf() });
+        }
+    });
+}
+
+/// Writes the records of all tests that have run so far to `path`, sorted by
+/// test file and revision.
+pub fn write(path: &Path) -> io::Result<()> {
+    let records = RECORDS.lock().unwrap().clone();
+    fs::write(path, to_json(records)?)
+}
+
+fn to_json(mut records: Vec<TestRecord>) -> serde_json::Result<String> {
+    records.sort_by(|a, b| (&a.file, &a.revision).cmp(&(&b.file, &b.revision)));
+    serde_json::to_string_pretty(&records)
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+fn record(file: &str, revision: Option<&str>, outcome: Outcome) -> TestRecord {
+    TestRecord {
+        file: PathBuf::from(file),
+        revision: revision.map(str::to_owned),
+        mode: "ui".to_owned(),
+        outcome,
+        should_fail: false,
+        wall_time_secs: 0.0,
+        rustc_command: None,
+        diffs: vec![],
+    }
+}
+
+#[test]
+fn test_record_collects_command_and_diffs() {
+    let (record, result) = record_test(PathBuf::from("a.rs"), None, "ui".to_owned(), false, || {
+        record_rustc_command("rustc a.rs");
+        record_rustc_command("rustc aux.rs");
+        record_diff("stderr", Path::new("a.stderr.diff"));
+    });
+    assert!(result.is_ok());
+    assert_eq!(record.outcome, Outcome::Passed);
+    assert_eq!(record.rustc_command, Some("rustc a.rs".to_owned()));
+    assert_eq!(record.diffs.len(), 1);
+    assert_eq!(record.diffs[0].kind, "stderr");
+    assert_eq!(record.diffs[0].path, PathBuf::from("a.stderr.diff"));
+
+    // Nothing is recorded outside of a test.
+    record_rustc_command("rustc b.rs");
+    let (record, _) = record_test(PathBuf::from("b.rs"), None, "ui".to_owned(), false, || {});
+    assert_eq!(record.rustc_command, None);
+}
+
+#[test]
+fn test_record_outcome() {
+    let outcome = |should_fail, panics| {
+        let (record, result) =
+            record_test(PathBuf::from("a.rs"), Some("rev"), "ui".to_owned(), should_fail, || {
+                if panics {
+                    panic!("test failed");
+                }
+            });
+        assert_eq!(result.is_err(), panics);
+        assert_eq!(record.revision, Some("rev".to_owned()));
+        record.outcome
+    };
+    assert_eq!(outcome(false, false), Outcome::Passed);
+    assert_eq!(outcome(false, true), Outcome::Failed);
+    assert_eq!(outcome(true, true), Outcome::Passed);
+    assert_eq!(outcome(true, false), Outcome::Failed);
+}
+
+#[test]
+fn test_to_json() {
+    let json = to_json(vec![
+        record("b.rs", None, Outcome::Passed),
+        record("a.rs", Some("y"), Outcome::Failed),
+        record("a.rs", None, Outcome::Passed),
+    ]).unwrap();
+    let records: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let keys = records.as_array().unwrap().iter().map(|r| {
+        (r["file"].as_str().unwrap(), r["revision"].as_str(), r["outcome"].as_str().unwrap())
+    }).collect::<Vec<_>>();
+    assert_eq!(keys, vec![
+        ("a.rs", None, "passed"),
+        ("a.rs", Some("y"), "failed"),
+        ("b.rs", None, "passed"),
+    ]);
+    assert_eq!(records[0]["should_fail"], false);
+    assert_eq!(records[0]["diffs"], serde_json::json!([]));
+}
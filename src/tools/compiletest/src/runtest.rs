@@ -13,6 +13,7 @@ use diff;
 use crate::errors::{self, Error, ErrorKind};
 use crate::header::TestProps;
 use crate::json;
+use crate::report;
 use regex::{Captures, Regex};
 use rustfix::{apply_suggestions, get_suggestions_from_json, Filter};
 use crate::util::{logv, PathBufExt};
@@ -268,6 +269,8 @@ pub fn compute_stamp_hash(config: &Config) -> String {
         config.force_pass_mode.hash(&mut hash);
     }
 
+    config.codegen_backend.hash(&mut hash);
+
     format!("{:x}", hash.finish())
 }
 
@@ -1855,12 +1858,14 @@ impl<'test> TestCx<'test> {
             .iter()
             .fold(&mut rustc, |rustc, v| rustc.env_remove(v));
         rustc.envs(self.props.rustc_env.clone());
-        self.compose_and_run(
+        let proc_res = self.compose_and_run(
             rustc,
             self.config.compile_lib_path.to_str().unwrap(),
             Some(aux_dir.to_str().unwrap()),
             input,
-        )
+        );
+        report::record_rustc_command(&proc_res.cmdline);
+        proc_res
     }
 
     fn compose_and_run(
@@ -2061,6 +2066,12 @@ impl<'test> TestCx<'test> {
             None => {}
         }
 
+        if !is_rustdoc {
+            if let Some(ref backend) = self.config.codegen_backend {
+                rustc.arg(format!("-Zcodegen-backend={}", backend));
+            }
+        }
+
         if self.props.force_host {
             self.maybe_add_external_args(&mut rustc,
                                          self.split_maybe_args(&self.config.host_rustcflags));
@@ -3517,41 +3528,55 @@ impl<'test> TestCx<'test> {
             return 0;
         }
 
+        let mode = self.config.compare_mode.as_ref().map_or("", |m| m.to_str());
+        let output_file = self
+            .output_base_name()
+            .with_extra_extension(self.revision.unwrap_or(""))
+            .with_extra_extension(mode)
+            .with_extra_extension(kind);
+
         if !self.config.bless {
-            if expected.is_empty() {
-                println!("normalized {}:\n{}\n", kind, actual);
+            let diff = if expected.is_empty() {
+                format!("normalized {}:\n{}\n\n", kind, actual)
             } else {
-                println!("diff of {}:\n", kind);
+                let mut diff = format!("diff of {}:\n\n", kind);
                 let diff_results = make_diff(expected, actual, 3);
                 for result in diff_results {
                     let mut line_number = result.line_number;
                     for line in result.lines {
                         match line {
                             DiffLine::Expected(e) => {
-                                println!("-\t{}", e);
+                                diff.push_str(&format!("-\t{}\n", e));
                                 line_number += 1;
                             }
                             DiffLine::Context(c) => {
-                                println!("{}\t{}", line_number, c);
+                                diff.push_str(&format!("{}\t{}\n", line_number, c));
                                 line_number += 1;
                             }
                             DiffLine::Resulting(r) => {
-                                println!("+\t{}", r);
+                                diff.push_str(&format!("+\t{}\n", r));
                             }
                         }
                     }
-                    println!();
+                    diff.push('\n');
                 }
+                diff
+            };
+            print!("{}", diff);
+
+            if self.config.report.is_some() {
+                let diff_file = output_file.with_extra_extension("diff");
+                if let Err(err) = fs::write(&diff_file, &diff) {
+                    self.fatal(&format!(
+                        "failed to write diff to `{}`: {}",
+                        diff_file.display(),
+                        err,
+                    ));
+                }
+                report::record_diff(kind, &diff_file);
             }
         }
 
-        let mode = self.config.compare_mode.as_ref().map_or("", |m| m.to_str());
-        let output_file = self
-            .output_base_name()
-            .with_extra_extension(self.revision.unwrap_or(""))
-            .with_extra_extension(mode)
-            .with_extra_extension(kind);
-
         let mut files = vec![output_file];
         if self.config.bless {
             files.push(expected_output_path(
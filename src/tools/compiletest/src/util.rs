@@ -1,6 +1,6 @@
 use std::ffi::OsStr;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::common::Config;
 
 use log::*;
@@ -115,6 +115,23 @@ pub fn matches_env(triple: &str, name: &str) -> bool {
     }
 }
 
+/// Returns the name of the codegen backend given to `--codegen-backend`, which
+/// is either already a name such as `llvm`, or a path to a backend dylib such
+/// as `path/to/librustc_codegen_cranelift.so`.
+pub fn codegen_backend_name(backend: &str) -> &str {
+    // rustc treats anything containing a `.` as a path to a dylib.
+    if !backend.contains('.') {
+        return backend;
+    }
+    let stem = Path::new(backend).file_stem().and_then(|s| s.to_str()).unwrap_or(backend);
+    let stem = if stem.starts_with("lib") { &stem[3..] } else { stem };
+    if stem.starts_with("rustc_codegen_") {
+        &stem["rustc_codegen_".len()..]
+    } else {
+        stem
+    }
+}
+
 pub fn get_pointer_width(triple: &str) -> &'static str {
     if (triple.contains("64") && !triple.ends_with("gnux32")) || triple.starts_with("s390x") {
         "64bit"
@@ -30,3 +30,11 @@ fn test_matches_os() {
     assert!(matches_os("nvptx64-nvidia-cuda", "cuda"));
     assert!(matches_os("x86_64-fortanix-unknown-sgx", "sgx"));
 }
+
+#[test]
+fn test_codegen_backend_name() {
+    assert_eq!("llvm", codegen_backend_name("llvm"));
+    assert_eq!("cranelift", codegen_backend_name("/a/b/librustc_codegen_cranelift.so"));
+    assert_eq!("cranelift", codegen_backend_name("rustc_codegen_cranelift.dll"));
+    assert_eq!("my_backend", codegen_backend_name("./libmy_backend.dylib"));
+}
@@ -264,7 +264,7 @@ function main(argv) {
     searchIndex = loadContent(searchIndex.join("\n") + '\nexports.searchIndex = searchIndex;');
     finalJS = "";
 
-    var arraysToLoad = ["itemTypes"];
+    var arraysToLoad = ["itemTypes", "STOP_WORDS"];
     var variablesToLoad = ["MAX_LEV_DISTANCE", "MAX_RESULTS",
                            "GENERICS_DATA", "NAME", "INPUTS_DATA", "OUTPUT_DATA",
                            "TY_PRIMITIVE", "TY_KEYWORD",
@@ -273,7 +273,7 @@ function main(argv) {
     // execQuery last parameter is built in buildIndex.
     // buildIndex requires the hashmap from search-index.
     var functionsToLoad = ["buildHrefAndPath", "pathSplitter", "levenshtein", "validateResult",
-                           "getQuery", "buildIndex", "execQuery", "execSearch"];
+                           "getQuery", "buildIndex", "execQuery", "execSearch", "stem"];
 
     finalJS += 'window = { "currentCrate": "std" };\n';
     finalJS += 'var rootPath = "../";\n';